@@ -0,0 +1,205 @@
+//! Measures the cost of running the CPU with the disassembly of the instructions enabled and
+//! disabled, with and without taking the snapshots of the instructions, and with its cartridge
+//! boxed and held as is.
+
+use std::io::Cursor;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use tinfo::cartridge::{Cartridge, CartridgeError};
+use tinfo::cpu::{Cpu, CycleResult};
+use tinfo::rom::ines::InesFile;
+
+/// The number of instructions run on every iteration of the benchmarks.
+const INSTRUCTIONS: usize = 10_000;
+
+/// The size of the header of an iNES file.
+const INES_HEADER_SIZE: usize = 16;
+
+/// The size of a bank of PRG ROM, the only one of the NROM cartridge of the benchmarks.
+const PRG_ROM_BANK_SIZE: usize = 0x4000;
+
+/// A loop going through the addressing modes whose disassembly reads the bus, after storing the
+/// pointer of its indirect jump back to `$8008`.
+const PROGRAM: [u8; 22] = [
+    // LDA #$08
+    0xA9, 0x08,
+
+    // STA $40
+    0x85, 0x40,
+
+    // LDA #$80
+    0xA9, 0x80,
+
+    // STA $41
+    0x85, 0x41,
+
+    // LAX ($10,X)
+    0xA3, 0x10,
+
+    // STA $0200,Y
+    0x99, 0x00, 0x02,
+
+    // ADC ($20),Y
+    0x71, 0x20,
+
+    // INC $30
+    0xE6, 0x30,
+
+    // LDY $30
+    0xA4, 0x30,
+
+    // JMP ($0040)
+    0x6C, 0x40, 0x00,
+];
+
+/// A cartridge mapping [PROGRAM] at the start of its PRG ROM and pointing the reset vector to it.
+struct LoopCartridge;
+
+impl Cartridge for LoopCartridge {
+    unsafe fn read(&self, address: u16) -> Result<u8, CartridgeError> {
+        Ok(match address {
+            0xFFFC => 0x00,
+            0xFFFD => 0x80,
+            _ => PROGRAM.get(address.wrapping_sub(0x8000) as usize).copied().unwrap_or(0xEA),
+        })
+    }
+
+    unsafe fn write(&mut self, _address: u16, _value: u8) -> Result<(), CartridgeError> {
+        Ok(())
+    }
+}
+
+/// Make a [Cpu] running [PROGRAM] with the given tracing.
+fn loop_cpu(trace: bool) -> Cpu {
+    let mut cpu = Cpu::new(Box::new(LoopCartridge)).unwrap();
+    cpu.set_trace(trace);
+
+    cpu
+}
+
+/// Make an iNES file of a NROM cartridge with [PROGRAM] at the start of its PRG ROM and the reset
+/// vector pointing to it.
+fn nrom_ines_file() -> Vec<u8> {
+    let mut file = vec![0xEA; INES_HEADER_SIZE + PRG_ROM_BANK_SIZE];
+    file[..INES_HEADER_SIZE].fill(0x00);
+    file[..5].copy_from_slice(b"NES\x1A\x01");
+
+    file[INES_HEADER_SIZE..INES_HEADER_SIZE + PROGRAM.len()].copy_from_slice(&PROGRAM);
+
+    // The bank is mirrored, so $BFFC is seen at $FFFC
+    let reset_vector = INES_HEADER_SIZE + PRG_ROM_BANK_SIZE - 4;
+    file[reset_vector..reset_vector + 2].copy_from_slice(&[0x00, 0x80]);
+
+    file
+}
+
+/// Run [INSTRUCTIONS] on the given [Cpu].
+fn run_instructions<C: Cartridge>(mut cpu: Cpu<C>) {
+    for _ in 0..INSTRUCTIONS {
+        black_box(cpu.step().unwrap());
+    }
+}
+
+/// Compare running [INSTRUCTIONS] with and without tracing.
+fn bench_trace(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("step");
+
+    for (name, trace) in [("traced", true), ("untraced", false)] {
+        group.bench_function(name, |bencher| {
+            bencher.iter_batched(
+                || loop_cpu(trace),
+                run_instructions,
+                BatchSize::LargeInput,
+            );
+        });
+    }
+
+    group.finish();
+}
+
+/// Compare running [INSTRUCTIONS] untraced cycle by cycle, with and without taking the snapshot of
+/// every instruction.
+fn bench_snapshot(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("cycle");
+
+    group.bench_function("snapshot", |bencher| {
+        bencher.iter_batched(
+            || loop_cpu(false),
+            |mut cpu| {
+                let mut instructions = 0;
+
+                while instructions < INSTRUCTIONS {
+                    if let Some(snapshot) = cpu.cycle_with_snapshot().unwrap() {
+                        black_box(snapshot);
+                        instructions += 1;
+                    }
+                }
+            },
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.bench_function("no_snapshot", |bencher| {
+        bencher.iter_batched(
+            || loop_cpu(false),
+            |mut cpu| {
+                let mut instructions = 0;
+
+                while instructions < INSTRUCTIONS {
+                    if black_box(cpu.cycle().unwrap()) == CycleResult::Dispatched {
+                        instructions += 1;
+                    }
+                }
+            },
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.finish();
+}
+
+/// Compare running [INSTRUCTIONS] untraced on a NROM cartridge boxed, as loaded by
+/// [InesFile::from_read], and held as is.
+fn bench_dispatch(criterion: &mut Criterion) {
+    let file = nrom_ines_file();
+    let mut group = criterion.benchmark_group("nrom");
+
+    group.bench_function("boxed", |bencher| {
+        bencher.iter_batched(
+            || {
+                let cartridge = InesFile::from_read(&mut Cursor::new(&file)).unwrap();
+                let mut cpu = Cpu::new(cartridge).unwrap();
+                cpu.set_trace(false);
+
+                cpu
+            },
+            run_instructions,
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.bench_function("generic", |bencher| {
+        bencher.iter_batched(
+            || {
+                let cartridge = InesFile::nrom_from_read(&mut Cursor::new(&file)).unwrap();
+                let mut cpu = Cpu::from_cartridge(cartridge).unwrap();
+                cpu.set_trace(false);
+
+                cpu
+            },
+            run_instructions,
+            BatchSize::LargeInput,
+        );
+    });
+
+    group.finish();
+}
+
+/// Groups the benchmarks, as the function generated by [criterion_group] can't be documented.
+mod group {
+    use super::*;
+
+    criterion_group!(benches, bench_trace, bench_snapshot, bench_dispatch);
+}
+
+criterion_main!(group::benches);
@@ -0,0 +1,256 @@
+//! Holds the placeholder of the registers of the APU and the IO ports, answering the CPU until
+//! they are emulated, and the shift registers of the controllers.
+
+use log::warn;
+
+use crate::bus::APU_AND_IO_REGISTERS_START_ADDRESS;
+
+/// The number of registers of the APU and the IO ports, from `$4000` to `$4017`.
+pub const APU_IO_REGISTERS: usize = 0x18;
+
+/// The address of the register enabling the channels of the APU and telling their status.
+const STATUS_ADDRESS: u16 = 0x4015;
+
+/// The address of the register of the first controller port.
+const FIRST_CONTROLLER_ADDRESS: u16 = 0x4016;
+
+/// The address of the register of the second controller port, which is the frame counter when
+/// written.
+const SECOND_CONTROLLER_ADDRESS: u16 = 0x4017;
+
+/// The bits of the status register telling the channels with their length counter running, as
+/// enabled by its writes.
+const STATUS_CHANNEL_BITS: u8 = 0b0001_1111;
+
+/// The bits of the status register not driven by the APU, left to the open bus.
+const STATUS_OPEN_BUS_BITS: u8 = 0b0010_0000;
+
+/// The first address of the registers of the CPU Test Mode telling the outputs of the channels,
+/// the pulse channels first, then the triangle and noise ones, then the DMC.
+const TEST_MODE_OUTPUTS_START_ADDRESS: u16 = 0x4018;
+
+/// The last address of the registers of the CPU Test Mode telling the outputs of the channels.
+const TEST_MODE_OUTPUTS_END_ADDRESS: u16 = 0x401A;
+
+/// The bits of the controller ports not driven by the console, left to the open bus.
+const CONTROLLER_OPEN_BUS_BITS: u8 = 0b1110_0000;
+
+/// The bit of the first controller port telling the controllers to latch their buttons while set.
+const CONTROLLER_STROBE_BIT: u8 = 0b0000_0001;
+
+/// The number of controller ports.
+pub const CONTROLLER_PORTS: usize = 2;
+
+/// Stands for the registers of the APU and the IO ports, storing the values written to them to be
+/// used once the APU is emulated.
+///
+/// The registers are write-only, reading the open bus, but for the status one, which tells the
+/// channels enabled by its last write, and the controller ports, which shift out the buttons set
+/// with [ApuIoRegisters::set_controller_buttons] one by one after a strobe, then ones. Only the
+/// controllers are emulated, so a warning is logged the first time each of the other registers is
+/// accessed.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ApuIoRegisters {
+    /// The last value written to every register, zero if never written.
+    values: [u8; APU_IO_REGISTERS],
+
+    /// The buttons pressed on the controller of every port, with `A` on the bit 0, then `B`,
+    /// `Select`, `Start`, `Up`, `Down`, `Left` and `Right`.
+    controller_buttons: [u8; CONTROLLER_PORTS],
+
+    /// The shift register of the controller of every port, its bit 0 being the next one read.
+    controller_shifts: [u8; CONTROLLER_PORTS],
+
+    /// A bit for every register already warned about.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    warned: u32,
+}
+
+impl ApuIoRegisters {
+    /// Create a new [ApuIoRegisters] with no register written.
+    pub fn new() -> ApuIoRegisters {
+        ApuIoRegisters {
+            values: [0; APU_IO_REGISTERS],
+            controller_buttons: [0; CONTROLLER_PORTS],
+            controller_shifts: [0; CONTROLLER_PORTS],
+            warned: 0,
+        }
+    }
+
+    /// Get the last value written to the register at the given address, zero if never written.
+    ///
+    /// # Panics
+    /// If the address is not the one of an APU or IO register.
+    pub fn value(&self, address: u16) -> u8 {
+        self.values[ApuIoRegisters::index(address)]
+    }
+
+    /// Set the buttons pressed on the controller of the given port, with `A` on the bit 0, then
+    /// `B`, `Select`, `Start`, `Up`, `Down`, `Left` and `Right`.
+    ///
+    /// The buttons are seen by the CPU once the controllers are strobed.
+    ///
+    /// # Panics
+    /// If the port is not below [CONTROLLER_PORTS].
+    pub fn set_controller_buttons(&mut self, port: usize, buttons: u8) {
+        self.controller_buttons[port] = buttons;
+    }
+
+    /// Read the register at the given address, given the value left on the open bus.
+    ///
+    /// Reading a controller port shifts the next button in, unless the controllers are strobed.
+    pub(crate) fn read(&mut self, address: u16, open_bus: u8) -> u8 {
+        let value = self.peek(address, open_bus);
+
+        match ApuIoRegisters::controller_port(address) {
+            Some(port) if !self.strobe() => {
+                // The official controllers shift ones in once the buttons are read
+                self.controller_shifts[port] = (self.controller_shifts[port] >> 1) | 0x80;
+            }
+            Some(_) => {}
+            None => self.warn_once(address),
+        }
+
+        value
+    }
+
+    /// Read the register at the given address like [ApuIoRegisters::read] does, without logging.
+    pub(crate) fn peek(&self, address: u16, open_bus: u8) -> u8 {
+        match address {
+            STATUS_ADDRESS => (self.value(address) & STATUS_CHANNEL_BITS) | (open_bus & STATUS_OPEN_BUS_BITS),
+            FIRST_CONTROLLER_ADDRESS | SECOND_CONTROLLER_ADDRESS => {
+                let port = (address - FIRST_CONTROLLER_ADDRESS) as usize;
+
+                // The controllers keep answering the first button while strobed
+                let shift = match self.strobe() {
+                    true => self.controller_buttons[port],
+                    false => self.controller_shifts[port],
+                };
+
+                (shift & 0b0000_0001) | (open_bus & CONTROLLER_OPEN_BUS_BITS)
+            }
+            _ => open_bus,
+        }
+    }
+
+    /// Read the register of the CPU Test Mode at the given address, given the value left on the
+    /// open bus, without side effects.
+    ///
+    /// The registers from `$4018` to `$401A` tell the outputs of the channels, which stay silent
+    /// until the APU is emulated, while the other ones are not documented and read the open bus.
+    pub(crate) fn peek_test_mode(&self, address: u16, open_bus: u8) -> u8 {
+        match address {
+            TEST_MODE_OUTPUTS_START_ADDRESS..=TEST_MODE_OUTPUTS_END_ADDRESS => 0x00,
+            _ => open_bus,
+        }
+    }
+
+    /// Write a value to the register at the given address.
+    ///
+    /// Writing the first controller port strobes the controllers, latching their buttons.
+    pub(crate) fn write(&mut self, address: u16, value: u8) {
+        self.values[ApuIoRegisters::index(address)] = value;
+
+        match address {
+            FIRST_CONTROLLER_ADDRESS => self.controller_shifts = self.controller_buttons,
+            _ => self.warn_once(address),
+        }
+    }
+
+    /// Tell if the controllers are strobed, latching their buttons again on every cycle.
+    fn strobe(&self) -> bool {
+        self.value(FIRST_CONTROLLER_ADDRESS) & CONTROLLER_STROBE_BIT != 0
+    }
+
+    /// Get the controller port read at the given address, if any.
+    fn controller_port(address: u16) -> Option<usize> {
+        match address {
+            FIRST_CONTROLLER_ADDRESS | SECOND_CONTROLLER_ADDRESS => Some((address - FIRST_CONTROLLER_ADDRESS) as usize),
+            _ => None,
+        }
+    }
+
+    /// Get the index of the register at the given address.
+    fn index(address: u16) -> usize {
+        (address - APU_AND_IO_REGISTERS_START_ADDRESS) as usize
+    }
+
+    /// Log a warning about the register at the given address not being emulated, unless it was
+    /// already logged.
+    fn warn_once(&mut self, address: u16) {
+        let bit = 1 << ApuIoRegisters::index(address);
+
+        if self.warned & bit != 0 {
+            return;
+        }
+
+        self.warned |= bit;
+        warn!("APU: The register ${address:04X} is not emulated yet");
+    }
+}
+
+impl PartialEq for ApuIoRegisters {
+    /// Compare the registers, leaving out the warnings already logged.
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+            && self.controller_buttons == other.controller_buttons
+            && self.controller_shifts == other.controller_shifts
+    }
+}
+
+impl Default for ApuIoRegisters {
+    fn default() -> Self {
+        ApuIoRegisters::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_readback() {
+        let mut registers = ApuIoRegisters::new();
+        registers.write(STATUS_ADDRESS, 0xFF);
+
+        // The interrupt flags are clear and the bit 5 comes from the open bus
+        assert_eq!(registers.read(STATUS_ADDRESS, 0x00), 0x1F);
+        assert_eq!(registers.read(STATUS_ADDRESS, 0xFF), 0x3F);
+    }
+
+    #[test]
+    fn test_write_only_registers_read_open_bus() {
+        let mut registers = ApuIoRegisters::new();
+        registers.write(0x4000, 0x3F);
+
+        assert_eq!(registers.read(0x4000, 0x40), 0x40);
+        assert_eq!(registers.read(0x4011, 0x12), 0x12);
+        assert_eq!(registers.value(0x4000), 0x3F);
+
+        // No controller is plugged
+        assert_eq!(registers.read(FIRST_CONTROLLER_ADDRESS, 0x40), 0x40);
+        assert_eq!(registers.read(SECOND_CONTROLLER_ADDRESS, 0x1F), 0x00);
+    }
+
+    #[test]
+    fn test_controller_shift_register() {
+        let mut registers = ApuIoRegisters::new();
+
+        // A, Start and Right
+        registers.set_controller_buttons(0, 0b1000_1001);
+        registers.set_controller_buttons(1, 0b0000_0010);
+
+        // The buttons are latched while strobed
+        registers.write(FIRST_CONTROLLER_ADDRESS, 0x01);
+        assert_eq!(registers.read(FIRST_CONTROLLER_ADDRESS, 0x00), 0x01);
+        assert_eq!(registers.read(FIRST_CONTROLLER_ADDRESS, 0x00), 0x01);
+        registers.write(FIRST_CONTROLLER_ADDRESS, 0x00);
+
+        let first_port: Vec<u8> = (0..10).map(|_| registers.read(FIRST_CONTROLLER_ADDRESS, 0x40)).collect();
+        assert_eq!(first_port, [0x41, 0x40, 0x40, 0x41, 0x40, 0x40, 0x40, 0x41, 0x41, 0x41]);
+
+        let second_port: Vec<u8> = (0..3).map(|_| registers.read(SECOND_CONTROLLER_ADDRESS, 0x00)).collect();
+        assert_eq!(second_port, [0x00, 0x01, 0x00]);
+    }
+}
@@ -1,14 +1,28 @@
 //! Holds the implementation of a memory bus for the NES.
 
-use std::io::Read;
-use std::time::Instant;
+mod trace;
 
-use log::trace;
-use rand::prelude::*;
+use std::cell::RefCell;
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::ops::{Range, RangeInclusive};
+
+use log::{log_enabled, trace, Level};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use thiserror::Error;
 
+use crate::apu::ApuIoRegisters;
 use crate::cartridge::{Cartridge, CartridgeError};
-use crate::BYTES_ON_A_KIBIBYTE;
+use crate::cheat::Cheat;
+use crate::coverage::CoverageRecorder;
+use crate::ppu::{Ppu, OAM_SIZE};
+use crate::{build_address, next_address_in_page, BYTES_ON_A_KIBIBYTE};
+pub use crate::bus::trace::{BusTraceEvent, BusTraceKind};
+use crate::bus::trace::BusTraceSink;
+
+/// The size of the CPU RAM, mirrored three times after it.
+pub const CPU_RAM_SIZE: usize = 2 * BYTES_ON_A_KIBIBYTE;
 
 /// The address of the first byte of the CPU RAM.
 pub(crate) const CPU_RAM_WITH_MIRRORING_START_ADDRESS: u16 = 0x0000;
@@ -17,17 +31,26 @@ pub(crate) const CPU_RAM_WITH_MIRRORING_START_ADDRESS: u16 = 0x0000;
 pub(crate) const CPU_RAM_WITH_MIRRORING_END_ADDRESS: u16 = 0x1FFF;
 
 /// The address of the first byte of the PPU registers.
-const PPU_REGISTERS_WITH_MIRRORING_START_ADDRESS: u16 = 0x2000;
+pub(crate) const PPU_REGISTERS_WITH_MIRRORING_START_ADDRESS: u16 = 0x2000;
 
 /// The address of the last byte of the PPU registers after all the mirrors.
 const PPU_REGISTERS_WITH_MIRRORING_END_ADDRESS: u16 = 0x3FFF;
 
 /// The address of the first byte of the APU and IO registers.
-const APU_AND_IO_REGISTERS_START_ADDRESS: u16 = 0x4000;
+pub(crate) const APU_AND_IO_REGISTERS_START_ADDRESS: u16 = 0x4000;
 
 /// The address of the last byte of the APU and IO registers.
 const APU_AND_IO_REGISTERS_END_ADDRESS: u16 = 0x4017;
 
+/// The address of the register starting an OAM DMA when written.
+const OAM_DMA_ADDRESS: u16 = 0x4014;
+
+/// The address of the register of the PPU writing the OAM, the one the OAM DMA writes to.
+const OAMDATA_ADDRESS: u16 = 0x2004;
+
+/// The number of dots the PPU runs for every CPU cycle.
+const PPU_DOTS_PER_CPU_CYCLE: usize = 3;
+
 /// The address of the first byte of the APU and IO registers available only on the CPU Test Mode.
 const APU_AND_IO_CPU_TEST_MODE_REGISTERS_START_ADDRESS: u16 = 0x4018;
 
@@ -41,16 +64,184 @@ const CARTRIDGE_CONTROLLED_REGION_START_ADDRESS: u16 = 0x4020;
 const CARTRIDGE_CONTROLLED_REGION_END_ADDRESS: u16 = 0xFFFF;
 
 /// Emulation of the chips and boards related to memory address management.
-pub struct Bus {
+///
+/// The bus has no notion of the real time, it's clocked by the CPU ticking it once per
+/// CPU cycle, so the same program always runs the same way no matter how fast the host is. The
+/// chips attached to the bus advance from there: three PPU dots and one APU step per CPU cycle.
+///
+/// The cartridge is boxed unless a concrete one is given to [Bus::from_cartridge].
+pub struct Bus<C: Cartridge = Box<dyn Cartridge>> {
     /// The RAM of the CPU.
-    cpu_ram: [u8; 2 * BYTES_ON_A_KIBIBYTE],
-
-    last_cpu_cycle: Instant,
+    cpu_ram: [u8; CPU_RAM_SIZE],
 
     /// The inserted cartridge in the board.
-    cartridge: Box<dyn Cartridge>,
+    cartridge: C,
+
+    /// If the cartridge maps the whole address space, as told by [Cartridge::maps_whole_address_space].
+    whole_address_space_cartridge: bool,
+
+    /// The PPU, answering the accesses to its registers.
+    ppu: Ppu,
+
+    /// The placeholder of the registers of the APU and the IO ports.
+    apu_io_registers: ApuIoRegisters,
+
+    /// The last value driven on the data bus by a read or a write, read back from the write-only
+    /// registers and the addresses with nothing mapped, as the data bus keeps it.
+    last_bus_value: u8,
+
+    /// The page whose copy to the OAM was requested, waiting for the CPU to start the DMA.
+    oam_dma_request: Option<u8>,
+
+    /// The address ranges whose accesses are recorded.
+    watchpoints: Vec<Watchpoint>,
+
+    /// The accesses that hit a watchpoint since they were last taken.
+    watchpoint_hits: Vec<WatchpointHit>,
+
+    /// The observer notified of every access, if any.
+    observer: Option<Box<dyn BusObserver>>,
+
+    /// The recorder of the accessed addresses, if the coverage is enabled.
+    coverage: Option<CoverageRecorder>,
+
+    /// The cheats patching the reads of the cartridge, in the order they were added.
+    cheats: Vec<Cheat>,
+
+    /// If the registers of the CPU Test Mode are enabled, like on a CPU with its test pin pulled up.
+    cpu_test_mode: bool,
+
+    /// Where the accesses are traced, if anywhere.
+    trace_sink: RefCell<Option<BusTraceSink>>,
+
+    /// The number of cycles the CPU had run before the cycle it's running, stamped on the traced
+    /// accesses.
+    cpu_cycle: u64,
+}
+
+/// Observes every access done to the bus, for tooling like coverage analyzers or trace viewers.
+///
+/// The observer is notified once the access has resolved, and never for the peeks done for
+/// debugging and disassembling purposes, which can be traced with [Bus::set_trace_callback].
+pub trait BusObserver {
+    /// Called after a value was read from the given address.
+    fn on_read(&mut self, _address: u16, _value: u8) {}
+
+    /// Called after a value was written to the given address.
+    fn on_write(&mut self, _address: u16, _value: u8) {}
+}
+
+/// The state of a [Bus] that changes while running, to be restored later with [Bus::load_state].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(crate) struct BusState {
+    /// The RAM of the CPU.
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    cpu_ram: [u8; CPU_RAM_SIZE],
+
+    /// The state of the inserted cartridge, given by [Cartridge::save_state].
+    cartridge: Vec<u8>,
+
+    /// The state of the PPU.
+    ppu: Ppu,
+
+    /// The placeholder of the registers of the APU and the IO ports.
+    apu_io_registers: ApuIoRegisters,
+
+    /// The last value driven on the data bus.
+    last_bus_value: u8,
+
+    /// The page whose copy to the OAM was requested.
+    oam_dma_request: Option<u8>,
+}
+
+/// The values the RAM of the CPU holds on power-on.
+///
+/// The bits of the RAM are undefined on a real console, which some games use as a source of
+/// entropy while some test ROMs expect a given fill, so the RAM is zeroed by default to keep the
+/// runs deterministic.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RamInitPattern {
+    /// Every byte is `$00`.
+    #[default]
+    AllZeros,
+
+    /// Every byte is `$FF`.
+    AllOnes,
+
+    /// Every byte is the given value.
+    Pattern(u8),
+
+    /// Every byte is random, drawn from a generator seeded with the given value so the runs are
+    /// reproducible.
+    Random {
+        /// The seed of the generator.
+        seed: u64,
+    },
+}
+
+impl RamInitPattern {
+    /// Fill the given RAM with the pattern.
+    fn fill(self, ram: &mut [u8]) {
+        match self {
+            RamInitPattern::AllZeros => ram.fill(0x00),
+            RamInitPattern::AllOnes => ram.fill(0xFF),
+            RamInitPattern::Pattern(value) => ram.fill(value),
+            RamInitPattern::Random { seed } => StdRng::seed_from_u64(seed).fill(ram),
+        }
+    }
+}
+
+/// The kind of an access to the bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusAccess {
+    /// A value was read from the bus.
+    Read,
+
+    /// A value was written to the bus.
+    Write,
+}
+
+/// The region of the address space of the CPU an address is mapped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Region {
+    /// The RAM of the CPU, at the given offset once the mirroring is removed.
+    CpuRam(usize),
+
+    /// The registers of the PPU.
+    Ppu,
+
+    /// The registers of the APU and the IO ports.
+    ApuIoRegisters,
+
+    /// The registers only available on the CPU Test Mode.
+    TestModeRegisters,
+
+    /// The range controlled by the cartridge, or the whole address space if it maps all of it.
+    Cartridge,
+}
 
-    cpu_response: Option<u8>,
+/// A range of addresses whose accesses of a kind are recorded.
+#[derive(Debug, Clone)]
+struct Watchpoint {
+    /// The watched addresses, as seen by the CPU before any mirroring.
+    addresses: RangeInclusive<u16>,
+
+    /// The kind of access watched.
+    access: BusAccess,
+}
+
+/// An access to the bus that hit a watchpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WatchpointHit {
+    /// The accessed address.
+    pub(crate) address: u16,
+
+    /// The value read or written.
+    pub(crate) value: u8,
+
+    /// The kind of access.
+    pub(crate) access: BusAccess,
 }
 
 #[derive(Error, Debug)]
@@ -72,95 +263,855 @@ pub enum BusError {
 impl Bus {
     /// Create a new [Bus].
     pub fn new(cartridge: Box<dyn Cartridge>) -> Bus {
-        // The CPU RAM should be randomized to emulate the undefined state of the bits on startup,
-        // used on some games as a pseudo RNG
+        Bus::from_cartridge(cartridge)
+    }
 
-        //let rng = rand::rng();
-        //let cpu_ram: Vec<u8> = rng.random_iter().take(2 * BYTES_ON_A_KIBIBYTE).collect();
+    /// Create a new [Bus] with the RAM of the CPU filled with the given pattern.
+    pub fn new_with_ram_init(cartridge: Box<dyn Cartridge>, ram_init: RamInitPattern) -> Bus {
+        Bus::from_cartridge_with_ram_init(cartridge, ram_init)
+    }
+}
+
+impl<C: Cartridge> Bus<C> {
+    /// Create a new [Bus] holding the given cartridge as is, with the RAM of the CPU zeroed.
+    pub fn from_cartridge(cartridge: C) -> Bus<C> {
+        Bus::from_cartridge_with_ram_init(cartridge, RamInitPattern::default())
+    }
+
+    /// Create a new [Bus] holding the given cartridge as is, with the RAM of the CPU filled with
+    /// the given pattern.
+    pub fn from_cartridge_with_ram_init(cartridge: C, ram_init: RamInitPattern) -> Bus<C> {
+        let mut cpu_ram = [0; CPU_RAM_SIZE];
+        ram_init.fill(&mut cpu_ram);
 
         Bus {
-            //cpu_ram: cpu_ram.try_into().unwrap(),
-            cpu_ram: [0; 2 * BYTES_ON_A_KIBIBYTE],
+            cpu_ram,
+            whole_address_space_cartridge: cartridge.maps_whole_address_space(),
             cartridge,
-            last_cpu_cycle: Instant::now(),
-            cpu_response: None,
+            ppu: Ppu::new(),
+            apu_io_registers: ApuIoRegisters::new(),
+            last_bus_value: 0x00,
+            oam_dma_request: None,
+            watchpoints: vec![],
+            watchpoint_hits: vec![],
+            observer: None,
+            coverage: None,
+            cheats: vec![],
+            cpu_test_mode: false,
+            trace_sink: RefCell::new(None),
+            cpu_cycle: 0,
         }
     }
 
-    /// Request a read to the bus.
-    pub(crate) fn read(&self, address: u16) -> Result<u8, BusError> {
-        let value = match address {
+    /// Advance the chips attached to the bus by one CPU cycle.
+    ///
+    /// It's called by the CPU at the end of every cycle it runs, including the ones stalled by the
+    /// OAM DMA and the ones of the reset sequence. The PPU runs three dots on every cycle, fetching
+    /// the pattern tables from the cartridge.
+    pub(crate) fn tick(&mut self) {
+        for _ in 0..PPU_DOTS_PER_CPU_CYCLE {
+            self.ppu.step(&self.cartridge);
+        }
+
+        // The APU will run one step here
+    }
+
+    /// Get the region the given address is mapped to.
+    fn resolve(&self, address: u16) -> Region {
+        match address {
+            _ if self.whole_address_space_cartridge => Region::Cartridge,
+
             CPU_RAM_WITH_MIRRORING_START_ADDRESS..=CPU_RAM_WITH_MIRRORING_END_ADDRESS => {
                 // Remove everything past the first 11 bits, mirroring the memory in the process
-                let masked_adress = address & 0b00000111_11111111;
-
-                Ok(self.cpu_ram[masked_adress as usize])
+                Region::CpuRam((address & 0b00000111_11111111) as usize)
             }
 
-            PPU_REGISTERS_WITH_MIRRORING_START_ADDRESS
-                ..=PPU_REGISTERS_WITH_MIRRORING_END_ADDRESS => {
-                // It's only needed to check the first three bits of the address to get the number of the PPU register to access
-                todo!("PPU registers have not been implemented yet")
-            }
+            PPU_REGISTERS_WITH_MIRRORING_START_ADDRESS..=PPU_REGISTERS_WITH_MIRRORING_END_ADDRESS => Region::Ppu,
+            APU_AND_IO_REGISTERS_START_ADDRESS..=APU_AND_IO_REGISTERS_END_ADDRESS => Region::ApuIoRegisters,
 
-            APU_AND_IO_REGISTERS_START_ADDRESS..=APU_AND_IO_REGISTERS_END_ADDRESS => {
-                todo!("APU and IO registers have not been implemented yet")
+            APU_AND_IO_CPU_TEST_MODE_REGISTERS_START_ADDRESS..=APU_AND_IO_CPU_TEST_MODE_REGISTERS_END_ADDRESS => {
+                Region::TestModeRegisters
             }
 
-            APU_AND_IO_CPU_TEST_MODE_REGISTERS_START_ADDRESS
-                ..=APU_AND_IO_CPU_TEST_MODE_REGISTERS_END_ADDRESS => {
-                todo!("APU and IO special registers when the CPU is in Test Mode have not been implemented yet")
-            }
+            CARTRIDGE_CONTROLLED_REGION_START_ADDRESS..=CARTRIDGE_CONTROLLED_REGION_END_ADDRESS => Region::Cartridge,
+        }
+    }
+
+    /// Request a read to the bus.
+    pub(crate) fn read(&mut self, address: u16) -> Result<u8, BusError> {
+        let value = match self.resolve(address) {
+            Region::CpuRam(offset) => Ok(self.cpu_ram[offset]),
+            Region::Ppu => Ok(self.ppu.read(address, &self.cartridge)),
+            Region::ApuIoRegisters => Ok(self.apu_io_registers.read(address, self.last_bus_value)),
 
-            CARTRIDGE_CONTROLLED_REGION_START_ADDRESS..=CARTRIDGE_CONTROLLED_REGION_END_ADDRESS => unsafe {
-                self.cartridge
-                    .read(address)
-                    .map_err(BusError::CartridgeError)
-            },
+            Region::TestModeRegisters => Ok(self.test_mode_value(address)),
+
+            Region::Cartridge => self
+                .cartridge_value(unsafe { self.cartridge.read(address) })
+                .map(|value| self.apply_cheats(address, value)),
         };
 
         match value {
-            Ok(value) => trace!("Bus: Read {value:#02X} @ {address:#02X}"),
-            Err(ref err) => trace!("Bus: Read @ {address:#02X} failed! ({err})"),
+            Ok(value) => {
+                if log_enabled!(Level::Trace) {
+                    trace!("Bus: Read {value:#02X} @ {address:#02X}");
+                }
+
+                self.last_bus_value = value;
+                self.trace_access(BusTraceKind::Read, address, value);
+                self.check_watchpoints(address, value, BusAccess::Read);
+
+                if let Some(observer) = &mut self.observer {
+                    observer.on_read(address, value);
+                }
+
+                if let Some(coverage) = &mut self.coverage {
+                    coverage.mark_read(address);
+                }
+            }
+            Err(ref err) if log_enabled!(Level::Trace) => trace!("Bus: Read @ {address:#02X} failed! ({err})"),
+            Err(_) => {}
+        };
+
+        value
+    }
+
+    /// Read a byte from the bus without any side effect, for debuggers and disassemblers, decoding
+    /// the address like [Bus::read] does.
+    ///
+    /// The registers answer what a read would without changing their state, like the controller
+    /// ports not shifting their buttons. The write-only registers, the addresses with nothing
+    /// mapped and the ones the cartridge fails to peek answer with the last value on the data bus,
+    /// which is left as it is.
+    pub fn peek(&self, address: u16) -> u8 {
+        let value = match self.resolve(address) {
+            Region::CpuRam(offset) => Ok(self.cpu_ram[offset]),
+            Region::Ppu => Ok(self.ppu.peek(address)),
+            Region::ApuIoRegisters => Ok(self.apu_io_registers.peek(address, self.last_bus_value)),
+            Region::TestModeRegisters => Ok(self.test_mode_value(address)),
+            Region::Cartridge => self.cartridge_value(unsafe { self.cartridge.peek(address) }),
         };
 
+        let value = value.unwrap_or(self.last_bus_value);
+        self.trace_access(BusTraceKind::Peek, address, value);
+
         value
     }
 
+    /// Read a byte from the bus without any side effect like [Bus::peek] does, but with the cheats
+    /// applied like the CPU sees them.
+    pub fn peek_with_cheats(&self, address: u16) -> u8 {
+        let value = self.peek(address);
+
+        match self.resolve(address) {
+            Region::Cartridge => self.apply_cheats(address, value),
+            _ => value,
+        }
+    }
+
+    /// Peek the given number of bytes from the bus with [Bus::peek], starting at the given address
+    /// and wrapping around the end of the address space, like the hexdump views need.
+    pub fn peek_range(&self, start: u16, length: usize) -> Vec<u8> {
+        (0..length).map(|offset| self.peek(start.wrapping_add(offset as u16))).collect()
+    }
+
+    /// Render the given addresses as a hexdump, with every line holding the address of its first
+    /// byte, up to 16 bytes in hexadecimal and the same bytes in ASCII, dots standing for the ones
+    /// not printable.
+    ///
+    /// The bytes are peeked with [Bus::peek], so any region can be dumped without side effects.
+    pub fn hexdump(&self, range: Range<u16>) -> String {
+        let bytes = self.peek_range(range.start, range.len());
+
+        bytes
+            .chunks(HEXDUMP_LINE_BYTES)
+            .enumerate()
+            .map(|(line, chunk)| {
+                let address = range.start.wrapping_add((line * HEXDUMP_LINE_BYTES) as u16);
+
+                let hex: Vec<String> = chunk.iter().map(|byte| format!("{byte:02X}")).collect();
+                let (first_half, second_half) = hex.split_at(hex.len().min(HEXDUMP_LINE_BYTES / 2));
+
+                let ascii: String = chunk
+                    .iter()
+                    .map(|&byte| match byte.is_ascii_graphic() || byte == b' ' {
+                        true => byte as char,
+                        false => '.',
+                    })
+                    .collect();
+
+                format!("{address:04X}  {:<23}  {:<23}  |{ascii}|\n", first_half.join(" "), second_half.join(" "))
+            })
+            .collect()
+    }
+
+    /// Add a cheat patching the reads of the cartridge done by the CPU, taking precedence over the
+    /// ones added before at the same address.
+    ///
+    /// The writes and the peeks are left as they are, see [Bus::peek_with_cheats].
+    pub fn add_cheat(&mut self, cheat: Cheat) {
+        self.cheats.push(cheat);
+    }
+
+    /// Remove the given cheat, telling if it was added.
+    pub fn remove_cheat(&mut self, cheat: &Cheat) -> bool {
+        let Some(index) = self.cheats.iter().position(|added| added == cheat) else {
+            return false;
+        };
+
+        self.cheats.remove(index);
+
+        true
+    }
+
+    /// Get the cheats patching the reads of the cartridge, in the order they were added.
+    pub fn list_cheats(&self) -> &[Cheat] {
+        &self.cheats
+    }
+
+    /// Get the value read from the given address of the cartridge with the cheats applied, given
+    /// the one answered by the cartridge.
+    fn apply_cheats(&self, address: u16, value: u8) -> u8 {
+        // The last cheat added whose compare value matches wins, even if it patches nothing
+        self.cheats
+            .iter()
+            .rev()
+            .find(|cheat| cheat.address == address && cheat.matches(value))
+            .map_or(value, |cheat| cheat.value)
+    }
+
+    /// Get the value read from the given register of the CPU Test Mode, which is the last value on
+    /// the data bus unless the test mode is enabled, as the registers are disabled on the consoles.
+    fn test_mode_value(&self, address: u16) -> u8 {
+        match self.cpu_test_mode {
+            true => self.apu_io_registers.peek_test_mode(address, self.last_bus_value),
+            false => self.last_bus_value,
+        }
+    }
+
+    /// Turn the result of an access to the cartridge into the one of the bus, answering with the
+    /// last value on the data bus if the cartridge has nothing mapped at the address.
+    fn cartridge_value(&self, result: Result<u8, CartridgeError>) -> Result<u8, BusError> {
+        match result {
+            Err(CartridgeError::OpenBus) => Ok(self.last_bus_value),
+            result => result.map_err(BusError::CartridgeError),
+        }
+    }
+
+    /// Read a little-endian 16-bit word from the bus, with the lower byte at the given address.
+    ///
+    /// Both bytes are read like the CPU does, with all the side effects of the reads, so the tools
+    /// only inspecting the bus should use [Bus::peek_u16] instead.
+    pub fn read_u16(&mut self, address: u16) -> Result<u16, BusError> {
+        let lower_byte = self.read(address)?;
+        let upper_byte = self.read(address.wrapping_add(1))?;
+
+        Ok(build_address(lower_byte, upper_byte))
+    }
+
+    /// Read a little-endian 16-bit word from the bus like [Bus::read_u16] does, but with the upper
+    /// byte read from the same page, wrapping from `$xxFF` to `$xx00` like the pointers of the
+    /// indirect jump and the zero page do.
+    pub fn read_u16_page_wrapped(&mut self, address: u16) -> Result<u16, BusError> {
+        let lower_byte = self.read(address)?;
+        let upper_byte = self.read(next_address_in_page(address))?;
+
+        Ok(build_address(lower_byte, upper_byte))
+    }
+
+    /// Peek a little-endian 16-bit word from the bus with [Bus::peek], with the lower byte at the
+    /// given address.
+    pub fn peek_u16(&self, address: u16) -> u16 {
+        build_address(self.peek(address), self.peek(address.wrapping_add(1)))
+    }
+
+    /// Peek a little-endian 16-bit word from the bus with [Bus::peek], with the upper byte from the
+    /// same page, see [Bus::read_u16_page_wrapped].
+    pub fn peek_u16_page_wrapped(&self, address: u16) -> u16 {
+        build_address(self.peek(address), self.peek(next_address_in_page(address)))
+    }
+
+    /// Write a byte to the RAM of the CPU or to the cartridge without driving the data bus, for the
+    /// tooling patching the memory behind the back of the CPU.
+    ///
+    /// The watchpoints, the observer and the coverage are not told about the write.
+    pub(crate) fn write_quietly(&mut self, address: u16, value: u8) -> Result<(), BusError> {
+        match self.resolve(address) {
+            Region::CpuRam(offset) => {
+                self.cpu_ram[offset] = value;
+
+                Ok(())
+            }
+
+            Region::Cartridge => unsafe { self.cartridge.write(address, value).map_err(BusError::CartridgeError) },
+
+            _ => Err(BusError::CannotWrite("Only the RAM and the cartridge can be written quietly")),
+        }
+    }
+
     /// Write a byte to a memory address in the bus.
     pub(crate) fn write(&mut self, address: u16, value: u8) -> Result<(), BusError> {
-        trace!("Bus: Write {value:#02X} @ {address:#02X}");
+        if log_enabled!(Level::Trace) {
+            trace!("Bus: Write {value:#02X} @ {address:#02X}");
+        }
 
-        match address {
-            CPU_RAM_WITH_MIRRORING_START_ADDRESS..=CPU_RAM_WITH_MIRRORING_END_ADDRESS => {
-                // Remove everything past the first 11 bits
-                let masked_adress = address & 0b00000111_11111111;
+        let result = match self.resolve(address) {
+            Region::CpuRam(offset) => {
+                self.cpu_ram[offset] = value;
+
+                Ok(())
+            }
 
-                self.cpu_ram[masked_adress as usize] = value;
+            Region::Ppu => {
+                self.ppu.write(address, value, &mut self.cartridge);
 
                 Ok(())
             }
 
-            PPU_REGISTERS_WITH_MIRRORING_START_ADDRESS
-                ..=PPU_REGISTERS_WITH_MIRRORING_END_ADDRESS => {
-                // It's only needed to check the first three bits of the address to get the number of the PPU register to access
-                todo!("PPU registers have not been implemented yet")
+            Region::ApuIoRegisters if address == OAM_DMA_ADDRESS => {
+                // The copy is run by the CPU, which stalls while it's in progress
+                self.oam_dma_request = Some(value);
+
+                Ok(())
             }
 
-            APU_AND_IO_REGISTERS_START_ADDRESS..=APU_AND_IO_REGISTERS_END_ADDRESS => {
-                todo!("APU and IO registers have not been implemented yet")
+            Region::ApuIoRegisters => {
+                self.apu_io_registers.write(address, value);
+
+                Ok(())
             }
 
-            APU_AND_IO_CPU_TEST_MODE_REGISTERS_START_ADDRESS
-                ..=APU_AND_IO_CPU_TEST_MODE_REGISTERS_END_ADDRESS => {
-                todo!("APU and IO special registers when the CPU is in Test Mode have not been implemented yet")
+            // None of the registers of the CPU Test Mode has an effect emulated yet
+            Region::TestModeRegisters => Ok(()),
+
+            Region::Cartridge => unsafe { self.cartridge.write(address, value).map_err(BusError::CartridgeError) },
+        };
+
+        if result.is_ok() {
+            self.last_bus_value = value;
+            self.trace_access(BusTraceKind::Write, address, value);
+            self.check_watchpoints(address, value, BusAccess::Write);
+
+            if let Some(observer) = &mut self.observer {
+                observer.on_write(address, value);
             }
 
-            CARTRIDGE_CONTROLLED_REGION_START_ADDRESS..=CARTRIDGE_CONTROLLED_REGION_END_ADDRESS => unsafe {
-                self.cartridge
-                    .write(address, value)
-                    .map_err(BusError::CartridgeError)
-            },
+            if let Some(coverage) = &mut self.coverage {
+                coverage.mark_written(address);
+            }
+        }
+
+        result
+    }
+
+    /// Get the state of the bus that changes while running, including the one of the cartridge.
+    ///
+    /// The watchpoints, the observer and the coverage are tooling, so they are not part of the state.
+    pub(crate) fn save_state(&self) -> BusState {
+        BusState {
+            cpu_ram: self.cpu_ram,
+            cartridge: self.cartridge.save_state(),
+            ppu: self.ppu.clone(),
+            apu_io_registers: self.apu_io_registers.clone(),
+            last_bus_value: self.last_bus_value,
+            oam_dma_request: self.oam_dma_request,
         }
     }
+
+    /// Restore a state given by [Bus::save_state], failing if the cartridge rejects its state.
+    pub(crate) fn load_state(&mut self, state: &BusState) -> Result<(), BusError> {
+        self.cartridge.load_state(&state.cartridge)?;
+        self.cpu_ram = state.cpu_ram;
+        self.ppu = state.ppu.clone();
+        self.apu_io_registers = state.apu_io_registers.clone();
+        self.last_bus_value = state.last_bus_value;
+        self.oam_dma_request = state.oam_dma_request;
+
+        Ok(())
+    }
+
+    /// Get the RAM of the CPU, without its mirrors.
+    pub fn ram(&self) -> &[u8; CPU_RAM_SIZE] {
+        &self.cpu_ram
+    }
+
+    /// Get the RAM of the CPU, without its mirrors, to edit it without going through the bus.
+    pub fn ram_mut(&mut self) -> &mut [u8; CPU_RAM_SIZE] {
+        &mut self.cpu_ram
+    }
+
+    /// Replace the RAM of the CPU with the given one, like a dump taken with [Bus::ram].
+    pub fn load_ram(&mut self, ram: &[u8; CPU_RAM_SIZE]) {
+        self.cpu_ram = *ram;
+    }
+
+    /// Fill the RAM of the CPU with the given pattern, like it was just powered on.
+    pub(crate) fn init_ram(&mut self, ram_init: RamInitPattern) {
+        ram_init.fill(&mut self.cpu_ram);
+    }
+
+    /// Get the PPU attached to the bus.
+    pub fn ppu(&self) -> &Ppu {
+        &self.ppu
+    }
+
+    /// Get the PPU attached to the bus.
+    pub fn ppu_mut(&mut self) -> &mut Ppu {
+        &mut self.ppu
+    }
+
+    /// Get the placeholder of the registers of the APU and the IO ports.
+    pub fn apu_io_registers(&self) -> &ApuIoRegisters {
+        &self.apu_io_registers
+    }
+
+    /// Get the placeholder of the registers of the APU and the IO ports, to press the buttons of
+    /// the controllers.
+    pub fn apu_io_registers_mut(&mut self) -> &mut ApuIoRegisters {
+        &mut self.apu_io_registers
+    }
+
+    /// Get the OAM of the PPU, as filled by OAMDATA and the OAM DMA.
+    pub fn oam(&self) -> &[u8; OAM_SIZE] {
+        self.ppu.oam()
+    }
+
+    /// Write a byte to the OAM of the PPU through OAMDATA, like the OAM DMA does, without the
+    /// side effects of a write done by the CPU.
+    pub(crate) fn write_oam_data(&mut self, value: u8) {
+        self.ppu.write(OAMDATA_ADDRESS, value, &mut self.cartridge);
+    }
+
+    /// Enable or disable the registers of the CPU Test Mode from `$4018` to `$401F`, disabled by
+    /// default like on the consoles, where they read the open bus.
+    ///
+    /// Once enabled, the registers from `$4018` to `$401A` read the outputs of the channels of the
+    /// APU. The writes are ignored either way.
+    pub fn set_cpu_test_mode(&mut self, enabled: bool) {
+        self.cpu_test_mode = enabled;
+    }
+
+    /// Tell if the registers of the CPU Test Mode are enabled, see [Bus::set_cpu_test_mode].
+    pub fn cpu_test_mode(&self) -> bool {
+        self.cpu_test_mode
+    }
+
+    /// Take the NMI raised by the PPU since the last call, telling if there was one.
+    pub(crate) fn take_nmi_request(&mut self) -> bool {
+        self.ppu.take_nmi_request()
+    }
+
+    /// Take the page whose copy to the OAM was requested by writing to $4014, if any.
+    pub(crate) fn take_oam_dma_request(&mut self) -> Option<u8> {
+        self.oam_dma_request.take()
+    }
+
+    /// Set the observer notified of every access to the bus, or remove it with [None].
+    pub fn set_observer(&mut self, observer: Option<Box<dyn BusObserver>>) {
+        self.observer = observer;
+    }
+
+    /// Start recording the coverage of the addresses, keeping the one already recorded if any.
+    pub(crate) fn enable_coverage(&mut self) {
+        self.coverage.get_or_insert_with(CoverageRecorder::new);
+    }
+
+    /// Stop recording the coverage of the addresses, returning the one recorded if any.
+    pub(crate) fn take_coverage(&mut self) -> Option<CoverageRecorder> {
+        self.coverage.take()
+    }
+
+    /// Get the coverage of the addresses recorded, if it's enabled.
+    pub(crate) fn coverage(&self) -> Option<&CoverageRecorder> {
+        self.coverage.as_ref()
+    }
+
+    /// Record the bytes of an instruction of the given length starting at the given address as
+    /// executed, if the coverage is enabled.
+    pub(crate) fn mark_executed(&mut self, address: u16, length: u8) {
+        if let Some(coverage) = &mut self.coverage {
+            coverage.mark_executed(address, length);
+        }
+    }
+
+    /// Record the accesses of the given kind to the given addresses.
+    ///
+    /// Only the accesses done through [Bus::read] and [Bus::write] are recorded, never the peeks.
+    pub(crate) fn watch(&mut self, addresses: RangeInclusive<u16>, access: BusAccess) {
+        self.watchpoints.push(Watchpoint { addresses, access });
+    }
+
+    /// Remove all the watchpoints, keeping the hits not taken yet.
+    pub(crate) fn clear_watchpoints(&mut self) {
+        self.watchpoints.clear();
+    }
+
+    /// Take the accesses that hit a watchpoint since the last call, in the order they were done.
+    pub(crate) fn take_watchpoint_hits(&mut self) -> Vec<WatchpointHit> {
+        std::mem::take(&mut self.watchpoint_hits)
+    }
+
+    /// Record the given access if it hits a watchpoint.
+    fn check_watchpoints(&mut self, address: u16, value: u8, access: BusAccess) {
+        let hit = self
+            .watchpoints
+            .iter()
+            .any(|watchpoint| watchpoint.access == access && watchpoint.addresses.contains(&address));
+
+        if hit {
+            self.watchpoint_hits.push(WatchpointHit { address, value, access });
+        }
+    }
+}
+
+/// The number of bytes shown on every line of a [hexdump](Bus::hexdump).
+const HEXDUMP_LINE_BYTES: usize = 16;
+
+/// The number of bytes at the start of the RAM shown by the [Debug] output of the [Bus].
+const DEBUG_RAM_BYTES: usize = 16;
+
+impl<C: Cartridge> fmt::Debug for Bus<C> {
+    /// Format the bus summarizing the RAM as its first bytes and a hash of its content, so two
+    /// dumps tell if the RAM changed without printing all of it.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut hasher = DefaultHasher::new();
+        self.cpu_ram.hash(&mut hasher);
+
+        f.debug_struct("Bus")
+            .field("cpu_ram", &format_args!("{:02X?}...", &self.cpu_ram[..DEBUG_RAM_BYTES]))
+            .field("cpu_ram_hash", &format_args!("{:016X}", hasher.finish()))
+            .field("watchpoints", &self.watchpoints)
+            .field("cheats", &self.cheats)
+            .field("trace_sink", &self.trace_sink.borrow())
+            .field("observer", &self.observer.is_some())
+            .field("coverage", &self.coverage.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<C: Cartridge> serde::Serialize for Bus<C> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.save_state().serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::testing::FlatCartridge;
+    use crate::cpu::Cpu;
+
+    #[test]
+    fn test_ppu_registers_mirroring() {
+        let mut bus = Bus::from_cartridge(FlatCartridge::new(vec![]));
+
+        // $3FF8 is the last mirror of PPUCTRL
+        bus.write(0x3FF8, 0x02).unwrap();
+        assert_eq!(bus.ppu().temporary_address(), 0x0800);
+
+        // $3FFE and $200E are mirrors of PPUADDR
+        bus.write(0x3FFE, 0x21).unwrap();
+        bus.write(0x200E, 0x08).unwrap();
+        assert_eq!(bus.ppu().vram_address(), 0x2108);
+
+        // Every address of the range can be accessed
+        for address in PPU_REGISTERS_WITH_MIRRORING_START_ADDRESS..=PPU_REGISTERS_WITH_MIRRORING_END_ADDRESS {
+            bus.read(address).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_apu_init_sequence() {
+        let mut cpu = Cpu::with_program(&[
+            // LDA #$40, STA $4017
+            0xA9, 0x40, 0x8D, 0x17, 0x40,
+
+            // LDA #$00, STA $4010
+            0xA9, 0x00, 0x8D, 0x10, 0x40,
+
+            // LDA #$0F, STA $4015
+            0xA9, 0x0F, 0x8D, 0x15, 0x40,
+
+            // LDX $4015, LDY $4000
+            0xAE, 0x15, 0x40, 0xAC, 0x00, 0x40,
+        ]);
+
+        cpu.step_n(8).unwrap();
+
+        assert_eq!(cpu.bus().apu_io_registers().value(0x4017), 0x40);
+        assert_eq!(cpu.bus().apu_io_registers().value(0x4010), 0x00);
+
+        // The enabled channels are read back, and the write-only registers read the upper byte of
+        // the address left on the open bus
+        assert_eq!(cpu.x(), 0x0F);
+        assert_eq!(cpu.y(), 0x40);
+    }
+
+    #[test]
+    fn test_open_bus() {
+        // LDA #$57
+        let mut cpu = Cpu::with_program(&[0xA9, 0x57]);
+        cpu.step().unwrap();
+
+        // The operand fetch was the last bus activity
+        assert_eq!(cpu.bus().peek(0x4018), 0x57);
+        assert_eq!(cpu.bus_mut().read(0x401F).unwrap(), 0x57);
+        assert_eq!(cpu.bus_mut().read(0x4000).unwrap(), 0x57);
+
+        // The writes drive the data bus too
+        cpu.bus_mut().write(0x0010, 0x21).unwrap();
+        assert_eq!(cpu.bus_mut().read(0x4018).unwrap(), 0x21);
+    }
+
+    #[test]
+    fn test_cpu_test_mode() {
+        let mut bus = Bus::from_cartridge(FlatCartridge::new(vec![]));
+        bus.write(0x0000, 0x5A).unwrap();
+
+        assert!(!bus.cpu_test_mode());
+        assert_eq!(bus.read(0x4018).unwrap(), 0x5A);
+
+        // The channels are silent, and the undocumented registers still read the open bus
+        bus.set_cpu_test_mode(true);
+        assert_eq!(bus.peek(0x401A), 0x00);
+        assert_eq!(bus.read(0x4018).unwrap(), 0x00);
+        assert_eq!(bus.read(0x401F).unwrap(), 0x00);
+
+        bus.write(0x401A, 0xFF).unwrap();
+        assert_eq!(bus.read(0x401B).unwrap(), 0xFF);
+    }
+
+    #[test]
+    fn test_whole_address_space_never_panics() {
+        let mut bus = Bus::from_cartridge(FlatCartridge::new(vec![]));
+
+        for cpu_test_mode in [false, true] {
+            bus.set_cpu_test_mode(cpu_test_mode);
+
+            for address in 0x0000..=0xFFFF {
+                bus.peek(address);
+                assert!(bus.read(address).is_ok(), "reading ${address:04X} failed");
+            }
+        }
+    }
+
+    #[test]
+    fn test_open_bus_unmapped_cartridge() {
+        let mut rom = vec![0x00; 0x10 + 0x4000 + 0x2000];
+        rom[..4].copy_from_slice(b"NES\x1A");
+        rom[4] = 1;
+        rom[5] = 1;
+
+        let cartridge = crate::rom::ines::InesFile::nrom_from_read(&mut std::io::Cursor::new(rom)).unwrap();
+        let mut bus = Bus::from_cartridge(cartridge);
+
+        bus.write(0x0000, 0x33).unwrap();
+
+        // The NROM has no PRG RAM, and peeking leaves the data bus as it is
+        assert_eq!(bus.peek(0x6000), 0x33);
+        assert_eq!(bus.read(0x6000).unwrap(), 0x33);
+        assert_eq!(bus.read(0x8000).unwrap(), 0x00);
+        assert_eq!(bus.peek(0x6000), 0x00);
+    }
+
+    #[test]
+    fn test_ram_init_random_is_reproducible() {
+        let ram_init = RamInitPattern::Random { seed: 0x5EED };
+
+        let bus = Bus::from_cartridge_with_ram_init(FlatCartridge::new(vec![]), ram_init);
+        let other_bus = Bus::from_cartridge_with_ram_init(FlatCartridge::new(vec![]), ram_init);
+        assert_eq!(bus.cpu_ram, other_bus.cpu_ram);
+
+        let other_seed = Bus::from_cartridge_with_ram_init(FlatCartridge::new(vec![]), RamInitPattern::Random { seed: 1 });
+        assert_ne!(bus.cpu_ram, other_seed.cpu_ram);
+    }
+
+    #[test]
+    fn test_ram_init_pattern() {
+        let mut bus = Bus::from_cartridge_with_ram_init(FlatCartridge::new(vec![]), RamInitPattern::Pattern(0xA5));
+
+        // The mirrors show the same fill
+        for address in CPU_RAM_WITH_MIRRORING_START_ADDRESS..=CPU_RAM_WITH_MIRRORING_END_ADDRESS {
+            assert_eq!(bus.read(address).unwrap(), 0xA5);
+        }
+
+        bus.init_ram(RamInitPattern::AllOnes);
+        assert!(bus.peek_range(0x0000, 0x2000).iter().all(|&value| value == 0xFF));
+    }
+
+    #[test]
+    fn test_cheats() {
+        let mut data = vec![0xEA; 0x2000];
+        data[0x11D9] = 0xCE;
+
+        let mut bus = Bus::from_cartridge(FlatCartridge::new(data));
+
+        let cheat = Cheat::from_game_genie("SXIOPO").unwrap();
+        bus.add_cheat(cheat);
+        assert_eq!(bus.list_cheats(), [cheat]);
+
+        assert_eq!(bus.read(0x91D9).unwrap(), 0xAD);
+        assert_eq!(bus.read(0x91DA).unwrap(), 0xEA);
+
+        // The peeks see the cartridge unless asked
+        assert_eq!(bus.peek(0x91D9), 0xCE);
+        assert_eq!(bus.peek_with_cheats(0x91D9), 0xAD);
+
+        // The writes reach the cartridge, whose value stays patched
+        bus.write(0x91D9, 0x00).unwrap();
+        assert_eq!(bus.peek(0x91D9), 0x00);
+        assert_eq!(bus.read(0x91D9).unwrap(), 0xAD);
+
+        assert!(bus.remove_cheat(&cheat));
+        assert!(!bus.remove_cheat(&cheat));
+        assert_eq!(bus.read(0x91D9).unwrap(), 0x00);
+    }
+
+    #[test]
+    fn test_cheat_compare() {
+        let mut bus = Bus::from_cartridge(FlatCartridge::new(vec![0x10, 0x20]));
+        bus.add_cheat(Cheat { address: 0x8000, value: 0x99, compare: Some(0x10) });
+        bus.add_cheat(Cheat { address: 0x8001, value: 0x99, compare: Some(0x10) });
+
+        assert_eq!(bus.read(0x8000).unwrap(), 0x99);
+
+        // The cartridge answers another value, so the cheat is bypassed
+        assert_eq!(bus.read(0x8001).unwrap(), 0x20);
+
+        // Only the cartridge space is patched
+        bus.add_cheat(Cheat { address: 0x0010, value: 0x99, compare: None });
+        assert_eq!(bus.read(0x0010).unwrap(), 0x00);
+    }
+
+    #[test]
+    fn test_cheat_precedence() {
+        let mut bus = Bus::from_cartridge(FlatCartridge::new(vec![0x10]));
+        bus.add_cheat(Cheat { address: 0x8000, value: 0x99, compare: None });
+
+        let newer_cheat = Cheat { address: 0x8000, value: 0x10, compare: Some(0x10) };
+        bus.add_cheat(newer_cheat);
+
+        // The newest cheat wins even when it writes back the value of the cartridge
+        assert_eq!(bus.read(0x8000).unwrap(), 0x10);
+        assert_eq!(bus.peek_with_cheats(0x8000), 0x10);
+
+        assert!(bus.remove_cheat(&newer_cheat));
+        assert_eq!(bus.read(0x8000).unwrap(), 0x99);
+    }
+
+    #[test]
+    fn test_hexdump() {
+        let mut bus = Bus::from_cartridge(FlatCartridge::new(vec![]));
+        bus.ram_mut()[..0x10].copy_from_slice(b"Hello, NES!\x00\x01\x7F\xFF~");
+        bus.ram_mut()[0x10..0x13].fill(0x41);
+
+        assert_eq!(
+            bus.hexdump(0x0000..0x0013),
+            "0000  48 65 6C 6C 6F 2C 20 4E  45 53 21 00 01 7F FF 7E  |Hello, NES!....~|\n\
+             0010  41 41 41                                          |AAA|\n"
+        );
+
+        assert!(bus.hexdump(0x0010..0x0010).is_empty());
+    }
+
+    #[test]
+    fn test_hexdump_across_ram_mirror() {
+        let mut bus = Bus::from_cartridge(FlatCartridge::new(vec![]));
+        bus.ram_mut()[0x7F8..].fill(0x11);
+        bus.ram_mut()[..0x08].fill(0x22);
+
+        // $0800 is the first mirror of $0000
+        assert_eq!(
+            bus.hexdump(0x07F8..0x0808),
+            "07F8  11 11 11 11 11 11 11 11  22 22 22 22 22 22 22 22  |........\"\"\"\"\"\"\"\"|\n"
+        );
+    }
+
+    #[test]
+    fn test_ram_dump_round_trip() {
+        let mut bus = Bus::from_cartridge_with_ram_init(FlatCartridge::new(vec![]), RamInitPattern::Random { seed: 7 });
+        let dump = *bus.ram();
+
+        bus.write(0x0123, !dump[0x0123]).unwrap();
+        assert_ne!(bus.ram(), &dump);
+
+        bus.load_ram(&dump);
+        assert_eq!(bus.ram(), &dump);
+        assert_eq!(bus.peek(0x0923), dump[0x0123]);
+    }
+
+    #[test]
+    fn test_read_u16_page_wrapped() {
+        let mut bus = Bus::from_cartridge(FlatCartridge::new(vec![]));
+        bus.write(0x02FF, 0x34).unwrap();
+        bus.write(0x0200, 0x12).unwrap();
+        bus.write(0x0300, 0x56).unwrap();
+
+        assert_eq!(bus.read_u16(0x02FF).unwrap(), 0x5634);
+        assert_eq!(bus.read_u16_page_wrapped(0x02FF).unwrap(), 0x1234);
+        assert_eq!(bus.peek_u16(0x02FF), 0x5634);
+        assert_eq!(bus.peek_u16_page_wrapped(0x02FF), 0x1234);
+
+        // Away from the end of a page both reads agree
+        assert_eq!(bus.read_u16_page_wrapped(0x02FE).unwrap(), bus.read_u16(0x02FE).unwrap());
+    }
+
+    #[test]
+    fn test_read_u16_vector() {
+        let mut bus = Bus::from_cartridge(FlatCartridge::new(vec![]).with_reset_vector(0xC123));
+
+        assert_eq!(bus.read_u16(0xFFFC).unwrap(), 0xC123);
+        assert_eq!(bus.peek_u16(0xFFFC), 0xC123);
+
+        // The upper byte of the last address wraps around to the start of the address space
+        bus.write(0x0000, 0xAB).unwrap();
+        assert_eq!(bus.peek_u16(0xFFFF), 0xABEA);
+    }
+
+    #[test]
+    fn test_peek_matches_read() {
+        let mut program = vec![0x00; 0x100];
+        program[0x42] = 0x99;
+
+        let mut cpu = Cpu::with_program(&program);
+        cpu.bus_mut().write(0x0123, 0x45).unwrap();
+
+        // $0923 mirrors $0123
+        for address in [0x0123, 0x0923, 0x8042, 0x8043, 0xFFFC] {
+            assert_eq!(cpu.peek(address), cpu.bus_mut().read(address).unwrap());
+        }
+
+        assert_eq!(cpu.peek_range(0x0922, 3), [0x00, 0x45, 0x00]);
+    }
+
+    #[test]
+    fn test_peek_range_wraps() {
+        let mut bus = Bus::from_cartridge(FlatCartridge::new(vec![]).with_reset_vector(0x1234));
+        bus.write(0x0000, 0x11).unwrap();
+        bus.write(0x0001, 0x22).unwrap();
+
+        assert_eq!(bus.peek_range(0xFFFC, 6), [0x34, 0x12, 0xEA, 0xEA, 0x11, 0x22]);
+        assert!(bus.peek_range(0x0000, 0).is_empty());
+    }
+
+    #[test]
+    fn test_peek_leaves_controller_as_is() {
+        let mut bus = Bus::from_cartridge(FlatCartridge::new(vec![]));
+
+        // A and B
+        bus.apu_io_registers_mut().set_controller_buttons(0, 0b0000_0011);
+        bus.write(0x4016, 0x01).unwrap();
+        bus.write(0x4016, 0x00).unwrap();
+
+        // The write left $00 on the data bus
+        for _ in 0..4 {
+            assert_eq!(bus.peek(0x4016), 0x01);
+        }
+
+        assert_eq!(bus.read(0x4016).unwrap(), 0x01);
+        assert_eq!(bus.read(0x4016).unwrap(), 0x01);
+        assert_eq!(bus.peek(0x4016), 0x00);
+        assert_eq!(bus.read(0x4016).unwrap(), 0x00);
+    }
 }
@@ -0,0 +1,213 @@
+//! Holds the structured trace of the bus, which hands every access to a callback or to a bounded
+//! buffer for the tools analyzing the activity of the bus.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::bus::Bus;
+use crate::cartridge::Cartridge;
+
+/// The kind of an access traced by the bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BusTraceKind {
+    /// A value was read from the bus.
+    Read,
+
+    /// A value was written to the bus.
+    Write,
+
+    /// A value was peeked from the bus, without side effects.
+    Peek,
+}
+
+/// An access traced by the bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BusTraceEvent {
+    /// The kind of access.
+    pub kind: BusTraceKind,
+
+    /// The accessed address, as seen by the CPU before any mirroring.
+    pub address: u16,
+
+    /// The value read, written or peeked.
+    pub value: u8,
+
+    /// The number of cycles the CPU had run before the cycle doing the access.
+    pub cpu_cycle: u64,
+}
+
+/// Where the traced accesses are sent.
+pub(super) enum BusTraceSink {
+    /// A callback called with every access.
+    Callback(Box<dyn FnMut(BusTraceEvent)>),
+
+    /// A buffer keeping the last accesses, dropping the oldest ones past its capacity.
+    Buffer {
+        /// The accesses not taken yet, the oldest first.
+        events: VecDeque<BusTraceEvent>,
+
+        /// The number of accesses kept.
+        capacity: usize,
+    },
+}
+
+impl BusTraceSink {
+    /// Send an access to the sink.
+    pub(super) fn send(&mut self, event: BusTraceEvent) {
+        match self {
+            BusTraceSink::Callback(callback) => callback(event),
+            BusTraceSink::Buffer { events, capacity } => {
+                if *capacity == 0 {
+                    return;
+                }
+
+                if events.len() == *capacity {
+                    events.pop_front();
+                }
+
+                events.push_back(event);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for BusTraceSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BusTraceSink::Callback(_) => f.write_str("Callback"),
+            BusTraceSink::Buffer { events, capacity } => {
+                write!(f, "Buffer({}/{capacity})", events.len())
+            }
+        }
+    }
+}
+
+impl<C: Cartridge> Bus<C> {
+    /// Call the given callback with every access to the bus, peeks included, replacing the
+    /// previous sink if any.
+    pub fn set_trace_callback(&mut self, callback: impl FnMut(BusTraceEvent) + 'static) {
+        *self.trace_sink.get_mut() = Some(BusTraceSink::Callback(Box::new(callback)));
+    }
+
+    /// Keep the last accesses to the bus, peeks included, up to the given number of them, to be
+    /// taken with [Bus::take_trace_events], replacing the previous sink if any.
+    pub fn set_trace_buffer(&mut self, capacity: usize) {
+        *self.trace_sink.get_mut() = Some(BusTraceSink::Buffer {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+        });
+    }
+
+    /// Stop tracing the accesses to the bus, dropping the ones not taken.
+    pub fn clear_trace_sink(&mut self) {
+        *self.trace_sink.get_mut() = None;
+    }
+
+    /// Take the accesses kept by the buffer set with [Bus::set_trace_buffer], the oldest first,
+    /// empty if the accesses are not kept.
+    pub fn take_trace_events(&mut self) -> Vec<BusTraceEvent> {
+        match self.trace_sink.get_mut() {
+            Some(BusTraceSink::Buffer { events, .. }) => events.drain(..).collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Send an access of the given kind to the trace sink, if any.
+    pub(super) fn trace_access(&self, kind: BusTraceKind, address: u16, value: u8) {
+        // The peeks borrow the bus immutably, so the sink is behind a cell
+        if let Some(sink) = self.trace_sink.borrow_mut().as_mut() {
+            sink.send(BusTraceEvent {
+                kind,
+                address,
+                value,
+                cpu_cycle: self.cpu_cycle,
+            });
+        }
+    }
+
+    /// Set the number of cycles the CPU has run before the cycle it's about to run, stamped on the
+    /// traced accesses.
+    pub(crate) fn set_cpu_cycle(&mut self, cpu_cycle: u64) {
+        self.cpu_cycle = cpu_cycle;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::testing::FlatCartridge;
+    use crate::cpu::Cpu;
+
+    /// Build a read or a write event.
+    fn event(kind: BusTraceKind, address: u16, value: u8, cpu_cycle: u64) -> BusTraceEvent {
+        BusTraceEvent { kind, address, value, cpu_cycle }
+    }
+
+    #[test]
+    fn test_trace_jsr() {
+        // JSR $8010
+        let mut cpu = Cpu::with_program(&[0x20, 0x10, 0x80]);
+        cpu.bus_mut().set_trace_buffer(16);
+
+        for _ in 0..6 {
+            cpu.cycle().unwrap();
+        }
+
+        use BusTraceKind::*;
+
+        // The return address pushed is the one of the last byte of the instruction
+        assert_eq!(
+            cpu.bus_mut().take_trace_events(),
+            [
+                event(Read, 0x8000, 0x20, 7),
+                event(Read, 0x8001, 0x10, 8),
+                event(Read, 0x01FD, 0x00, 9),
+                event(Write, 0x01FD, 0x80, 10),
+                event(Write, 0x01FC, 0x02, 11),
+                event(Read, 0x8002, 0x80, 12),
+            ]
+        );
+
+        assert_eq!(cpu.program_counter(), 0x8010);
+    }
+
+    #[test]
+    fn test_trace_callback_and_peeks() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut bus = Bus::from_cartridge(FlatCartridge::new(vec![0xA9]));
+        let events = Rc::new(RefCell::new(vec![]));
+
+        let sink = events.clone();
+        bus.set_trace_callback(move |event| sink.borrow_mut().push(event));
+
+        bus.write(0x0010, 0x42).unwrap();
+        bus.peek(0x8000);
+
+        assert_eq!(
+            *events.borrow(),
+            [event(BusTraceKind::Write, 0x0010, 0x42, 0), event(BusTraceKind::Peek, 0x8000, 0xA9, 0)]
+        );
+
+        bus.clear_trace_sink();
+        bus.read(0x0010).unwrap();
+        assert_eq!(events.borrow().len(), 2);
+    }
+
+    #[test]
+    fn test_trace_buffer_is_bounded() {
+        let mut bus = Bus::from_cartridge(FlatCartridge::new(vec![]));
+        bus.set_trace_buffer(2);
+
+        for address in 0..4 {
+            bus.write(address, address as u8).unwrap();
+        }
+
+        let addresses: Vec<u16> = bus.take_trace_events().iter().map(|event| event.address).collect();
+        assert_eq!(addresses, [0x0002, 0x0003]);
+        assert!(bus.take_trace_events().is_empty());
+    }
+}
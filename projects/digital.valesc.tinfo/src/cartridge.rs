@@ -3,7 +3,33 @@
 
 use thiserror::Error;
 
-pub(crate) mod nrom;
+pub mod nrom;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
+
+/// The layout of the four nametables of the PPU memory map on the VRAM of the PPU, wired by the
+/// cartridge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mirroring {
+    /// The nametables at `$2000` and `$2400` share the first table, and the ones at `$2800` and
+    /// `$2C00` the second one, for the games scrolling vertically.
+    #[default]
+    Horizontal,
+
+    /// The nametables at `$2000` and `$2800` share the first table, and the ones at `$2400` and
+    /// `$2C00` the second one, for the games scrolling horizontally.
+    Vertical,
+
+    /// The four nametables share the first table.
+    SingleScreenLow,
+
+    /// The four nametables share the second table.
+    SingleScreenHigh,
+
+    /// The four nametables are distinct, stored on RAM of the cartridge accessed through
+    /// [Cartridge::ppu_read] and [Cartridge::ppu_write].
+    FourScreen,
+}
 
 /// The [Cartridge] trait provides an implementation of the hardware of a NES cartridge,
 /// both in its static and dynamic behaviors.
@@ -19,12 +45,139 @@ pub trait Cartridge {
     /// calls below `0x4020` may not be handled by the implementor.
     unsafe fn read(&self, address: u16) -> Result<u8, CartridgeError>;
 
+    /// Read data from the cartridge without the side effects a read may have on the mapper chip,
+    /// for debugging and disassembling purposes.
+    ///
+    /// By default the data is read with [Cartridge::read], as most mappers have no side effects on reads.
+    ///
+    /// # Safety
+    /// The given `address` is relative to the NES CPU global memory map,
+    /// calls below `0x4020` may not be handled by the implementor.
+    unsafe fn peek(&self, address: u16) -> Result<u8, CartridgeError> {
+        self.read(address)
+    }
+
     /// Write data to the cartridge.
     ///
     /// # Safety
     /// The given `address` is relative to the NES CPU global memory map,
     /// calls below `0x4020` may not be handled by the implementor.
     unsafe fn write(&mut self, _address: u16, _value: u8) -> Result<(), CartridgeError>;
+
+    /// Read data from the cartridge on behalf of the PPU, usually from the CHR ROM or RAM holding
+    /// the pattern tables.
+    ///
+    /// By default nothing is mapped to the PPU.
+    ///
+    /// # Safety
+    /// The given `address` is relative to the NES PPU memory map,
+    /// calls from `0x2000` to `0x2FFF` are only done with [Mirroring::FourScreen],
+    /// and calls from `0x3000` may not be handled by the implementor.
+    unsafe fn ppu_read(&self, _address: u16) -> Result<u8, CartridgeError> {
+        Err(CartridgeError::OpenBus)
+    }
+
+    /// Read data from the cartridge on behalf of the PPU without the side effects a read may have
+    /// on the mapper chip, for debugging purposes.
+    ///
+    /// By default the data is read with [Cartridge::ppu_read].
+    ///
+    /// # Safety
+    /// The given `address` is relative to the NES PPU memory map,
+    /// calls from `0x2000` to `0x2FFF` are only done with [Mirroring::FourScreen],
+    /// and calls from `0x3000` may not be handled by the implementor.
+    unsafe fn ppu_peek(&self, address: u16) -> Result<u8, CartridgeError> {
+        self.ppu_read(address)
+    }
+
+    /// Write data to the cartridge on behalf of the PPU, which only lands on CHR RAM or on the
+    /// nametables of [Mirroring::FourScreen].
+    ///
+    /// By default nothing is mapped to the PPU.
+    ///
+    /// # Safety
+    /// The given `address` is relative to the NES PPU memory map,
+    /// calls from `0x2000` to `0x2FFF` are only done with [Mirroring::FourScreen],
+    /// and calls from `0x3000` may not be handled by the implementor.
+    unsafe fn ppu_write(&mut self, _address: u16, _value: u8) -> Result<(), CartridgeError> {
+        Err(CartridgeError::CannotWrite("Nothing is mapped to the PPU by the cartridge"))
+    }
+
+    /// Get the layout of the nametables wired by the cartridge, which the mapper chip may change
+    /// while running.
+    ///
+    /// By default the nametables are mirrored horizontally.
+    fn mirroring(&self) -> Mirroring {
+        Mirroring::Horizontal
+    }
+
+    /// Check if the cartridge maps the whole address space of the CPU, taking the place of the RAM
+    /// and the registers of the console, like the flat memories the test suites of the CPU expect.
+    ///
+    /// By default only the addresses from `0x4020` are mapped to the cartridge.
+    fn maps_whole_address_space(&self) -> bool {
+        false
+    }
+
+    /// Get the state of the cartridge that changes while running, like the registers of the mapper
+    /// chip or the PRG RAM, to be restored later with [Cartridge::load_state].
+    ///
+    /// The ROM is not part of the state. By default the cartridge has no state.
+    fn save_state(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    /// Restore a state given by [Cartridge::save_state].
+    ///
+    /// By default the cartridge has no state, so only an empty one is accepted.
+    fn load_state(&mut self, state: &[u8]) -> Result<(), CartridgeError> {
+        match state.is_empty() {
+            true => Ok(()),
+            false => Err(CartridgeError::InvalidState("The cartridge has no state to restore")),
+        }
+    }
+}
+
+impl<T: Cartridge + ?Sized> Cartridge for Box<T> {
+    unsafe fn read(&self, address: u16) -> Result<u8, CartridgeError> {
+        (**self).read(address)
+    }
+
+    unsafe fn peek(&self, address: u16) -> Result<u8, CartridgeError> {
+        (**self).peek(address)
+    }
+
+    unsafe fn write(&mut self, address: u16, value: u8) -> Result<(), CartridgeError> {
+        (**self).write(address, value)
+    }
+
+    unsafe fn ppu_read(&self, address: u16) -> Result<u8, CartridgeError> {
+        (**self).ppu_read(address)
+    }
+
+    unsafe fn ppu_peek(&self, address: u16) -> Result<u8, CartridgeError> {
+        (**self).ppu_peek(address)
+    }
+
+    unsafe fn ppu_write(&mut self, address: u16, value: u8) -> Result<(), CartridgeError> {
+        (**self).ppu_write(address, value)
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        (**self).mirroring()
+    }
+
+    fn maps_whole_address_space(&self) -> bool {
+        (**self).maps_whole_address_space()
+    }
+
+    fn save_state(&self) -> Vec<u8> {
+        (**self).save_state()
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> Result<(), CartridgeError> {
+        (**self).load_state(state)
+    }
 }
 
 #[derive(Error, Debug)]
@@ -37,4 +190,12 @@ pub enum CartridgeError {
     #[error("Unable to read data from the cartridge: {0}")]
     /// Unable to read data from the cartridge.
     CannotWrite(&'static str),
+
+    #[error("Unable to restore the state of the cartridge: {0}")]
+    /// Unable to restore the state of the cartridge.
+    InvalidState(&'static str),
+
+    #[error("Nothing is mapped at the address by the cartridge")]
+    /// Nothing is mapped at the address by the cartridge, leaving the last value on the data bus.
+    OpenBus,
 }
@@ -1,39 +1,69 @@
 //! Holds the implementation of a NROM based cartridge.
 
-use crate::cartridge::{Cartridge, CartridgeError};
+use crate::cartridge::{Cartridge, CartridgeError, Mirroring};
 use crate::rom::Rom;
 use crate::BYTES_ON_A_KIBIBYTE;
 
+/// The size in bytes of the CHR ROM or RAM of a NROM cartridge, holding both pattern tables.
+const CHR_SIZE: usize = 8 * BYTES_ON_A_KIBIBYTE;
+
+/// The size in bytes of the RAM holding the four nametables of [Mirroring::FourScreen].
+const FOUR_SCREEN_RAM_SIZE: usize = 4 * BYTES_ON_A_KIBIBYTE;
+
+/// The first address of the nametables on the PPU memory map.
+const NAMETABLES_START_ADDRESS: u16 = 0x2000;
+
 /// Implementation for the cartridges that uses the NROM mapper chip.
 ///
 /// # TODO
 /// Currently support for [Family Basic](https://en.wikipedia.org/wiki/Family_BASIC)
 /// is not available due to missing PRG RAM implementation.
-pub(crate) struct Nrom {
+pub struct Nrom {
     /// Dynamically holds the ROM of the cartridge.
     rom: Box<dyn Rom>,
 
     /// If the cartridge has 32KiB or 16KiB of PRG ROM size,
     /// the later enables mirroring of the ROM addresses.
     has_32_kibibytes_prg_rom_capacity: bool,
+
+    /// The CHR RAM holding the pattern tables when the ROM has no CHR ROM.
+    chr_ram: Option<Vec<u8>>,
+
+    /// The layout of the nametables, wired on the board.
+    mirroring: Mirroring,
+
+    /// The RAM holding the nametables with [Mirroring::FourScreen].
+    four_screen_ram: Option<Vec<u8>>,
 }
 
 impl Nrom {
     /// Create a new NROM cartridge
-    pub(crate) fn new<T: Rom + 'static>(has_32_kibibytes_prg_rom_capacity: bool, rom: T) -> Nrom {
+    pub(crate) fn new<T: Rom + 'static>(has_32_kibibytes_prg_rom_capacity: bool, mirroring: Mirroring, rom: T) -> Nrom {
+        let chr_ram = match rom.chr_rom_size() {
+            0 => Some(vec![0; CHR_SIZE]),
+            _ => None,
+        };
+
+        let four_screen_ram = match mirroring {
+            Mirroring::FourScreen => Some(vec![0; FOUR_SCREEN_RAM_SIZE]),
+            _ => None,
+        };
+
         Nrom {
             rom: Box::new(rom),
             has_32_kibibytes_prg_rom_capacity,
+            chr_ram,
+            mirroring,
+            four_screen_ram,
         }
     }
 }
 
 impl Cartridge for Nrom {
     unsafe fn read(&self, address: u16) -> Result<u8, CartridgeError> {
+        // There is no PRG RAM below the PRG ROM
         if address < 0x8000 {
-            return Err(CartridgeError::CannotRead(
-                "On a NROM memory mapper read operations below 0x800 are undefined behavior",
-            ));
+            return Err(CartridgeError::OpenBus);
         }
 
         let address = address as usize - 0x8000;
@@ -50,6 +80,63 @@ impl Cartridge for Nrom {
             "Write operations cannot be done with a NROM memory mapper",
         ))
     }
+
+    unsafe fn ppu_read(&self, address: u16) -> Result<u8, CartridgeError> {
+        if address >= NAMETABLES_START_ADDRESS {
+            let four_screen_ram = self.four_screen_ram.as_ref().ok_or(CartridgeError::OpenBus)?;
+
+            return Ok(four_screen_ram[address as usize % FOUR_SCREEN_RAM_SIZE]);
+        }
+
+        let index = address as usize % CHR_SIZE;
+
+        match &self.chr_ram {
+            Some(chr_ram) => Ok(chr_ram[index]),
+            None => Ok(self.rom.read_chr_data(index)),
+        }
+    }
+
+    unsafe fn ppu_write(&mut self, address: u16, value: u8) -> Result<(), CartridgeError> {
+        let (ram, size) = match address >= NAMETABLES_START_ADDRESS {
+            true => (&mut self.four_screen_ram, FOUR_SCREEN_RAM_SIZE),
+            false => (&mut self.chr_ram, CHR_SIZE),
+        };
+
+        let Some(ram) = ram else {
+            return Err(CartridgeError::CannotWrite("The CHR ROM of a NROM cartridge cannot be written"));
+        };
+
+        ram[address as usize % size] = value;
+
+        Ok(())
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    /// Save the CHR RAM and the RAM of the four nametables, if any.
+    fn save_state(&self) -> Vec<u8> {
+        [&self.chr_ram, &self.four_screen_ram].into_iter().flatten().flatten().copied().collect()
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> Result<(), CartridgeError> {
+        let rams: Vec<&mut Vec<u8>> = [&mut self.chr_ram, &mut self.four_screen_ram].into_iter().flatten().collect();
+
+        if state.len() != rams.iter().map(|ram| ram.len()).sum::<usize>() {
+            return Err(CartridgeError::InvalidState("The RAM state of the NROM cartridge has the wrong size"));
+        }
+
+        let mut state = state;
+
+        for ram in rams {
+            let (ram_state, rest) = state.split_at(ram.len());
+            ram.copy_from_slice(ram_state);
+            state = rest;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -75,11 +162,19 @@ mod tests {
 
             MockRom::MOCK_VALUE_ON_LOWER_HALF
         }
+
+        fn chr_rom_size(&self) -> usize {
+            0
+        }
+
+        fn read_chr_data(&self, _index: usize) -> u8 {
+            unreachable!("The mock ROM has CHR RAM")
+        }
     }
 
     #[test]
     fn test_write_protection() {
-        let mut nrom_cartridge = Nrom::new(true, MockRom {});
+        let mut nrom_cartridge = Nrom::new(true, Mirroring::Horizontal, MockRom {});
 
         unsafe {
             assert!(nrom_cartridge.write(INVALID_NROM_ADDRESS, 0).is_err());
@@ -94,14 +189,14 @@ mod tests {
 
     #[test]
     fn test_read_below_prg_protection() {
-        let nrom_cartridge = Nrom::new(true, MockRom {});
+        let nrom_cartridge = Nrom::new(true, Mirroring::Horizontal, MockRom {});
 
-        unsafe { assert!(nrom_cartridge.read(INVALID_NROM_ADDRESS).is_err()) }
+        unsafe { assert!(matches!(nrom_cartridge.read(INVALID_NROM_ADDRESS), Err(CartridgeError::OpenBus))) }
     }
 
     #[test]
     fn test_read_on_32k() {
-        let nrom_cartridge = Nrom::new(true, MockRom {});
+        let nrom_cartridge = Nrom::new(true, Mirroring::Horizontal, MockRom {});
 
         assert_eq!(
             unsafe { nrom_cartridge.read(NROM_FIRST_ROM_BANK_ADDRESS).unwrap() },
@@ -116,7 +211,7 @@ mod tests {
 
     #[test]
     fn test_read_on_16k() {
-        let nrom_cartridge = Nrom::new(false, MockRom {});
+        let nrom_cartridge = Nrom::new(false, Mirroring::Horizontal, MockRom {});
 
         assert_eq!(
             unsafe { nrom_cartridge.read(NROM_FIRST_ROM_BANK_ADDRESS).unwrap() },
@@ -128,4 +223,49 @@ mod tests {
             MockRom::MOCK_VALUE_ON_LOWER_HALF
         );
     }
+
+    #[test]
+    fn test_chr_ram() {
+        let mut nrom_cartridge = Nrom::new(true, Mirroring::Horizontal, MockRom {});
+
+        unsafe {
+            nrom_cartridge.ppu_write(0x1FFF, 0x42).unwrap();
+            assert_eq!(nrom_cartridge.ppu_read(0x1FFF).unwrap(), 0x42);
+            assert_eq!(nrom_cartridge.ppu_read(0x0000).unwrap(), 0x00);
+        }
+
+        // The CHR RAM is the state of the cartridge
+        let state = nrom_cartridge.save_state();
+        let mut restored = Nrom::new(true, Mirroring::Horizontal, MockRom {});
+        restored.load_state(&state).unwrap();
+
+        unsafe { assert_eq!(restored.ppu_read(0x1FFF).unwrap(), 0x42) }
+        assert!(restored.load_state(&[]).is_err());
+    }
+
+    #[test]
+    fn test_four_screen_ram() {
+        let mut nrom_cartridge = Nrom::new(true, Mirroring::FourScreen, MockRom {});
+        assert_eq!(nrom_cartridge.mirroring(), Mirroring::FourScreen);
+
+        unsafe {
+            nrom_cartridge.ppu_write(0x2C00, 0x42).unwrap();
+            assert_eq!(nrom_cartridge.ppu_read(0x2C00).unwrap(), 0x42);
+            assert_eq!(nrom_cartridge.ppu_read(0x2000).unwrap(), 0x00);
+            assert_eq!(nrom_cartridge.ppu_read(0x0C00).unwrap(), 0x00);
+        }
+
+        // Both RAMs are the state of the cartridge
+        let state = nrom_cartridge.save_state();
+        assert_eq!(state.len(), CHR_SIZE + FOUR_SCREEN_RAM_SIZE);
+
+        let mut restored = Nrom::new(true, Mirroring::FourScreen, MockRom {});
+        restored.load_state(&state).unwrap();
+
+        unsafe { assert_eq!(restored.ppu_read(0x2C00).unwrap(), 0x42) }
+
+        // Without it the nametables are on the PPU
+        let nrom_cartridge = Nrom::new(true, Mirroring::Vertical, MockRom {});
+        unsafe { assert!(matches!(nrom_cartridge.ppu_read(0x2000), Err(CartridgeError::OpenBus))) }
+    }
 }
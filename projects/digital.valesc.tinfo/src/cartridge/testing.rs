@@ -0,0 +1,260 @@
+//! Holds the cartridges meant for the tests of the CPU, available to other crates with the
+//! `test-utils` feature.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use crate::cartridge::{Cartridge, CartridgeError, Mirroring};
+use crate::cpu::{IRQ_VECTOR_ADDRESS, NMI_VECTOR_ADDRESS, RESET_VECTOR_ADDRESS};
+use crate::{build_address, U16Ex};
+
+/// The address the data of a [FlatCartridge] starts at unless told otherwise.
+pub const DEFAULT_BASE_ADDRESS: u16 = 0x8000;
+
+/// The value read from the addresses a [FlatCartridge] has no data for, a `NOP`.
+const FILL_VALUE: u8 = 0xEA;
+
+/// The size in bytes of the RAM of a [FlatCartridge] seen by the PPU, holding both pattern tables
+/// and the four nametables of [Mirroring::FourScreen].
+const PPU_RAM_SIZE: usize = 0x3000;
+
+/// An access done by the CPU to a [FlatCartridge].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlatAccess {
+    /// A read of the given address.
+    Read(u16),
+
+    /// A write of the given value to the given address.
+    Write(u16, u8),
+}
+
+/// A cartridge serving the given data from a base address, `$8000` by default, with every other
+/// address filled with `NOP`s.
+///
+/// The reset vector points to the base address unless the data covers it, and the vectors can be
+/// set on their own with [FlatCartridge::with_reset_vector], [FlatCartridge::with_nmi_vector] and
+/// [FlatCartridge::with_irq_vector], taking precedence over the data.
+///
+/// The writes land on a RAM overlay read back in place of the data, and every access but the
+/// peeks is logged, see [FlatCartridge::accesses]. The PPU sees 8 KiB of CHR RAM followed by the
+/// RAM of the four nametables, left out of the accesses and of the state, and the nametables are
+/// mirrored horizontally unless told otherwise with [FlatCartridge::with_mirroring].
+#[derive(Debug, Clone)]
+pub struct FlatCartridge {
+    /// The data served from the base address.
+    data: Vec<u8>,
+
+    /// The address of the first byte of the data.
+    base_address: u16,
+
+    /// The values written to the cartridge, by address.
+    overlay: BTreeMap<u16, u8>,
+
+    /// The vectors set on their own, by address.
+    vectors: BTreeMap<u16, u16>,
+
+    /// The log of the accesses, shared with the handles given by [FlatCartridge::accesses].
+    accesses: Rc<RefCell<Vec<FlatAccess>>>,
+
+    /// The RAM seen by the PPU.
+    ppu_ram: Vec<u8>,
+
+    /// The layout of the nametables.
+    mirroring: Mirroring,
+}
+
+impl FlatCartridge {
+    /// Create a new [FlatCartridge] serving the given data from `$8000`.
+    pub fn new(data: Vec<u8>) -> FlatCartridge {
+        FlatCartridge {
+            data,
+            base_address: DEFAULT_BASE_ADDRESS,
+            overlay: BTreeMap::new(),
+            vectors: BTreeMap::new(),
+            accesses: Rc::new(RefCell::new(vec![])),
+            ppu_ram: vec![0; PPU_RAM_SIZE],
+            mirroring: Mirroring::Horizontal,
+        }
+    }
+
+    /// Serve the data from the given address instead, which should not be below `$4020` as the
+    /// bus only maps the addresses from there to the cartridge.
+    pub fn with_base_address(mut self, base_address: u16) -> FlatCartridge {
+        self.base_address = base_address;
+        self
+    }
+
+    /// Lay the nametables out with the given mirroring instead.
+    pub fn with_mirroring(mut self, mirroring: Mirroring) -> FlatCartridge {
+        self.mirroring = mirroring;
+        self
+    }
+
+    /// Point the reset vector to the given address.
+    pub fn with_reset_vector(self, address: u16) -> FlatCartridge {
+        self.with_vector(RESET_VECTOR_ADDRESS, address)
+    }
+
+    /// Point the NMI vector to the given address.
+    pub fn with_nmi_vector(self, address: u16) -> FlatCartridge {
+        self.with_vector(NMI_VECTOR_ADDRESS, address)
+    }
+
+    /// Point the IRQ and `BRK` vector to the given address.
+    pub fn with_irq_vector(self, address: u16) -> FlatCartridge {
+        self.with_vector(IRQ_VECTOR_ADDRESS, address)
+    }
+
+    /// Get a shared handle to the log of accesses done to the cartridge, which keeps growing while
+    /// the cartridge is used.
+    pub fn accesses(&self) -> Rc<RefCell<Vec<FlatAccess>>> {
+        self.accesses.clone()
+    }
+
+    /// Point the vector at the given address to the given target.
+    fn with_vector(mut self, vector_address: u16, address: u16) -> FlatCartridge {
+        self.vectors.insert(vector_address, address);
+        self
+    }
+
+    /// Get the value stored at the given address.
+    fn value(&self, address: u16) -> u8 {
+        if let Some(&value) = self.overlay.get(&address) {
+            return value;
+        }
+
+        // The lower byte of a vector comes first
+        if let Some(&target) = self.vectors.get(&address) {
+            return target.lower_byte();
+        }
+
+        if let Some(&target) = self.vectors.get(&address.wrapping_sub(1)) {
+            return target.upper_byte();
+        }
+
+        match self.data.get(address.wrapping_sub(self.base_address) as usize) {
+            Some(&value) => value,
+            None if address == RESET_VECTOR_ADDRESS => self.base_address.lower_byte(),
+            None if address == RESET_VECTOR_ADDRESS + 1 => self.base_address.upper_byte(),
+            None => FILL_VALUE,
+        }
+    }
+}
+
+impl Cartridge for FlatCartridge {
+    unsafe fn read(&self, address: u16) -> Result<u8, CartridgeError> {
+        self.accesses.borrow_mut().push(FlatAccess::Read(address));
+
+        Ok(self.value(address))
+    }
+
+    unsafe fn peek(&self, address: u16) -> Result<u8, CartridgeError> {
+        Ok(self.value(address))
+    }
+
+    unsafe fn write(&mut self, address: u16, value: u8) -> Result<(), CartridgeError> {
+        self.accesses.borrow_mut().push(FlatAccess::Write(address, value));
+        self.overlay.insert(address, value);
+
+        Ok(())
+    }
+
+    unsafe fn ppu_read(&self, address: u16) -> Result<u8, CartridgeError> {
+        Ok(self.ppu_ram[address as usize % PPU_RAM_SIZE])
+    }
+
+    unsafe fn ppu_write(&mut self, address: u16, value: u8) -> Result<(), CartridgeError> {
+        self.ppu_ram[address as usize % PPU_RAM_SIZE] = value;
+
+        Ok(())
+    }
+
+    fn mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    /// Save the RAM overlay, as the address and the value of every byte written.
+    fn save_state(&self) -> Vec<u8> {
+        self.overlay
+            .iter()
+            .flat_map(|(&address, &value)| [address.lower_byte(), address.upper_byte(), value])
+            .collect()
+    }
+
+    fn load_state(&mut self, state: &[u8]) -> Result<(), CartridgeError> {
+        if !state.len().is_multiple_of(3) {
+            return Err(CartridgeError::InvalidState("The RAM overlay state is truncated"));
+        }
+
+        self.overlay = state
+            .chunks_exact(3)
+            .map(|chunk| (build_address(chunk[0], chunk[1]), chunk[2]))
+            .collect();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flat_cartridge_data() {
+        let cartridge = FlatCartridge::new(vec![0xA9, 0x01]).with_base_address(0xC000);
+
+        unsafe {
+            assert_eq!(cartridge.peek(0xC000).unwrap(), 0xA9);
+            assert_eq!(cartridge.peek(0xC001).unwrap(), 0x01);
+            assert_eq!(cartridge.peek(0xC002).unwrap(), FILL_VALUE);
+            assert_eq!(cartridge.peek(0x8000).unwrap(), FILL_VALUE);
+
+            // The reset vector points to the base address
+            assert_eq!(cartridge.peek(RESET_VECTOR_ADDRESS).unwrap(), 0x00);
+            assert_eq!(cartridge.peek(RESET_VECTOR_ADDRESS + 1).unwrap(), 0xC0);
+        }
+    }
+
+    #[test]
+    fn test_flat_cartridge_vectors() {
+        let mut data = vec![0xEA; 0x8000];
+        data[0x7FFA..0x8000].copy_from_slice(&[0x11, 0x11, 0x22, 0x22, 0x33, 0x33]);
+
+        let cartridge = FlatCartridge::new(data).with_nmi_vector(0x9000).with_irq_vector(0xA000);
+
+        unsafe {
+            assert_eq!(cartridge.peek(NMI_VECTOR_ADDRESS).unwrap(), 0x00);
+            assert_eq!(cartridge.peek(NMI_VECTOR_ADDRESS + 1).unwrap(), 0x90);
+            assert_eq!(cartridge.peek(IRQ_VECTOR_ADDRESS + 1).unwrap(), 0xA0);
+
+            // The vectors not set come from the data
+            assert_eq!(cartridge.peek(RESET_VECTOR_ADDRESS).unwrap(), 0x22);
+        }
+    }
+
+    #[test]
+    fn test_flat_cartridge_overlay() {
+        let mut cartridge = FlatCartridge::new(vec![0x00; 4]);
+        let accesses = cartridge.accesses();
+
+        unsafe {
+            cartridge.write(0x8001, 0x42).unwrap();
+            assert_eq!(cartridge.read(0x8001).unwrap(), 0x42);
+            assert_eq!(cartridge.peek(0x8000).unwrap(), 0x00);
+        }
+
+        assert_eq!(*accesses.borrow(), [FlatAccess::Write(0x8001, 0x42), FlatAccess::Read(0x8001)]);
+
+        // The overlay is the state of the cartridge
+        let state = cartridge.save_state();
+        let mut restored = FlatCartridge::new(vec![0x00; 4]);
+        restored.load_state(&state).unwrap();
+
+        unsafe {
+            assert_eq!(restored.peek(0x8001).unwrap(), 0x42);
+        }
+
+        assert!(restored.load_state(&state[..2]).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! Holds the cheats patching the values the CPU reads from the cartridge, along with the decoder
+//! of the Game Genie codes.
+
+use thiserror::Error;
+
+/// The letters of the Game Genie codes, by the value they stand for.
+const GAME_GENIE_LETTERS: &[u8; 16] = b"APZLGITYEOXUKSVN";
+
+/// The address added to the ones given by the Game Genie codes, which only patch the PRG ROM.
+const GAME_GENIE_BASE_ADDRESS: u16 = 0x8000;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+/// Errors that may happen when decoding a Game Genie code.
+pub enum CheatError {
+    #[error("A Game Genie code has 6 or 8 letters, not {0}")]
+    /// The code has neither 6 nor 8 letters.
+    InvalidLength(usize),
+
+    #[error("The letter {0:?} is not used by the Game Genie codes")]
+    /// The code holds a letter outside of `APZLGITYEOXUKSVN`.
+    InvalidLetter(char),
+}
+
+/// A patch of the value the CPU reads from an address of the cartridge, like the Game Genie does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cheat {
+    /// The address whose reads are patched.
+    pub address: u16,
+
+    /// The value read in place of the one of the cartridge.
+    pub value: u8,
+
+    /// The value the cartridge must answer for the read to be patched, if any, so a code only
+    /// hits the bank it was made for.
+    pub compare: Option<u8>,
+}
+
+impl Cheat {
+    /// Decode a Game Genie code of 6 letters, or 8 with a compare value, ignoring the case of the
+    /// letters.
+    ///
+    /// ```
+    /// use tinfo::cheat::Cheat;
+    ///
+    /// // The infinite lives of Super Mario Bros.
+    /// let cheat = Cheat::from_game_genie("SXIOPO").unwrap();
+    /// assert_eq!(cheat, Cheat { address: 0x91D9, value: 0xAD, compare: None });
+    /// ```
+    pub fn from_game_genie(code: &str) -> Result<Cheat, CheatError> {
+        let letters = code
+            .chars()
+            .map(|letter| {
+                GAME_GENIE_LETTERS
+                    .iter()
+                    .position(|&candidate| candidate as char == letter.to_ascii_uppercase())
+                    .map(|value| value as u16)
+                    .ok_or(CheatError::InvalidLetter(letter))
+            })
+            .collect::<Result<Vec<u16>, CheatError>>()?;
+
+        if letters.len() != 6 && letters.len() != 8 {
+            return Err(CheatError::InvalidLength(letters.len()));
+        }
+
+        // The bits of the address and the values are scrambled across the letters
+        let n = |index: usize| letters[index];
+
+        let address = GAME_GENIE_BASE_ADDRESS
+            | ((n(3) & 0x7) << 12)
+            | ((n(5) & 0x7) << 8)
+            | ((n(4) & 0x8) << 8)
+            | ((n(2) & 0x7) << 4)
+            | ((n(1) & 0x8) << 4)
+            | (n(4) & 0x7)
+            | (n(3) & 0x8);
+
+        let value_without_last_bit = ((n(1) & 0x7) << 4) | ((n(0) & 0x8) << 4) | (n(0) & 0x7);
+
+        let (value, compare) = match letters.len() {
+            6 => (value_without_last_bit | (n(5) & 0x8), None),
+            _ => {
+                let compare = ((n(7) & 0x7) << 4) | ((n(6) & 0x8) << 4) | (n(6) & 0x7) | (n(5) & 0x8);
+
+                (value_without_last_bit | (n(7) & 0x8), Some(compare as u8))
+            }
+        };
+
+        Ok(Cheat {
+            address,
+            value: value as u8,
+            compare,
+        })
+    }
+
+    /// Check if the cheat patches the reads of its address, given the value answered by the
+    /// cartridge.
+    pub(crate) fn matches(&self, value: u8) -> bool {
+        self.compare.is_none_or(|compare| compare == value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_six_letters() {
+        assert_eq!(
+            Cheat::from_game_genie("sxiopo").unwrap(),
+            Cheat { address: 0x91D9, value: 0xAD, compare: None }
+        );
+    }
+
+    #[test]
+    fn test_decode_eight_letters() {
+        // Every letter stands for its position, so the scrambled bits can be told apart
+        let cheat = Cheat::from_game_genie("APZLGITY").unwrap();
+        assert_eq!(cheat, Cheat { address: 0xB524, value: 0x10, compare: Some(0x76) });
+    }
+
+    #[test]
+    fn test_decode_invalid_codes() {
+        assert_eq!(Cheat::from_game_genie("SXIOP"), Err(CheatError::InvalidLength(5)));
+        assert_eq!(Cheat::from_game_genie("SXIOPB"), Err(CheatError::InvalidLetter('B')));
+    }
+
+    #[test]
+    fn test_matches_compare() {
+        let cheat = Cheat { address: 0x8000, value: 0x42, compare: Some(0x10) };
+
+        assert!(cheat.matches(0x10));
+        assert!(!cheat.matches(0x11));
+        assert!(Cheat { compare: None, ..cheat }.matches(0x11));
+    }
+}
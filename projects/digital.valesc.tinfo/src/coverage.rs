@@ -0,0 +1,220 @@
+//! Holds the coverage recorder, which tells the addresses of the CPU memory space ever executed,
+//! read or written.
+
+use std::io::{self, Write};
+
+/// The number of addresses of the CPU memory space.
+const ADDRESSES: usize = 0x10000;
+
+/// The number of addresses tracked by every word of a bitmap.
+const ADDRESSES_PER_WORD: usize = u64::BITS as usize;
+
+/// The bit of an address in the [binary export](CoverageRecorder::to_bytes) set if it was executed.
+pub const EXECUTED_BIT: u8 = 1 << 0;
+
+/// The bit of an address in the [binary export](CoverageRecorder::to_bytes) set if it was read.
+pub const READ_BIT: u8 = 1 << 1;
+
+/// The bit of an address in the [binary export](CoverageRecorder::to_bytes) set if it was written.
+pub const WRITTEN_BIT: u8 = 1 << 2;
+
+/// A bit for every address of the CPU memory space.
+#[derive(Debug, Clone, PartialEq)]
+struct Bitmap {
+    /// The bits, the one of an address at its position inside the word of its group.
+    words: Vec<u64>,
+}
+
+impl Bitmap {
+    /// Create a new [Bitmap] with every bit cleared.
+    fn new() -> Bitmap {
+        Bitmap {
+            words: vec![0; ADDRESSES / ADDRESSES_PER_WORD],
+        }
+    }
+
+    /// Set the bit of the given address.
+    fn set(&mut self, address: u16) {
+        let address = address as usize;
+        self.words[address / ADDRESSES_PER_WORD] |= 1 << (address % ADDRESSES_PER_WORD);
+    }
+
+    /// Check if the bit of the given address is set.
+    fn get(&self, address: u16) -> bool {
+        let address = address as usize;
+        self.words[address / ADDRESSES_PER_WORD] & (1 << (address % ADDRESSES_PER_WORD)) != 0
+    }
+}
+
+/// Records the addresses of the CPU memory space that were executed as part of an instruction,
+/// and the ones read or written through the bus, enabled with
+/// [Cpu::enable_coverage](crate::cpu::Cpu::enable_coverage).
+///
+/// The addresses are the ones seen by the CPU, before any mirroring or bank switching. The peeks
+/// done for debugging are not recorded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageRecorder {
+    /// The addresses holding the opcode or the operand of an instruction that started running.
+    executed: Bitmap,
+
+    /// The addresses read through the bus, including the dummy reads and the opcode fetches.
+    read: Bitmap,
+
+    /// The addresses written through the bus, including the dummy writes.
+    written: Bitmap,
+}
+
+impl CoverageRecorder {
+    /// Create a new [CoverageRecorder] with no address recorded.
+    pub fn new() -> CoverageRecorder {
+        CoverageRecorder {
+            executed: Bitmap::new(),
+            read: Bitmap::new(),
+            written: Bitmap::new(),
+        }
+    }
+
+    /// Check if the given address held the opcode or the operand of an instruction that ran.
+    pub fn is_executed(&self, address: u16) -> bool {
+        self.executed.get(address)
+    }
+
+    /// Check if the given address was read through the bus.
+    pub fn is_read(&self, address: u16) -> bool {
+        self.read.get(address)
+    }
+
+    /// Check if the given address was written through the bus.
+    pub fn is_written(&self, address: u16) -> bool {
+        self.written.get(address)
+    }
+
+    /// Get the bits of the given address, a combination of [EXECUTED_BIT], [READ_BIT] and
+    /// [WRITTEN_BIT].
+    pub fn bits(&self, address: u16) -> u8 {
+        let mut bits = 0;
+
+        if self.is_executed(address) {
+            bits |= EXECUTED_BIT;
+        }
+
+        if self.is_read(address) {
+            bits |= READ_BIT;
+        }
+
+        if self.is_written(address) {
+            bits |= WRITTEN_BIT;
+        }
+
+        bits
+    }
+
+    /// Export the coverage as a byte for every address of the CPU memory space, from `$0000` to
+    /// `$FFFF`, holding its [bits](CoverageRecorder::bits).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        (0..=u16::MAX).map(|address| self.bits(address)).collect()
+    }
+
+    /// Export the coverage as text, a line for every run of consecutive addresses with the same
+    /// coverage, leaving out the ones never accessed.
+    ///
+    /// Every line holds the first and the last address of the run followed by `x`, `r` and `w`
+    /// if the addresses were executed, read or written, or `-` in their place, like
+    /// `8000-8003 xr-`.
+    pub fn write_text<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let mut run: Option<(u16, u16, u8)> = None;
+
+        for address in 0..=u16::MAX {
+            let bits = self.bits(address);
+
+            run = match run {
+                Some((start, _, run_bits)) if run_bits == bits => Some((start, address, bits)),
+
+                _ => {
+                    if let Some(run) = run {
+                        write_run(writer, run)?;
+                    }
+
+                    (bits != 0).then_some((address, address, bits))
+                }
+            };
+        }
+
+        match run {
+            Some(run) => write_run(writer, run),
+            None => Ok(()),
+        }
+    }
+
+    /// Record the bytes of an instruction of the given length starting at the given address as
+    /// executed, wrapping around the end of the address space.
+    pub(crate) fn mark_executed(&mut self, address: u16, length: u8) {
+        for offset in 0..length as u16 {
+            self.executed.set(address.wrapping_add(offset));
+        }
+    }
+
+    /// Record the given address as read.
+    pub(crate) fn mark_read(&mut self, address: u16) {
+        self.read.set(address);
+    }
+
+    /// Record the given address as written.
+    pub(crate) fn mark_written(&mut self, address: u16) {
+        self.written.set(address);
+    }
+}
+
+impl Default for CoverageRecorder {
+    fn default() -> Self {
+        CoverageRecorder::new()
+    }
+}
+
+/// Write a line of the [text export](CoverageRecorder::write_text) for the given run of addresses
+/// and their bits.
+fn write_run<W: Write>(writer: &mut W, (start, end, bits): (u16, u16, u8)) -> io::Result<()> {
+    let flag = |bit, letter| if bits & bit != 0 { letter } else { '-' };
+
+    writeln!(
+        writer,
+        "{start:04X}-{end:04X} {}{}{}",
+        flag(EXECUTED_BIT, 'x'),
+        flag(READ_BIT, 'r'),
+        flag(WRITTEN_BIT, 'w')
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_text() {
+        let mut coverage = CoverageRecorder::new();
+        coverage.mark_executed(0xFFFE, 3);
+        coverage.mark_read(0xFFFE);
+        coverage.mark_read(0xFFFF);
+        coverage.mark_written(0x0010);
+
+        let mut text = vec![];
+        coverage.write_text(&mut text).unwrap();
+
+        // The instruction wraps around the end of the address space
+        assert_eq!(String::from_utf8(text).unwrap(), "0000-0000 x--\n0010-0010 --w\nFFFE-FFFF xr-\n");
+    }
+
+    #[test]
+    fn test_to_bytes() {
+        let mut coverage = CoverageRecorder::new();
+        coverage.mark_executed(0x8000, 2);
+        coverage.mark_read(0x8000);
+
+        let bytes = coverage.to_bytes();
+
+        assert_eq!(bytes.len(), 0x10000);
+        assert_eq!(bytes[0x8000], EXECUTED_BIT | READ_BIT);
+        assert_eq!(bytes[0x8001], EXECUTED_BIT);
+        assert_eq!(bytes.iter().filter(|&&bits| bits != 0).count(), 2);
+    }
+}
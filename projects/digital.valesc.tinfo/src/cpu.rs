@@ -1,22 +1,64 @@
 //! Holds the implementation of the modified 2A03 CPU used by the NES.
 
+mod addressing;
+mod add_with_carry;
 mod jump;
+mod load_accumulator;
 mod load_x_register;
+mod load_y_register;
+mod store_accumulator;
 mod store_x_register;
+mod store_y_register;
 mod subroutine;
 mod no_operation;
 mod flags;
 mod branching;
+mod subtract_with_carry;
+mod logical_inclusive_or;
+mod compare;
+mod bit_test;
+mod arithmetic_shift_left;
+mod rotate;
+mod increment_decrement;
+mod transfer;
+mod stack;
+mod interrupt;
+mod unofficial;
+pub(crate) mod opcode;
+mod cache;
+mod mnemonic;
+mod watchpoint;
+mod save_state;
+mod dma;
+mod coverage;
+mod call_stack;
+mod history;
+mod builder;
+mod freeze;
+#[cfg(any(test, feature = "test-utils"))]
+mod program;
 
-use core::panic;
 use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
 
 use bitflags::bitflags;
-use log::{error, trace};
+use log::trace;
 use thiserror::Error;
 
-use crate::bus::{Bus, BusError};
+use crate::bus::{Bus, BusAccess, BusError};
 use crate::cartridge::Cartridge;
+pub use crate::cpu::addressing::AddressingMode;
+pub use crate::cpu::builder::{CpuBuilder, CpuBuilderError};
+pub use crate::cpu::call_stack::{CallFrame, CallKind};
+pub use crate::cpu::freeze::FreezeError;
+pub use crate::cpu::history::{HistoryEntry, InstructionHistory};
+pub use crate::cpu::mnemonic::Mnemonic;
+pub use crate::cpu::save_state::SaveState;
+use crate::cpu::cache::InstructionCache;
+use crate::cpu::dma::OamDma;
+use crate::cpu::history::HistoryDump;
+use crate::cpu::opcode::OpcodeInfo;
 
 bitflags! {
     #[derive(Clone, Copy, PartialEq, Debug)]
@@ -50,11 +92,67 @@ bitflags! {
     }
 }
 
+impl CpuStatusFlags {
+    /// Build the flags from the raw value of the status register, like the nestest logs show it.
+    ///
+    /// The stub flag can't be cleared on the CPU, so it's always set.
+    pub fn from_p_byte(value: u8) -> CpuStatusFlags {
+        CpuStatusFlags::from_bits_retain(value) | CpuStatusFlags::Stub
+    }
+
+    /// Get the raw value of the status register as it's pushed to the stack by `PHP`, with both the
+    /// B and the stub flags set.
+    pub fn to_p_byte(self) -> u8 {
+        (self | CpuStatusFlags::B | CpuStatusFlags::Stub).bits()
+    }
+}
+
+impl fmt::Display for CpuStatusFlags {
+    /// Format the flags in the `NV-BDIZC` form, with the letters of the cleared flags in lowercase.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags = [
+            (CpuStatusFlags::Negative, 'N'),
+            (CpuStatusFlags::Overflow, 'V'),
+            (CpuStatusFlags::Stub, '-'),
+            (CpuStatusFlags::B, 'B'),
+            (CpuStatusFlags::Decimal, 'D'),
+            (CpuStatusFlags::InterruptsDisabled, 'I'),
+            (CpuStatusFlags::Zero, 'Z'),
+            (CpuStatusFlags::Carry, 'C'),
+        ];
+
+        for (flag, letter) in flags {
+            match self.contains(flag) {
+                true => write!(f, "{letter}")?,
+                false => write!(f, "{}", letter.to_ascii_lowercase())?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// The address to the first byte of the stack in the bus memory space.
 const STACK_ADDRESS: u16 = 0x0100;
 
+/// The address of the lower byte of the vector holding the address of the NMI handler.
+pub(crate) const NMI_VECTOR_ADDRESS: u16 = 0xFFFA;
+
+/// The address of the lower byte of the vector holding the address the CPU starts running from.
+pub(crate) const RESET_VECTOR_ADDRESS: u16 = 0xFFFC;
+
+/// The number of cycles of the reset sequence, as long as the one of the other interrupts.
+const RESET_SEQUENCE_CYCLES: u64 = 7;
+
+/// The address of the lower byte of the vector holding the address of the IRQ and BRK handler.
+pub(crate) const IRQ_VECTOR_ADDRESS: u16 = 0xFFFE;
+
 /// The 2A03 CPU used by the NES.
-pub struct Cpu {
+///
+/// The cartridge is boxed by default, as the one loaded from an iNES file, a concrete [Cartridge]
+/// can be given instead with [Cpu::from_cartridge] so the accesses to it are dispatched statically
+/// and can be inlined.
+pub struct Cpu<C: Cartridge = Box<dyn Cartridge>> {
     /// Accumulator register, also know as register `A`, used by some ALU operations.
     accumulator: u8,
 
@@ -73,17 +171,55 @@ pub struct Cpu {
     /// The address of the next instruction to process.
     program_counter: u16,
 
-    current_instruction: Instruction,
+    /// The opcode of the instruction that is being run.
+    current_opcode: u8,
+
+    /// The address the instruction that is being run was fetched from.
+    current_instruction_address: u16,
+
+    /// The metadata of the instruction that is being run, which may be an interrupt sequence.
+    current_instruction: OpcodeInfo<C>,
+
+    /// The cycle of the current instruction that is going to be run next, starting at 1.
     current_instruction_cycle: u8,
 
-    bus: Bus,
+    /// The memory bus the CPU is attached to.
+    bus: Bus<C>,
 
     /// The 2A05 CPU can access data retrived from previous cycles of the same instruction,
     /// cycles can store here well-known internal data.
-    cache: Vec<u8>,
+    cache: InstructionCache,
 
     /// The number of cycles the CPU has already executed.
-    cpu_cycles: u16,
+    cpu_cycles: u64,
+
+    /// The opcode and its address if the CPU has been jammed by one of the halting opcodes.
+    jammed: Option<(u8, u16)>,
+
+    /// If an edge on the NMI line has been detected and is waiting to be serviced.
+    nmi_pending: bool,
+
+    /// If the IRQ line is asserted, requesting an interrupt while it stays so.
+    irq_line: bool,
+
+    /// The OAM DMA stalling the CPU, if any.
+    oam_dma: Option<OamDma>,
+
+    /// The shadow call stack, if the calls are followed.
+    call_stack: Option<Vec<CallFrame>>,
+
+    /// The history of the last instructions fetched, if it's kept.
+    history: Option<InstructionHistory>,
+
+    /// If the snapshots get the disassembly of their instruction, which costs an allocation and
+    /// some extra reads of the bus per instruction.
+    trace: bool,
+
+    /// The events raised while running the cycles, waiting to be taken.
+    events: Vec<CpuEvent>,
+
+    /// The values rewritten to their address before every instruction, by address.
+    frozen: BTreeMap<u16, u8>,
 }
 
 #[derive(Error, Debug)]
@@ -96,29 +232,86 @@ pub enum CpuError {
     #[error("Running the cycle failed: {0}")]
     /// Accessing the bus failed
     InstructionError(#[from] CycleError),
+
+    #[error(
+        "Running the cycle {cycle} of {mnemonic} (opcode {opcode:02X}) at ${program_counter:04X} failed: {source}{}",
+        HistoryDump(.history)
+    )]
+    /// A cycle of an instruction failed after its opcode was fetched
+    CycleFailed {
+        /// The address the failing instruction was fetched from.
+        program_counter: u16,
+
+        /// The opcode of the failing instruction, the one of `BRK` for the interrupt sequences.
+        opcode: u8,
+
+        /// The mnemonic of the failing instruction, prefixed by `*` when the opcode is unofficial.
+        mnemonic: String,
+
+        /// The cycle of the instruction that failed, starting at 1.
+        cycle: u8,
+
+        /// The reason the cycle failed.
+        source: CycleError,
+
+        /// The innermost frames of the shadow call stack, from the innermost one, empty unless
+        /// it's enabled with [Cpu::enable_call_stack].
+        call_stack: Vec<CallFrame>,
+
+        /// The newest entries of the history of the instructions, ending with the failing one,
+        /// empty unless it's enabled with [Cpu::enable_history].
+        history: Vec<HistoryEntry>,
+    },
+
+    #[error("The opcode {opcode:02X} at ${program_counter:04X} is not supported{}", HistoryDump(.history))]
+    /// The CPU fetched an opcode it doesn't support
+    UnsupportedOpcode {
+        /// The unsupported opcode.
+        opcode: u8,
+
+        /// The address of the unsupported opcode.
+        program_counter: u16,
+
+        /// The innermost frames of the shadow call stack, from the innermost one, empty unless
+        /// it's enabled with [Cpu::enable_call_stack].
+        call_stack: Vec<CallFrame>,
+
+        /// The newest entries of the history of the instructions, ending with the failing one,
+        /// empty unless it's enabled with [Cpu::enable_history].
+        history: Vec<HistoryEntry>,
+    },
+
+    #[error("The CPU jammed running the opcode {opcode:02X} at ${program_counter:04X}{}", HistoryDump(.history))]
+    /// The CPU ran an opcode that halts it until it is reset
+    Jammed {
+        /// The opcode that jammed the CPU.
+        opcode: u8,
+
+        /// The address of the opcode that jammed the CPU.
+        program_counter: u16,
+
+        /// The innermost frames of the shadow call stack, from the innermost one, empty unless
+        /// it's enabled with [Cpu::enable_call_stack].
+        call_stack: Vec<CallFrame>,
+
+        /// The newest entries of the history of the instructions, ending with the failing one,
+        /// empty unless it's enabled with [Cpu::enable_history].
+        history: Vec<HistoryEntry>,
+    },
 }
 
-#[derive(Debug)]
-// To much of a hassle to document all of them
-#[allow(clippy::missing_docs_in_private_items)]
-/// The different instructions that the CPU can run.
-enum Instruction {
-    Stub,
-    JumpAbsolute,
-    LoadXRegisterImmediate,
-    StoreXRegisterZeroPage,
-    JumpToSubroutineAbsolute,
-    NoOperationImplied,
-    SetCarryFlagImplied,
-    ClearCarryFlagImplied,
-    BranchIfCarrySetRelative,
-    BranchIfCarryClearRelative,
-    BranchIfEqual,
-    BranchIfNotEqual,
-    BranchIfOverflowSet,
-    BranchIfOverflowClear,
-    BranchIfPositive,
-    BranchIfMinus,
+/// What a cycle run by [Cpu::cycle] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CycleResult {
+    /// The opcode of a new instruction was fetched, or an interrupt sequence was started in its
+    /// place.
+    Dispatched,
+
+    /// The instruction in flight ran one of its cycles.
+    Continued,
+
+    /// The CPU was stalled by the OAM DMA.
+    Stalled,
 }
 
 #[derive(Debug)]
@@ -128,42 +321,94 @@ pub struct CpuSnapshot {
     pub accumulator: u8,
     pub register_x: u8,
     pub register_y: u8,
-    pub status: u8,
+    pub status: CpuStatusFlags,
     pub stack_pointer: u8,
     pub program_counter: u16,
     pub opcode: u8,
     pub instruction_data: InstructionData,
-    pub cpy_cycles: u16,
+    pub cpu_cycles: u64,
+    pub ppu_scanline: u16,
+    pub ppu_dot: u16,
 }
 
 impl CpuSnapshot {
     /// Make a new [CpuSnapshot].
-    fn new(cpu: &Cpu) -> Result<CpuSnapshot, BusError> {
-        Ok(CpuSnapshot {
+    fn new<C: Cartridge>(cpu: &Cpu<C>) -> CpuSnapshot {
+        CpuSnapshot {
             accumulator: cpu.accumulator,
             register_x: cpu.register_x,
             register_y: cpu.register_y,
-            status: cpu.status.bits(),
+            status: cpu.status,
             stack_pointer: cpu.stack_pointer,
             program_counter: cpu.program_counter,
-            opcode: cpu.read_program_counter()?,
+            opcode: cpu.bus.peek(cpu.program_counter),
             instruction_data: InstructionData {
                 arg_1: None,
                 arg_2: None,
                 idle_cycles: 0,
                 assembly: String::new(),
+                mnemonic: Mnemonic::Nop,
+                is_official: true,
+                addressing_mode: AddressingMode::Implied,
+                length: 1,
+                bytes: [0x00; 3],
             },
-            cpy_cycles: cpu.cpu_cycles
-        })
+            cpu_cycles: cpu.cpu_cycles,
+            ppu_scanline: cpu.bus.ppu().scanline(),
+            ppu_dot: cpu.bus.ppu().dot(),
+        }
+    }
+
+    /// Get the number of bytes taken by the instruction, including the opcode.
+    pub fn length(&self) -> u8 {
+        self.instruction_data.length
+    }
+
+    /// Get the bytes of the instruction, starting with the opcode.
+    pub fn bytes(&self) -> &[u8] {
+        &self.instruction_data.bytes[..self.length() as usize]
+    }
+}
+
+impl fmt::Display for CpuSnapshot {
+    /// Format the snapshot as a line of the nestest logs.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = self.bytes().iter().map(|byte| format!("{byte:02X}")).collect::<Vec<_>>().join(" ");
+
+        // Like nestest, unofficial opcodes have their `*` prefix take the place of a separator space
+        let assembly = &self.instruction_data.assembly;
+        let separator = if assembly.starts_with('*') { "" } else { " " };
+
+        write!(
+            f,
+            "{:04X}  {bytes:<8} {:<33}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} PPU:{:>3},{:>3} CYC:{}",
+            self.program_counter,
+            format!("{separator}{assembly}"),
+            self.accumulator,
+            self.register_x,
+            self.register_y,
+            self.status.bits(),
+            self.stack_pointer,
+            self.ppu_scanline,
+            self.ppu_dot,
+            self.cpu_cycles,
+        )
     }
 }
 
 #[derive(Debug)]
 /// Data of the running instruction,.
 pub struct InstructionData {
-    /// The assembly code that represents the instruction.
+    /// The assembly code that represents the instruction, empty if the tracing of the CPU is
+    /// disabled.
     pub assembly: String,
 
+    /// The mnemonic of the instruction.
+    pub mnemonic: Mnemonic,
+
+    /// If the opcode is part of the documented instruction set of the 6502.
+    pub is_official: bool,
+
     /// The number of extra cycles is instruction is going to take.
     pub idle_cycles: u8,
 
@@ -172,6 +417,75 @@ pub struct InstructionData {
 
     /// The second "argument" given to the instruction, if it uses one.
     pub arg_2: Option<u8>,
+
+    /// The way the instruction gets its operand.
+    pub addressing_mode: AddressingMode,
+
+    /// The number of bytes taken by the instruction, including the opcode.
+    pub length: u8,
+
+    /// The bytes of the instruction, starting with the opcode, with the ones past its length set to zero.
+    pub bytes: [u8; 3],
+}
+
+/// Something worth stopping the emulation for that happened while running a cycle, for debugging
+/// purposes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuEvent {
+    /// An access to the bus hit one of the watchpoints.
+    Watchpoint {
+        /// The accessed address.
+        address: u16,
+
+        /// The value read or written.
+        value: u8,
+
+        /// The kind of access.
+        access: BusAccess,
+
+        /// The address of the instruction that did the access.
+        program_counter: u16,
+
+        /// The number of cycles the CPU had run before the one doing the access.
+        cycles: u64,
+    },
+
+    /// A return didn't jump to the address expected by the shadow call stack, or there was no call
+    /// to return from.
+    CallStackMismatch {
+        /// The address of the `RTS` or `RTI`.
+        program_counter: u16,
+
+        /// The return address of the popped frame, [None] if the call stack was empty.
+        expected: Option<u16>,
+
+        /// The address the return jumped to.
+        actual: u16,
+
+        /// The number of cycles the CPU had run when the return ended.
+        cycles: u64,
+    },
+}
+
+/// The interrupts waiting for the running instruction to end, given by [Cpu::pending_interrupts].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterruptState {
+    /// If an edge on the NMI line has been latched and not serviced yet.
+    pub nmi_pending: bool,
+
+    /// If the IRQ line is asserted.
+    pub irq_line: bool,
+
+    /// If the interrupt disable flag is set, masking the IRQ.
+    pub irq_masked: bool,
+}
+
+impl InterruptState {
+    /// Check if an interrupt sequence will run in place of the next instruction, unless the state
+    /// changes before the running instruction ends.
+    pub fn is_pending(&self) -> bool {
+        self.nmi_pending || (self.irq_line && !self.irq_masked)
+    }
 }
 
 #[derive(Error, Debug)]
@@ -184,6 +498,26 @@ pub enum CycleError {
     #[error("Accessing the bus failed: {0}")]
     /// Accessing the bus failed
     BusError(#[from] BusError),
+
+    #[error("The opcode {opcode:02X} at ${program_counter:04X} is not supported")]
+    /// The opcode is not supported by the CPU
+    UnsupportedOpcode {
+        /// The unsupported opcode.
+        opcode: u8,
+
+        /// The address of the unsupported opcode.
+        program_counter: u16,
+    },
+
+    #[error("The opcode {opcode:02X} at ${program_counter:04X} jams the CPU, which runs none of its cycles")]
+    /// The cycles of one of the opcodes jamming the CPU were asked for
+    Jammed {
+        /// The opcode jamming the CPU.
+        opcode: u8,
+
+        /// The address of the opcode.
+        program_counter: u16,
+    },
 }
 
 /// Macro to implement the cycles of an instruction. Takes the name of the variable of the CPU struct (usually `cpu`),
@@ -199,7 +533,7 @@ pub enum CycleError {
 ///
 ///    2, false => {
 ///        cpu.cache.push(cpu.read_program_counter()?);
-///        cpu.program_counter += 1;
+///        cpu.advance_program_counter(1);
 ///    },
 ///
 ///    3, true => {
@@ -219,7 +553,7 @@ macro_rules! impl_instruction_cycles {
         $function_name: ident,
         $($cycle_num: expr, $is_finish: expr => $cycle:expr),*,
     ) => {
-        impl Cpu {
+        impl<C: $crate::cartridge::Cartridge> Cpu<C> {
             $(#[$($attrss)*])*
             pub(crate) fn $function_name(&mut self) -> Result<bool, CycleError> {
                 #[allow(unused_mut)]
@@ -244,68 +578,207 @@ macro_rules! impl_instruction_cycles {
 pub(crate) use impl_instruction_cycles;
 
 impl Cpu {
-    /// Create a new [Cpu].
-    pub fn new(cartridge: Box<dyn Cartridge>) -> Cpu {
-        Cpu::new_with_program_counter(cartridge, 0x8000)
+    /// Create a new [Cpu] with the program counter set to the address in the reset vector of the
+    /// cartridge.
+    pub fn new(cartridge: Box<dyn Cartridge>) -> Result<Cpu, BusError> {
+        Cpu::from_cartridge(cartridge)
     }
 
     /// Create a new [Cpu] with the program counter set to the given value.
     pub fn new_with_program_counter(cartridge: Box<dyn Cartridge>, program_counter: u16) -> Cpu {
-        Self {
+        Cpu::from_cartridge_with_program_counter(cartridge, program_counter)
+    }
+}
+
+impl<C: Cartridge> Cpu<C> {
+    /// Create a new [Cpu] holding the given cartridge as is, with the program counter set to the
+    /// address in the reset vector of the cartridge.
+    pub fn from_cartridge(cartridge: C) -> Result<Cpu<C>, BusError> {
+        let mut cpu = Cpu::from_cartridge_with_program_counter(cartridge, 0);
+        cpu.program_counter = cpu.bus.read_u16(RESET_VECTOR_ADDRESS)?;
+
+        Ok(cpu)
+    }
+
+    /// Create a new [Cpu] holding the given cartridge as is, with the program counter set to the
+    /// given value.
+    pub fn from_cartridge_with_program_counter(cartridge: C, program_counter: u16) -> Cpu<C> {
+        let mut cpu = Self {
             accumulator: 0,
             register_x: 0,
             register_y: 0,
 
-            status: CpuStatusFlags::Decimal | CpuStatusFlags::B,
+            // The B flag only exists on the pushed copies of the status
+            status: CpuStatusFlags::InterruptsDisabled | CpuStatusFlags::Stub,
             stack_pointer: 0xFD,
             program_counter,
 
-            current_instruction: Instruction::Stub,
+            current_opcode: 0xEA,
+            current_instruction_address: program_counter,
+            current_instruction: Self::OPCODES[0xEA].unwrap(),
             current_instruction_cycle: 1,
 
-            bus: Bus::new(cartridge),
-            cache: vec![],
+            bus: Bus::from_cartridge(cartridge),
+            cache: InstructionCache::default(),
+
+            cpu_cycles: 0,
+
+            jammed: None,
+            nmi_pending: false,
+            irq_line: false,
+            oam_dma: None,
+            call_stack: None,
+            history: None,
 
-            cpu_cycles: 6,
+            trace: true,
+            events: vec![],
+            frozen: BTreeMap::new(),
+        };
+
+        // The power-on runs the reset sequence before the first instruction
+        cpu.clock_reset_sequence();
+
+        cpu
+    }
+
+    /// Run a cycle of the CPU, telling if it started a new instruction.
+    ///
+    /// Once the CPU has been jammed every cycle fails with [CpuError::Jammed] without running.
+    /// The events raised by the cycle can be taken with [Cpu::take_events].
+    ///
+    /// No [CpuSnapshot] is taken, use [Cpu::cycle_with_snapshot] to get the one of the started
+    /// instructions.
+    pub fn cycle(&mut self) -> Result<CycleResult, CpuError> {
+        self.clocked_cycle(false).map(|(result, _)| result)
+    }
+
+    /// Run a cycle of the CPU like [Cpu::cycle], returning the snapshot taken before running the
+    /// instruction it started, if any.
+    ///
+    /// The snapshot peeks the bus and, with the tracing enabled, disassembles the instruction, so
+    /// it's meant for tracers and debuggers rather than for the emulation loop.
+    pub fn cycle_with_snapshot(&mut self) -> Result<Option<CpuSnapshot>, CpuError> {
+        self.clocked_cycle(true).map(|(_, snapshot)| snapshot)
+    }
+
+    /// Run a cycle of the CPU, clocking the bus and collecting the events it raised, taking the
+    /// snapshot of the started instruction if asked to.
+    fn clocked_cycle(&mut self, take_snapshot: bool) -> Result<(CycleResult, Option<CpuSnapshot>), CpuError> {
+        let cycles = self.cpu_cycles;
+        self.bus.set_cpu_cycle(cycles);
+
+        let result = self.run_cycle(take_snapshot);
+
+        // A jammed CPU doesn't run the cycle
+        if self.cpu_cycles != cycles {
+            self.bus.tick();
+
+            if self.bus.take_nmi_request() {
+                self.raise_nmi();
+            }
         }
+
+        self.collect_watchpoint_hits(cycles);
+
+        result
     }
 
-    /// Run a cycle of the CPU.
-    pub fn cycle(&mut self) -> Result<Option<CpuSnapshot>, CpuError> {
+    /// Run a cycle of the CPU, without collecting the events it raised.
+    fn run_cycle(&mut self, take_snapshot: bool) -> Result<(CycleResult, Option<CpuSnapshot>), CpuError> {
+        if let Some((opcode, program_counter)) = self.jammed {
+            return Err(CpuError::Jammed {
+                opcode,
+                program_counter,
+                call_stack: self.error_call_stack(),
+                history: self.error_history(),
+            });
+        }
+
         trace!("PC: {:04X}", self.program_counter);
-        self.cpu_cycles += 1;
 
+        if self.oam_dma_cycle()? {
+            return Ok((CycleResult::Stalled, None));
+        }
+
+        // The snapshot holds the state before running the instruction, cycle count included
         if self.current_instruction_cycle == 1 {
-            let mut snapshot = CpuSnapshot::new(self)?;
+            self.apply_frozen();
+
+            let mut snapshot = match take_snapshot {
+                true => Some(CpuSnapshot::new(self)),
+                false => None,
+            };
+
+            self.cpu_cycles += 1;
+
+            let interrupt = self.take_pending_interrupt();
+
+            let info = if let Some(interrupt) = interrupt {
+                // The fetched opcode is discarded and the one of BRK is forced in its place
+                let _ = self.read_program_counter()?;
+                self.current_opcode = 0x00;
+
+                interrupt
+            } else {
+                let opcode = self.bus.read(self.program_counter)?;
+                self.record_history(self.cpu_cycles - 1);
+
+                let Some(info) = Self::OPCODES[opcode as usize] else {
+                    return Err(CpuError::UnsupportedOpcode {
+                        opcode,
+                        program_counter: self.program_counter,
+                        call_stack: self.error_call_stack(),
+                        history: self.error_history(),
+                    });
+                };
+
+                if info.is_jam() {
+                    self.jammed = Some((opcode, self.program_counter));
+
+                    return Err(CpuError::Jammed {
+                        opcode,
+                        program_counter: self.program_counter,
+                        call_stack: self.error_call_stack(),
+                        history: self.error_history(),
+                    });
+                }
+
+                self.current_opcode = opcode;
+                self.bus.mark_executed(self.program_counter, info.addressing_mode.length());
+
+                info
+            };
+
+            self.current_instruction = info;
+            self.current_instruction_address = self.program_counter;
+
+            if let Some(snapshot) = &mut snapshot {
+                snapshot.instruction_data = self.instruction_data(&info);
+            }
 
-            self.current_instruction = Self::dispatch_opcode(self.bus.read(self.program_counter)?);
-            
-            snapshot.instruction_data = self.dispatch_instruction()?;
+            // The interrupted instruction runs once the handler returns
+            if interrupt.is_none() {
+                self.advance_program_counter(1);
+            }
 
-            self.program_counter += 1;
             self.current_instruction_cycle += 1;
 
-            return Ok(Some(snapshot));
-        }
-
-        let instruction_ended = match self.current_instruction {
-            Instruction::JumpAbsolute => self.jump_absolute_cycles(),
-            Instruction::LoadXRegisterImmediate => self.load_x_register_immediate_cycles(),
-            Instruction::StoreXRegisterZeroPage => self.store_x_register_zero_page_cycles(),
-            Instruction::JumpToSubroutineAbsolute => self.jump_to_subroutine_absolute_cycles(),
-            Instruction::NoOperationImplied => self.no_operation_cycles(),
-            Instruction::SetCarryFlagImplied => self.set_carry_flag_implied_cycles(),
-            Instruction::ClearCarryFlagImplied => self.clear_carry_flag_implied_cycles(),
-            Instruction::BranchIfCarrySetRelative => self.branch_cycles(CpuStatusFlags::Carry, false),
-            Instruction::BranchIfCarryClearRelative => self.branch_cycles(CpuStatusFlags::Carry, true),
-            Instruction::BranchIfEqual => self.branch_cycles(CpuStatusFlags::Zero, false),
-            Instruction::BranchIfNotEqual => self.branch_cycles(CpuStatusFlags::Zero, true),
-            Instruction::BranchIfOverflowSet => self.branch_cycles(CpuStatusFlags::Overflow, false),
-            Instruction::BranchIfOverflowClear => self.branch_cycles(CpuStatusFlags::Overflow, true),
-            Instruction::BranchIfMinus => self.branch_cycles(CpuStatusFlags::Negative, false),
-            Instruction::BranchIfPositive => self.branch_cycles(CpuStatusFlags::Negative, true),
-            Instruction::Stub => panic!("The stub instruction should never go beyond step 1!"),
-        }?;
+            return Ok((CycleResult::Dispatched, snapshot));
+        }
+
+        self.cpu_cycles += 1;
+
+        let info = self.current_instruction;
+        let instruction_ended =
+            (info.handler)(self, info.addressing_mode).map_err(|source| CpuError::CycleFailed {
+                program_counter: self.current_instruction_address,
+                opcode: self.current_opcode,
+                mnemonic: info.assembly_mnemonic(),
+                cycle: self.current_instruction_cycle,
+                source,
+                call_stack: self.error_call_stack(),
+                history: self.error_history(),
+            })?;
 
         self.current_instruction_cycle += 1;
 
@@ -315,61 +788,174 @@ impl Cpu {
             self.cache.clear();
         }
 
-        Ok(None)
+        Ok((CycleResult::Continued, None))
+    }
+
+    /// Run the cycles of a whole instruction, returning the snapshot taken before running it.
+    ///
+    /// If an instruction is already in flight it's finished first, so the returned snapshot is
+    /// always the one of a new instruction. The cycles of an OAM DMA waiting to run are run before
+    /// the instruction.
+    pub fn step(&mut self) -> Result<CpuSnapshot, CpuError> {
+        while self.current_instruction_cycle != 1 {
+            self.cycle()?;
+        }
+
+        // The OAM DMA requested by the previous instruction runs before the next one starts
+        let snapshot = loop {
+            if let Some(snapshot) = self.cycle_with_snapshot()? {
+                break snapshot;
+            }
+        };
+
+        while self.current_instruction_cycle != 1 {
+            self.cycle()?;
+        }
+
+        Ok(snapshot)
+    }
+
+    /// Run the given number of whole instructions with [Cpu::step], returning the snapshots taken
+    /// before running each of them.
+    ///
+    /// ```
+    /// use tinfo::cpu::Cpu;
+    ///
+    /// // LDA #$01, ADC #$02, TAX
+    /// let mut cpu = Cpu::with_program(&[0xA9, 0x01, 0x69, 0x02, 0xAA]);
+    ///
+    /// let snapshots = cpu.step_n(3).unwrap();
+    /// assert_eq!(snapshots[2].instruction_data.assembly, "TAX");
+    /// assert_eq!(cpu.x(), 0x03);
+    /// ```
+    pub fn step_n(&mut self, count: usize) -> Result<Vec<CpuSnapshot>, CpuError> {
+        (0..count).map(|_| self.step()).collect()
+    }
+
+    /// Check if the CPU has been jammed by one of the halting opcodes, needing a reset to run again.
+    pub fn is_jammed(&self) -> bool {
+        self.jammed.is_some()
+    }
+
+    /// Signal an edge on the NMI line, servicing the interrupt once the running instruction ends.
+    ///
+    /// The interrupt is latched, so raising it again before it's serviced has no effect, and it's
+    /// serviced even if the interrupts are disabled.
+    ///
+    /// The interrupts are only polled at the instruction boundaries, on the first cycle of the next
+    /// instruction, so an instruction in flight always retires before the handler is entered. A
+    /// NMI raised during the interrupt sequence of a `BRK` or an IRQ before its vector is fetched
+    /// hijacks it instead, like on the real hardware.
+    pub fn raise_nmi(&mut self) {
+        self.nmi_pending = true;
+    }
+
+    /// Set the level of the IRQ line, requesting an interrupt at every instruction boundary while
+    /// it's asserted and the interrupts are enabled.
+    ///
+    /// The line is not latched, so the device raising it must keep it asserted until the handler
+    /// acknowledges it, otherwise the handler is entered again once the interrupts are enabled.
+    /// Its level is sampled on the first cycle of the next instruction, so deasserting it before
+    /// the running instruction ends cancels the request.
+    pub fn set_irq_line(&mut self, asserted: bool) {
+        self.irq_line = asserted;
+    }
+
+    /// Get the interrupts waiting for the running instruction to end, to be serviced in place of
+    /// the next one.
+    pub fn pending_interrupts(&self) -> InterruptState {
+        InterruptState {
+            nmi_pending: self.nmi_pending,
+            irq_line: self.irq_line,
+            irq_masked: self.status.contains(CpuStatusFlags::InterruptsDisabled),
+        }
+    }
+
+    /// Get the interrupt sequence to run in place of the next instruction, if any, consuming the
+    /// latched NMI.
+    ///
+    /// The NMI takes priority over the IRQ when both are pending.
+    fn take_pending_interrupt(&mut self) -> Option<OpcodeInfo<C>> {
+        if self.nmi_pending {
+            self.nmi_pending = false;
+
+            return Some(Self::NMI);
+        }
+
+        if self.irq_line && !self.status.contains(CpuStatusFlags::InterruptsDisabled) {
+            return Some(Self::IRQ);
+        }
+
+        None
+    }
+
+    /// Reset the CPU like the reset button of the console does, abandoning the instruction in flight
+    /// and jumping to the address in the reset vector.
+    ///
+    /// The reset runs the interrupt sequence with the writes to the stack turned into reads, so the
+    /// stack pointer still moves down three bytes while the stack is left untouched. The interrupts
+    /// are disabled and the rest of the registers keep their values.
+    ///
+    /// It's the way to recover from a jam or a fatal [CpuError]: the jam, the pending interrupts and
+    /// the OAM DMA in flight or requested are dropped, while the RAM and the state of the cartridge
+    /// are kept. The devices holding the IRQ line have to assert it again.
+    pub fn reset(&mut self) -> Result<(), BusError> {
+        self.program_counter = self.bus.read_u16(RESET_VECTOR_ADDRESS)?;
+        self.stack_pointer = self.stack_pointer.wrapping_sub(3);
+        self.status |= CpuStatusFlags::InterruptsDisabled;
+
+        self.current_instruction_address = self.program_counter;
+        self.current_instruction_cycle = 1;
+        self.cache.clear();
+        self.jammed = None;
+
+        self.nmi_pending = false;
+        self.irq_line = false;
+        self.oam_dma = None;
+        self.bus.take_oam_dma_request();
+
+        // The calls in flight are abandoned
+        if let Some(call_stack) = &mut self.call_stack {
+            call_stack.clear();
+        }
+
+        self.clock_reset_sequence();
+
+        Ok(())
+    }
+
+    /// Run the cycles of the reset sequence on the bus, whose chips keep running meanwhile.
+    fn clock_reset_sequence(&mut self) {
+        for _ in 0..RESET_SEQUENCE_CYCLES {
+            self.cpu_cycles += 1;
+            self.bus.set_cpu_cycle(self.cpu_cycles);
+            self.bus.tick();
+        }
+    }
+
+    /// Enable or disable the disassembly of the instructions, enabled by default.
+    ///
+    /// While disabled the [InstructionData::assembly] of the snapshots is left empty, which avoids
+    /// its cost when running at full speed without anyone reading it. The emulation is not affected.
+    pub fn set_trace(&mut self, enabled: bool) {
+        self.trace = enabled;
+    }
+
+    /// Get the number of cycles the CPU has run since it was powered on, including the ones of the
+    /// startup and reset sequences.
+    pub fn cycles(&self) -> u64 {
+        self.cpu_cycles
     }
 
     /// Read a byte from the bus pointed by the program counter (PC).
-    fn read_program_counter(&self) -> Result<u8, BusError> {
+    fn read_program_counter(&mut self) -> Result<u8, BusError> {
         self.bus.read(self.program_counter)
     }
 
-    /// Get the matching instruction of the given opcode byte.
-    fn dispatch_opcode(opcode: u8) -> Instruction {
-        match opcode {
-            0x4C => Instruction::JumpAbsolute,
-            0xA2 => Instruction::LoadXRegisterImmediate,
-            0x86 => Instruction::StoreXRegisterZeroPage,
-            0x20 => Instruction::JumpToSubroutineAbsolute,
-            0xEA => Instruction::NoOperationImplied,
-            0x38 => Instruction::SetCarryFlagImplied,
-            0xB0 => Instruction::BranchIfCarrySetRelative,
-            0x18 => Instruction::ClearCarryFlagImplied,
-            0x90 => Instruction::BranchIfCarryClearRelative,
-            0xF0 => Instruction::BranchIfEqual,
-            0xD0 => Instruction::BranchIfNotEqual,
-            0x70 => Instruction::BranchIfOverflowSet,
-            0x50 => Instruction::BranchIfOverflowClear,
-            0x30 => Instruction::BranchIfMinus,
-            0x10 => Instruction::BranchIfPositive,
-            _ => unimplemented!("The opcode {opcode:02X} is not implemented yet!"),
-        }
-    }
-
-    /// Get the matching instruction data for the current running instruction.
-    fn dispatch_instruction(&mut self) -> Result<InstructionData, BusError> {
-        match self.current_instruction {
-            Instruction::JumpAbsolute => self.jump_absolute_instruction(),
-            Instruction::LoadXRegisterImmediate => self.load_x_register_immediate_instruction(),
-            Instruction::StoreXRegisterZeroPage => self.store_x_register_zero_page_instruction(),
-            Instruction::JumpToSubroutineAbsolute => self.jump_to_subroutine_absolute_instruction(),
-            Instruction::NoOperationImplied => self.no_operation_implied_instruction(),
-            Instruction::SetCarryFlagImplied => self.set_carry_flag_implied_instruction(),
-            Instruction::BranchIfCarrySetRelative => self.branch_instruction(CpuStatusFlags::Carry, false),
-            Instruction::BranchIfCarryClearRelative => self.branch_instruction(CpuStatusFlags::Carry, true),
-            Instruction::BranchIfEqual => self.branch_instruction(CpuStatusFlags::Zero, false),
-            Instruction::BranchIfNotEqual => self.branch_instruction(CpuStatusFlags::Zero, true),
-            Instruction::BranchIfOverflowSet => self.branch_instruction(CpuStatusFlags::Overflow, false),
-            Instruction::BranchIfOverflowClear => self.branch_instruction(CpuStatusFlags::Overflow, true),
-            Instruction::BranchIfMinus => self.branch_instruction(CpuStatusFlags::Negative, false),
-            Instruction::BranchIfPositive => self.branch_instruction(CpuStatusFlags::Negative, true),
-            Instruction::ClearCarryFlagImplied => self.clear_carry_flag_implied_instruction(),
-            Instruction::Stub => Ok(InstructionData {
-                arg_1: None,
-                arg_2: None,
-                assembly: String::from("INVALID STUB"),
-                idle_cycles: 0,
-            })
-        }
+    /// Move the program counter forward the given number of bytes, wrapping around the end of the
+    /// address space like the CPU does.
+    fn advance_program_counter(&mut self, bytes: u16) {
+        self.program_counter = self.program_counter.wrapping_add(bytes);
     }
 
     /// Given a value set the cpu flags related to the positive, negative or zero value
@@ -394,48 +980,964 @@ impl Cpu {
     }
 
     /// Push a value to the stack.
+    ///
+    /// The stack pointer wraps around inside the stack page.
     fn stack_push(&mut self, value: u8) -> Result<(), BusError> {
         self.bus.write(STACK_ADDRESS + self.stack_pointer as u16, value)?;
-        self.stack_pointer -= 1;
+        self.stack_pointer = self.stack_pointer.wrapping_sub(1);
+
+        Ok(())
+    }
+
+    /// Pull a value from the stack.
+    ///
+    /// The stack pointer wraps around inside the stack page.
+    fn stack_pull(&mut self) -> Result<u8, BusError> {
+        self.stack_pointer = self.stack_pointer.wrapping_add(1);
+
+        self.bus.read(STACK_ADDRESS + self.stack_pointer as u16)
+    }
+
+    /// Push a copy of the status register to the stack, with the B flag set when pushed by `PHP`
+    /// or `BRK` and clear when pushed by the hardware interrupts.
+    ///
+    /// The B and stub flags don't exist on the register, they only show up on the pushed copies,
+    /// where the stub flag is always set.
+    fn push_status(&mut self, with_b: bool) -> Result<(), BusError> {
+        let mut pushed_status = self.status | CpuStatusFlags::Stub;
+        pushed_status.set(CpuStatusFlags::B, with_b);
+
+        self.stack_push(pushed_status.bits())
+    }
+
+    /// Pull the status register from the stack, ignoring the pulled B flag and forcing the stub
+    /// flag on.
+    fn pull_status(&mut self) -> Result<(), BusError> {
+        let pulled_status = CpuStatusFlags::from_bits_retain(self.stack_pull()?);
+        self.status = (pulled_status - CpuStatusFlags::B) | CpuStatusFlags::Stub;
 
         Ok(())
     }
 }
 
 
+// The setters change the registers right away, in the middle of the instruction in flight if any,
+// so they are meant for debuggers and tests priming the state of the CPU rather than for emulation.
+impl<C: Cartridge> Cpu<C> {
+    /// Get the value of the accumulator register.
+    pub fn accumulator(&self) -> u8 {
+        self.accumulator
+    }
+
+    /// Set the value of the accumulator register.
+    pub fn set_accumulator(&mut self, value: u8) {
+        self.accumulator = value;
+    }
+
+    /// Get the value of the X index register.
+    pub fn x(&self) -> u8 {
+        self.register_x
+    }
+
+    /// Set the value of the X index register.
+    pub fn set_x(&mut self, value: u8) {
+        self.register_x = value;
+    }
+
+    /// Get the value of the Y index register.
+    pub fn y(&self) -> u8 {
+        self.register_y
+    }
+
+    /// Set the value of the Y index register.
+    pub fn set_y(&mut self, value: u8) {
+        self.register_y = value;
+    }
+
+    /// Get the flags of the status register.
+    pub fn status(&self) -> CpuStatusFlags {
+        self.status
+    }
+
+    /// Set the flags of the status register.
+    pub fn set_status(&mut self, status: CpuStatusFlags) {
+        self.status = status;
+    }
+
+    /// Get the current offset of the top of the stack from the start of the stack page.
+    pub fn stack_pointer(&self) -> u8 {
+        self.stack_pointer
+    }
+
+    /// Set the offset of the top of the stack from the start of the stack page.
+    pub fn set_stack_pointer(&mut self, value: u8) {
+        self.stack_pointer = value;
+    }
+
+    /// Get the address of the next instruction to run.
+    pub fn program_counter(&self) -> u16 {
+        self.program_counter
+    }
+
+    /// Set the address of the next instruction to run.
+    ///
+    /// The instruction in flight, if any, still runs its remaining cycles, which may move the
+    /// program counter again.
+    pub fn set_program_counter(&mut self, address: u16) {
+        self.program_counter = address;
+    }
+
+    /// Get the bus the CPU is attached to, to inspect it without side effects like the
+    /// [disassembler](crate::disasm) does.
+    pub fn bus(&self) -> &Bus<C> {
+        &self.bus
+    }
+
+    /// Get the bus the CPU is attached to, to hook tooling like a [BusObserver](crate::bus::BusObserver).
+    pub fn bus_mut(&mut self) -> &mut Bus<C> {
+        &mut self.bus
+    }
+
+    /// Read a byte from the bus without any side effect, see [Bus::peek].
+    pub fn peek(&self, address: u16) -> u8 {
+        self.bus.peek(address)
+    }
+
+    /// Read the given number of bytes from the bus without any side effect, see [Bus::peek_range].
+    pub fn peek_range(&self, start: u16, length: usize) -> Vec<u8> {
+        self.bus.peek_range(start, length)
+    }
+}
+
+/// The number of bytes on the top of the stack shown by the [Debug] output of the [Cpu].
+const DEBUG_STACK_BYTES: u8 = 4;
+
+impl<C: Cartridge> fmt::Debug for Cpu<C> {
+    /// Format the registers, the instruction in flight and the top of the stack, only peeking the
+    /// bus so debugging the CPU has no side effects.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stack: Vec<u8> = (1..=DEBUG_STACK_BYTES)
+            .map(|offset| self.bus.peek(STACK_ADDRESS + self.stack_pointer.wrapping_add(offset) as u16))
+            .collect();
+
+        f.debug_struct("Cpu")
+            .field("program_counter", &format_args!("${:04X}", self.program_counter))
+            .field("accumulator", &format_args!("${:02X}", self.accumulator))
+            .field("x", &format_args!("${:02X}", self.register_x))
+            .field("y", &format_args!("${:02X}", self.register_y))
+            .field("stack_pointer", &format_args!("${:02X}", self.stack_pointer))
+            .field("status", &format_args!("{}", self.status))
+            .field(
+                "current_instruction",
+                &format_args!(
+                    "{} (opcode {:02X}) at ${:04X}",
+                    self.current_instruction.assembly_mnemonic(),
+                    self.current_opcode,
+                    self.current_instruction_address
+                ),
+            )
+            .field("current_instruction_cycle", &self.current_instruction_cycle)
+            .field("cycles", &self.cpu_cycles)
+            .field("stack", &format_args!("{stack:02X?}"))
+            .field("jammed", &self.jammed.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
 
-    const DEFAULT_PROGRAM_COUNTER: usize = 0x8000;
+    pub(crate) use crate::cartridge::testing::{FlatAccess, FlatCartridge};
+
+    /// Get a loop writing values that change on every iteration to the zero page, the stack and two
+    /// other pages of the RAM, calling a subroutine on the way.
+    pub(crate) fn counter_program() -> Vec<u8> {
+        vec![
+            // INC $10
+            0xE6, 0x10,
 
-    pub(crate) struct MockCartridge {
-        prg_data: Vec<u8>,
+            // LDX $10
+            0xA6, 0x10,
+
+            // STA $0200,Y
+            0x99, 0x00, 0x02,
+
+            // STA $0300,X
+            0x9D, 0x00, 0x03,
+
+            // ADC #$03
+            0x69, 0x03,
+
+            // PHA
+            0x48,
+
+            // PLA
+            0x68,
+
+            // JSR $8020
+            0x20, 0x20, 0x80,
+
+            // JMP $8000
+            0x4C, 0x00, 0x80,
+
+            // NOP
+            0xEA, 0xEA, 0xEA, 0xEA, 0xEA, 0xEA, 0xEA, 0xEA, 0xEA, 0xEA, 0xEA, 0xEA,
+
+            // TAY
+            0xA8,
+
+            // RTS
+            0x60,
+        ]
+    }
+
+    /// Get the registers, RAM and cycle count of the CPU.
+    pub(crate) fn machine_state<C: Cartridge>(cpu: &Cpu<C>) -> (u8, u8, u8, CpuStatusFlags, u8, u16, u64, Vec<u8>) {
+        (
+            cpu.accumulator,
+            cpu.register_x,
+            cpu.register_y,
+            cpu.status,
+            cpu.stack_pointer,
+            cpu.program_counter,
+            cpu.cpu_cycles,
+            (0x0000..0x0800).map(|address| cpu.bus.peek(address)).collect(),
+        )
+    }
+
+    #[test]
+    fn test_new_reads_reset_vector() {
+        let cartridge = FlatCartridge::new(vec![]).with_reset_vector(0xC123);
+        let accesses = cartridge.accesses();
+
+        let cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        assert_eq!(cpu.program_counter, 0xC123);
+
+        assert_eq!(*accesses.borrow(), vec![FlatAccess::Read(0xFFFC), FlatAccess::Read(0xFFFD)]);
+    }
+
+    #[test]
+    fn test_power_on_status() {
+        let cpu = Cpu::new(Box::new(FlatCartridge::new(vec![]))).unwrap();
+
+        // The interrupts start disabled and the B flag is not part of the register
+        assert_eq!(cpu.status.bits(), 0x24);
+    }
+
+    #[test]
+    fn test_determinism() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+        let mut other_cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+
+        for _ in 0..5_000 {
+            cpu.cycle().unwrap();
+        }
+
+        for _ in 0..5_000 {
+            other_cpu.cycle().unwrap();
+        }
+
+        assert_eq!(cpu.save_state(), other_cpu.save_state());
+
+        // The loop takes 44 cycles, so the 114th INC is the last one run
+        assert_eq!(cpu.cycles(), 7 + 5_000);
+        assert_eq!(cpu.peek(0x0010), 114);
+    }
+
+    #[test]
+    fn test_cycle_with_and_without_snapshot() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+        let mut snapshot_cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+
+        let mut stalled_cycles = 0;
+
+        for cycle in 0..5_000 {
+            // The OAM DMA stalls the CPU on cycles that take no snapshot
+            if cycle % 1_000 == 0 {
+                cpu.poke(0x4014, 0x02).unwrap();
+                snapshot_cpu.poke(0x4014, 0x02).unwrap();
+            }
+
+            let result = cpu.cycle().unwrap();
+            let snapshot = snapshot_cpu.cycle_with_snapshot().unwrap();
+
+            // Only the cycles starting an instruction take a snapshot
+            assert_eq!(result == CycleResult::Dispatched, snapshot.is_some());
+
+            if result == CycleResult::Stalled {
+                stalled_cycles += 1;
+            }
+        }
+
+        assert!(stalled_cycles >= 5 * 513);
+        assert_eq!(cpu.save_state(), snapshot_cpu.save_state());
+    }
+
+    #[test]
+    fn test_cycle_result() {
+        let cartridge = FlatCartridge::new(vec![
+            // STA $4014
+            0x8D, 0x14, 0x40,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+
+        assert_eq!(cpu.cycle().unwrap(), CycleResult::Dispatched);
+
+        for _ in 0..3 {
+            assert_eq!(cpu.cycle().unwrap(), CycleResult::Continued);
+        }
+
+        // The OAM DMA stalls the CPU before the next instruction
+        for _ in 0..514 {
+            assert_eq!(cpu.cycle().unwrap(), CycleResult::Stalled);
+        }
+
+        assert_eq!(cpu.cycle().unwrap(), CycleResult::Dispatched);
+    }
+
+    #[test]
+    fn test_generic_cartridge() {
+        let mut boxed_cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+        let mut generic_cpu: Cpu<FlatCartridge> = Cpu::from_cartridge(FlatCartridge::new(counter_program())).unwrap();
+
+        for _ in 0..100 {
+            assert_eq!(generic_cpu.step().unwrap().to_string(), boxed_cpu.step().unwrap().to_string());
+        }
+
+        assert_eq!(generic_cpu.save_state(), boxed_cpu.save_state());
     }
 
-    impl MockCartridge {
-        pub(crate) fn new(prg_data: Vec<u8>) -> MockCartridge {
-            MockCartridge { prg_data }
+    #[test]
+    fn test_cycles_do_not_wrap() {
+        let cartridge = FlatCartridge::new(vec![
+            // JMP $8000
+            0x4C, 0x00, 0x80,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        let mut cycles = cpu.cycles();
+
+        while cycles <= u16::MAX as u64 + 1 {
+            cpu.cycle().unwrap();
+
+            assert_eq!(cpu.cycles(), cycles + 1);
+            cycles = cpu.cycles();
         }
+
+        assert_eq!(cpu.program_counter & 0xFF00, 0x8000);
+    }
+
+    #[test]
+    fn test_program_counter_wraps_around() {
+        // NOP, then LDA #$42 with the operand at $0000
+        let cartridge = FlatCartridge::new(vec![0xEA, 0xA9]).with_base_address(0xFFFE);
+
+        let mut cpu = Cpu::new_with_program_counter(Box::new(cartridge), 0xFFFE);
+
+        cpu.bus.write(0x0000, 0x42).unwrap();
+
+        // LDX #$07
+        cpu.bus.write(0x0001, 0xA2).unwrap();
+        cpu.bus.write(0x0002, 0x07).unwrap();
+
+        cpu.run_full_instruction();
+        assert_eq!(cpu.program_counter, 0xFFFF);
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDA #$42");
+        assert_eq!(cpu.program_counter, 0x0001);
+        assert_eq!(cpu.accumulator, 0x42);
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDX #$07");
+        assert_eq!(cpu.program_counter, 0x0003);
+    }
+
+    /// Build a program with instructions of different lengths and cycle counts.
+    fn step_cartridge() -> FlatCartridge {
+        FlatCartridge::new(vec![
+            // LDA #$10
+            0xA9, 0x10,
+
+            // STA $0200
+            0x8D, 0x00, 0x02,
+
+            // PHA
+            0x48,
+
+            // INC $0200
+            0xEE, 0x00, 0x02,
+
+            // LDX $0200
+            0xAE, 0x00, 0x02,
+        ])
+    }
+
+    /// Assert the visible state of two CPUs is the same.
+    fn assert_same_state(cpu: &Cpu, other: &Cpu) {
+        assert_eq!(cpu.accumulator, other.accumulator);
+        assert_eq!(cpu.register_x, other.register_x);
+        assert_eq!(cpu.register_y, other.register_y);
+        assert_eq!(cpu.status, other.status);
+        assert_eq!(cpu.stack_pointer, other.stack_pointer);
+        assert_eq!(cpu.program_counter, other.program_counter);
+        assert_eq!(cpu.cpu_cycles, other.cpu_cycles);
+        assert_eq!(cpu.current_instruction_cycle, other.current_instruction_cycle);
     }
 
-    impl Cartridge for MockCartridge {
-        unsafe fn read(&self, address: u16) -> Result<u8, crate::cartridge::CartridgeError> {
-            Ok(self.prg_data.get(address as usize - DEFAULT_PROGRAM_COUNTER).copied().unwrap_or(0xEA))
+    #[test]
+    fn test_step_matches_cycles() {
+        let mut cpu = Cpu::new(Box::new(step_cartridge())).unwrap();
+        let mut stepped_cpu = Cpu::new(Box::new(step_cartridge())).unwrap();
+
+        for _ in 0..5 {
+            let instruction_data = cpu.run_full_instruction();
+            let snapshot = stepped_cpu.step().unwrap();
+
+            assert_eq!(snapshot.instruction_data.assembly, instruction_data.assembly);
+            assert_same_state(&cpu, &stepped_cpu);
         }
 
-        unsafe fn write(
-            &mut self,
-            _address: u16,
-            _value: u8,
-        ) -> Result<(), crate::cartridge::CartridgeError> {
-            Ok(())
+        assert_eq!(stepped_cpu.register_x, 0x11);
+    }
+
+    #[test]
+    fn test_step_finishes_instruction_in_flight() {
+        let mut cpu = Cpu::new(Box::new(step_cartridge())).unwrap();
+
+        // Start the LDA
+        cpu.cycle().unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let snapshot = cpu.step().unwrap();
+        assert_eq!(snapshot.instruction_data.assembly, "STA $0200 = 00");
+        assert_eq!(snapshot.program_counter, 0x8002);
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 1 + 4);
+
+        assert_eq!(cpu.program_counter, 0x8005);
+        assert_eq!(cpu.bus.read(0x0200).unwrap(), 0x10);
+    }
+
+    #[test]
+    fn test_disassembly_does_not_read_the_bus() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDA $80F8,X
+            0xBD, 0xF8, 0x80,
+        ]);
+        let accesses = cartridge.accesses();
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_x = 0x10;
+        accesses.borrow_mut().clear();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDA $80F8,X @ 8108 = EA");
+
+        // Only the reads of the instruction cycles reach the cartridge
+        assert_eq!(
+            *accesses.borrow(),
+            vec![
+                FlatAccess::Read(0x8000),
+                FlatAccess::Read(0x8001),
+                FlatAccess::Read(0x8002),
+                FlatAccess::Read(0x8008),
+                FlatAccess::Read(0x8108),
+            ]
+        );
+        assert_eq!(accesses.borrow().len() as u64, cpu.cpu_cycles - cpu_cycles);
+    }
+
+    #[test]
+    fn test_snapshot_instruction_bytes() {
+        let cartridge = FlatCartridge::new(vec![
+            // NOP
+            0xEA,
+
+            // LDX #$42
+            0xA2, 0x42,
+
+            // JMP $8000
+            0x4C, 0x00, 0x80,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+
+        let snapshot = cpu.step().unwrap();
+        assert_eq!(snapshot.length(), 1);
+        assert_eq!(snapshot.bytes(), [0xEA]);
+
+        let snapshot = cpu.step().unwrap();
+        assert_eq!(snapshot.length(), 2);
+        assert_eq!(snapshot.bytes(), [0xA2, 0x42]);
+        assert_eq!(snapshot.instruction_data.arg_2, None);
+
+        let snapshot = cpu.step().unwrap();
+        assert_eq!(snapshot.length(), 3);
+        assert_eq!(snapshot.bytes(), [0x4C, 0x00, 0x80]);
+        assert_eq!(snapshot.instruction_data.arg_1, Some(0x00));
+        assert_eq!(snapshot.instruction_data.arg_2, Some(0x80));
+    }
+
+    /// Build a CPU about to run the given bytes at the given address, with the given registers,
+    /// its PPU run up to the given number of cycles.
+    fn nestest_cpu(address: u16, bytes: &[u8], registers: [u8; 5], cpu_cycles: u64) -> Cpu {
+        let cartridge = FlatCartridge::new(bytes.to_vec()).with_base_address(address);
+
+        let mut cpu = Cpu::new_with_program_counter(Box::new(cartridge), address);
+        let [accumulator, register_x, register_y, status, stack_pointer] = registers;
+        cpu.accumulator = accumulator;
+        cpu.register_x = register_x;
+        cpu.register_y = register_y;
+        cpu.status = CpuStatusFlags::from_p_byte(status);
+        cpu.stack_pointer = stack_pointer;
+
+        for _ in cpu.cpu_cycles..cpu_cycles {
+            cpu.bus.tick();
         }
+
+        cpu.cpu_cycles = cpu_cycles;
+
+        cpu
     }
 
-    impl Cpu {
+    /// Assert the snapshot of the next instruction matches a line of the nestest log.
+    fn assert_nestest_line(cpu: &mut Cpu, expected_line: &str) {
+        assert_eq!(cpu.step().unwrap().to_string(), expected_line);
+    }
+
+    #[test]
+    fn test_snapshot_nestest_format() {
+        let mut cpu = nestest_cpu(0xC000, &[0x4C, 0xF5, 0xC5], [0x00, 0x00, 0x00, 0x24, 0xFD], 7);
+        assert_nestest_line(
+            &mut cpu,
+            "C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7",
+        );
+
+        let mut cpu = nestest_cpu(0xC5F7, &[0x86, 0x00], [0x00, 0x00, 0x00, 0x26, 0xFD], 12);
+        assert_nestest_line(
+            &mut cpu,
+            "C5F7  86 00     STX $00 = 00                    A:00 X:00 Y:00 P:26 SP:FD PPU:  0, 36 CYC:12",
+        );
+
+        let mut cpu = nestest_cpu(0xC72C, &[0x60], [0xCB, 0x00, 0x53, 0x6F, 0xF9], 14906);
+        assert_nestest_line(
+            &mut cpu,
+            "C72C  60        RTS                             A:CB X:00 Y:53 P:6F SP:F9 PPU:131, 47 CYC:14906",
+        );
+
+        let mut cpu = nestest_cpu(0xC6BD, &[0x04, 0xA9], [0xAA, 0x97, 0x4E, 0xEF, 0xF9], 14579);
+        assert_nestest_line(
+            &mut cpu,
+            "C6BD  04 A9    *NOP $A9 = 00                    A:AA X:97 Y:4E P:EF SP:F9 PPU:128, 89 CYC:14579",
+        );
+
+        let mut cpu = nestest_cpu(0xDBB5, &[0x6C, 0xFF, 0x02], [0x60, 0x07, 0x00, 0x65, 0xF9], 9615);
+        cpu.bus.write(0x02FF, 0x00).unwrap();
+        cpu.bus.write(0x0200, 0x03).unwrap();
+        assert_nestest_line(
+            &mut cpu,
+            "DBB5  6C FF 02  JMP ($02FF) = 0300              A:60 X:07 Y:00 P:65 SP:F9 PPU: 84,201 CYC:9615",
+        );
+    }
+
+    #[test]
+    fn test_snapshot_ppu_position() {
+        let mut cpu = Cpu::from_cartridge(FlatCartridge::new(counter_program())).unwrap();
+        let mut previous = cpu.step().unwrap();
+        assert_eq!((previous.ppu_scanline, previous.ppu_dot), (0, 21));
+
+        while previous.ppu_scanline < 2 {
+            let snapshot = cpu.step().unwrap();
+
+            // The PPU runs three dots on every cycle, wrapping to the next scanline after the dot 340
+            let dots = previous.ppu_scanline as u64 * 341 + previous.ppu_dot as u64;
+            let expected = dots + 3 * (snapshot.cpu_cycles - previous.cpu_cycles);
+            assert_eq!((snapshot.ppu_scanline as u64, snapshot.ppu_dot as u64), (expected / 341, expected % 341));
+
+            previous = snapshot;
+        }
+    }
+
+    #[test]
+    fn test_status_flags_display() {
+        assert_eq!(CpuStatusFlags::from_p_byte(0x24).to_string(), "nv-bdIzc");
+        assert_eq!(CpuStatusFlags::from_p_byte(0xE5).to_string(), "NV-bdIzC");
+        assert_eq!(CpuStatusFlags::from_p_byte(0x00).to_string(), "nv-bdizc");
+        assert_eq!(CpuStatusFlags::all().to_string(), "NV-BDIZC");
+    }
+
+    #[test]
+    fn test_status_flags_p_byte_round_trip() {
+        for value in 0..=0xFF {
+            let status = CpuStatusFlags::from_p_byte(value);
+
+            assert_eq!(status.bits(), value | 0x20);
+            assert_eq!(status.to_p_byte(), value | 0x30);
+            assert_eq!(CpuStatusFlags::from_p_byte(status.bits()), status);
+
+            // Every flag but the stub one is readable back from its letter
+            let parsed = status.to_string().chars().enumerate().fold(0x20, |parsed, (bit, letter)| {
+                match letter.is_ascii_uppercase() {
+                    true => parsed | 0x80 >> bit,
+                    false => parsed,
+                }
+            });
+
+            assert_eq!(parsed, status.bits());
+        }
+    }
+
+    #[test]
+    fn test_cumulative_cycles() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+
+        // The power-on reset sequence takes 7 cycles, like the first line of the nestest logs shows
+        assert_eq!(cpu.cycles(), 7);
+
+        let mut expected_cycles = 7;
+
+        for (assembly, cycles) in [
+            ("INC $10 = 00", 5),
+            ("LDX $10 = 01", 3),
+            ("STA $0200,Y @ 0200 = 00", 5),
+            ("STA $0300,X @ 0301 = 00", 5),
+            ("ADC #$03", 2),
+            ("PHA", 3),
+            ("PLA", 4),
+            ("JSR $8020", 6),
+            ("TAY", 2),
+            ("RTS", 6),
+            ("JMP $8000", 3),
+        ] {
+            let snapshot = cpu.step().unwrap();
+            assert_eq!(snapshot.instruction_data.assembly, assembly);
+            assert_eq!(snapshot.cpu_cycles, expected_cycles, "{assembly}");
+
+            expected_cycles += cycles;
+            assert_eq!(cpu.cycles(), expected_cycles, "{assembly}");
+        }
+
+        assert_eq!(cpu.cycles(), 51);
+    }
+
+    #[test]
+    fn test_jam() {
+        let cartridge = FlatCartridge::new(vec![
+            // NOP
+            0xEA,
+
+            // JAM
+            0x02,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.run_full_instruction();
+        assert!(!cpu.is_jammed());
+
+        assert!(matches!(
+            cpu.cycle(),
+            Err(CpuError::Jammed { opcode: 0x02, program_counter: 0x8001, .. })
+        ));
+        assert!(cpu.is_jammed());
+
+        let cpu_cycles = cpu.cpu_cycles;
+
+        for _ in 0..4 {
+            assert!(matches!(
+                cpu.cycle(),
+                Err(CpuError::Jammed { opcode: 0x02, program_counter: 0x8001, .. })
+            ));
+        }
+
+        // The state of the CPU is frozen
+        assert_eq!(cpu.cpu_cycles, cpu_cycles);
+        assert_eq!(cpu.program_counter, 0x8001);
+    }
+
+    #[test]
+    fn test_unsupported_opcode() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDA #$42
+            0xA9, 0x42,
+
+            // An opcode that is not supported
+            0xBB,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.run_full_instruction();
+
+        let status = cpu.status;
+
+        assert!(matches!(
+            cpu.cycle(),
+            Err(CpuError::UnsupportedOpcode { opcode: 0xBB, program_counter: 0x8002, .. })
+        ));
+
+        assert_eq!(cpu.accumulator, 0x42);
+        assert_eq!(cpu.register_x, 0x00);
+        assert_eq!(cpu.register_y, 0x00);
+        assert_eq!(cpu.status, status);
+        assert_eq!(cpu.stack_pointer, 0xFD);
+        assert_eq!(cpu.program_counter, 0x8002);
+    }
+
+    #[test]
+    fn test_debug() {
+        let cartridge = FlatCartridge::new(vec![]);
+        let accesses = cartridge.accesses();
+
+        let cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        accesses.borrow_mut().clear();
+
+        let dump = format!("{cpu:?}");
+
+        assert!(dump.contains("program_counter: $8000"));
+        assert!(dump.contains("accumulator: $00"));
+        assert!(dump.contains("stack_pointer: $FD"));
+        assert!(dump.contains(&format!("status: {}", cpu.status)));
+        assert!(dump.contains("cycles: 7"));
+        assert!(dump.contains("stack: [00, 00, 00, 00]"));
+
+        // Only peeks are done
+        assert_eq!(*accesses.borrow(), vec![]);
+
+        let bus_dump = format!("{:?}", cpu.bus);
+        assert!(bus_dump.contains("cpu_ram: [00, 00"));
+        assert!(bus_dump.contains("cpu_ram_hash"));
+    }
+
+    #[test]
+    fn test_cycle_error_context() {
+        let cartridge = FlatCartridge::new(vec![
+            // NOP
+            0xEA,
+
+            // LDA #$42
+            0xA9, 0x42,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.run_full_instruction();
+        cpu.cycle().unwrap();
+
+        // Immediate addressing only reads its operand on the second cycle
+        cpu.current_instruction_cycle = 5;
+
+        let error = cpu.cycle().unwrap_err();
+        assert!(matches!(
+            error,
+            CpuError::CycleFailed {
+                program_counter: 0x8001,
+                opcode: 0xA9,
+                cycle: 5,
+                source: CycleError::InstructionCycleOutOfBounds,
+                ..
+            }
+        ));
+
+        assert_eq!(
+            error.to_string(),
+            "Running the cycle 5 of LDA (opcode A9) at $8001 failed: The requested instruction step is out of bounds"
+        );
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut prg_data = vec![0xEA; 0x8000];
+
+        // LDA #$42
+        prg_data[0x0000..0x0002].copy_from_slice(&[0xA9, 0x42]);
+
+        // PHA
+        prg_data[0x0002] = 0x48;
+
+        // LDX #$07
+        prg_data[0x0003..0x0005].copy_from_slice(&[0xA2, 0x07]);
+
+        let cartridge = FlatCartridge::new(prg_data).with_reset_vector(0x8000);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.status = CpuStatusFlags::Carry;
+
+        cpu.batch_run_full_instruction(2);
+        assert_eq!(cpu.program_counter, 0x8003);
+
+        // Abandon the LDX in the middle of it
+        cpu.cycle().unwrap();
+
+        let stack_pointer = cpu.stack_pointer;
+        let cpu_cycles = cpu.cpu_cycles;
+
+        cpu.reset().unwrap();
+
+        assert_eq!(cpu.program_counter, 0x8000);
+        assert_eq!(cpu.stack_pointer, stack_pointer - 3);
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 7);
+        assert_eq!(cpu.accumulator, 0x42);
+        assert_eq!(cpu.register_x, 0x00);
+        assert_eq!(cpu.status, CpuStatusFlags::Carry | CpuStatusFlags::InterruptsDisabled);
+
+        // Nothing is written to the stack
+        assert_eq!(cpu.bus.read(STACK_ADDRESS + stack_pointer as u16).unwrap(), 0x00);
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDA #$42");
+        assert_eq!(cpu.program_counter, 0x8002);
+    }
+
+    #[test]
+    fn test_reset_unjams() {
+        let cartridge = FlatCartridge::new(vec![
+            // JAM
+            0x02,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        assert!(cpu.cycle().is_err());
+        assert!(cpu.is_jammed());
+
+        cpu.reset().unwrap();
+        assert!(!cpu.is_jammed());
+        assert_eq!(cpu.program_counter, 0x8000);
+    }
+
+    #[test]
+    fn test_reset_recovers_from_jam() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDA #$42, STA $10, STA $6000
+            0xA9, 0x42, 0x85, 0x10, 0x8D, 0x00, 0x60,
+
+            // JAM
+            0x02,
+        ])
+        .with_reset_vector(0x8010);
+
+        let mut cpu = Cpu::from_cartridge_with_program_counter(cartridge, 0x8000);
+        cpu.batch_run_full_instruction(3);
+
+        // Every cycle fails once jammed, even with an interrupt waiting
+        assert!(matches!(cpu.cycle(), Err(CpuError::Jammed { .. })));
+        cpu.raise_nmi();
+        cpu.set_irq_line(true);
+        assert!(matches!(cpu.cycle(), Err(CpuError::Jammed { .. })));
+
+        cpu.reset().unwrap();
+        assert!(!cpu.is_jammed());
+        assert!(!cpu.pending_interrupts().nmi_pending);
+        assert!(!cpu.pending_interrupts().irq_line);
+
+        // The CPU runs from the reset vector again, with the memory untouched
+        let snapshot = cpu.cycle_with_snapshot().unwrap().unwrap();
+        assert_eq!(snapshot.program_counter, 0x8010);
+        assert_eq!(snapshot.instruction_data.mnemonic, Mnemonic::Nop);
+
+        assert_eq!(cpu.bus.peek(0x0010), 0x42);
+        assert_eq!(cpu.bus.peek(0x6000), 0x42);
+    }
+
+    #[test]
+    fn test_trace_does_not_change_emulation() {
+        let prg_data = vec![
+            // LDA #$03
+            0xA9, 0x03,
+
+            // STA $10
+            0x85, 0x10,
+
+            // LDA #$02
+            0xA9, 0x02,
+
+            // STA $11
+            0x85, 0x11,
+
+            // LDY #$FF
+            0xA0, 0xFF,
+
+            // ADC ($10),Y
+            0x71, 0x10,
+
+            // STA $0300,X
+            0x9D, 0x00, 0x03,
+
+            // JSR $8020
+            0x20, 0x20, 0x80,
+
+            // INC $12
+            0xE6, 0x12,
+
+            // LDX $12
+            0xA6, 0x12,
+
+            // CPX #$05
+            0xE0, 0x05,
+
+            // BNE $800A
+            0xD0, 0xF0,
+
+            // JMP $801A
+            0x4C, 0x1A, 0x80,
+
+            // NOP
+            0xEA, 0xEA, 0xEA,
+
+            // PHA
+            0x48,
+
+            // ROL $0300,X
+            0x3E, 0x00, 0x03,
+
+            // PLA
+            0x68,
+
+            // RTS
+            0x60,
+        ];
+
+        let run = |trace: bool| {
+            let mut cpu = Cpu::new(Box::new(FlatCartridge::new(prg_data.clone()))).unwrap();
+            cpu.set_trace(trace);
+
+            let snapshots: Vec<CpuSnapshot> = (0..80).map(|_| cpu.step().unwrap()).collect();
+
+            (cpu, snapshots)
+        };
+
+        let (traced_cpu, traced_snapshots) = run(true);
+        let (untraced_cpu, untraced_snapshots) = run(false);
+
+        for (traced, untraced) in traced_snapshots.iter().zip(&untraced_snapshots) {
+            assert!(!traced.instruction_data.assembly.is_empty());
+            assert!(untraced.instruction_data.assembly.is_empty());
+
+            assert_eq!(traced.program_counter, untraced.program_counter);
+            assert_eq!(traced.opcode, untraced.opcode);
+            assert_eq!(traced.accumulator, untraced.accumulator);
+            assert_eq!(traced.register_x, untraced.register_x);
+            assert_eq!(traced.register_y, untraced.register_y);
+            assert_eq!(traced.status, untraced.status);
+            assert_eq!(traced.stack_pointer, untraced.stack_pointer);
+            assert_eq!(traced.cpu_cycles, untraced.cpu_cycles);
+            assert_eq!(traced.instruction_data.idle_cycles, untraced.instruction_data.idle_cycles);
+            assert_eq!(traced.instruction_data.arg_1, untraced.instruction_data.arg_1);
+            assert_eq!(traced.instruction_data.arg_2, untraced.instruction_data.arg_2);
+            assert_eq!(traced.bytes(), untraced.bytes());
+        }
+
+        assert_eq!(traced_cpu.cpu_cycles, untraced_cpu.cpu_cycles);
+        assert_eq!(traced_cpu.program_counter, untraced_cpu.program_counter);
+
+        for address in 0x0000..0x0800 {
+            assert_eq!(traced_cpu.bus.peek(address), untraced_cpu.bus.peek(address));
+        }
+    }
+
+    impl<C: Cartridge> Cpu<C> {
         pub(crate) fn run_full_instruction(&mut self) -> InstructionData {
-            let instruction_data = self.cycle().unwrap().unwrap().instruction_data;
+            let instruction_data = self.cycle_with_snapshot().unwrap().unwrap().instruction_data;
 
             for _ in 0..instruction_data.idle_cycles {
                 self.cycle().unwrap();
@@ -449,5 +1951,14 @@ mod tests {
                 self.run_full_instruction();
             }
         }
+
+        /// Run cycles until the next instruction starts, returning the snapshot taken before running it.
+        pub(crate) fn run_until_next_snapshot(&mut self) -> CpuSnapshot {
+            loop {
+                if let Some(snapshot) = self.cycle_with_snapshot().unwrap() {
+                    return snapshot;
+                }
+            }
+        }
     }
 }
\ No newline at end of file
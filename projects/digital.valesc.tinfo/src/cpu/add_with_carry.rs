@@ -0,0 +1,215 @@
+//! Holds the implementation of the `ADC` instruction.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::addressing::AddressingMode;
+use crate::cpu::Cpu;
+use crate::cpu::CpuStatusFlags;
+use crate::cpu::CycleError;
+
+impl<C: Cartridge> Cpu<C> {
+    /// Add the given value and the carry flag to the accumulator, setting the carry, zero,
+    /// overflow and negative flags from the result.
+    ///
+    /// The 2A03 lacks the binary-coded decimal circuitry of the original 6502, so the
+    /// decimal flag is ignored.
+    pub(super) fn add_with_carry(&mut self, value: u8) {
+        let carry = self.status.contains(CpuStatusFlags::Carry) as u16;
+        let sum = self.accumulator as u16 + value as u16 + carry;
+        let result = sum as u8;
+
+        self.status.set(CpuStatusFlags::Carry, sum > 0xFF);
+
+        // The sign of the result differs from the sign of both operands
+        self.status.set(
+            CpuStatusFlags::Overflow,
+            (self.accumulator ^ result) & (value ^ result) & 0x80 != 0,
+        );
+
+        self.accumulator = result;
+        self.set_signedness(result);
+    }
+
+    /// Implements the add with carry instruction cycles for the given addressing mode.
+    pub(super) fn add_with_carry_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        let Some(value) = self.read_operand_cycles(mode)? else {
+            return Ok(false);
+        };
+
+        self.add_with_carry(value);
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+
+    fn add_with_carry_immediate(accumulator: u8, value: u8, status: CpuStatusFlags) -> Cpu {
+        let cartridge = FlatCartridge::new(vec![
+            // ADC #value
+            0x69, value,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = accumulator;
+        cpu.status = status;
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, format!("ADC #${value:02X}"));
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 2);
+        assert_eq!(cpu.program_counter, 0x8002);
+
+        cpu
+    }
+
+    #[test]
+    fn test_adc_immediate_signed_overflow() {
+        let cpu = add_with_carry_immediate(0x7F, 0x01, CpuStatusFlags::empty());
+
+        assert_eq!(cpu.accumulator, 0x80);
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Overflow));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_adc_immediate_unsigned_carry() {
+        let cpu = add_with_carry_immediate(0xFF, 0x01, CpuStatusFlags::empty());
+
+        assert_eq!(cpu.accumulator, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Overflow));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_adc_immediate_carry_in() {
+        let cpu = add_with_carry_immediate(0x10, 0x20, CpuStatusFlags::Carry);
+
+        assert_eq!(cpu.accumulator, 0x31);
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Overflow));
+    }
+
+    #[test]
+    fn test_adc_immediate_negative_overflow() {
+        let cpu = add_with_carry_immediate(0x80, 0xFF, CpuStatusFlags::empty());
+
+        assert_eq!(cpu.accumulator, 0x7F);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Overflow));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_adc_immediate_ignores_decimal_flag() {
+        let cpu = add_with_carry_immediate(0x09, 0x01, CpuStatusFlags::Decimal);
+
+        // A binary-coded decimal addition would have given 0x10
+        assert_eq!(cpu.accumulator, 0x0A);
+        assert!(cpu.status.contains(CpuStatusFlags::Decimal));
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+    }
+
+    #[test]
+    fn test_adc_zero_page_x() {
+        let cartridge = FlatCartridge::new(vec![
+            // ADC $FF,X
+            0x75, 0xFF,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x01;
+        cpu.register_x = 0x02;
+        cpu.bus.write(0x0001, 0x02).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "ADC $FF,X @ 01 = 02");
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.accumulator, 0x03);
+    }
+
+    #[test]
+    fn test_adc_absolute_y_page_cross() {
+        let cartridge = FlatCartridge::new(vec![
+            // ADC $02FF,Y
+            0x79, 0xFF, 0x02,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x01;
+        cpu.register_y = 0x01;
+        cpu.bus.write(0x0300, 0x40).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "ADC $02FF,Y @ 0300 = 40");
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 5);
+        assert_eq!(cpu.program_counter, 0x8003);
+        assert_eq!(cpu.accumulator, 0x41);
+    }
+
+    #[test]
+    fn test_adc_indirect_x() {
+        let cartridge = FlatCartridge::new(vec![
+            // ADC ($80,X)
+            0x61, 0x80,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x01;
+        cpu.register_x = 0x7F;
+        // The pointer wraps around inside the zero page
+        cpu.bus.write(0x00FF, 0x00).unwrap();
+        cpu.bus.write(0x0000, 0x02).unwrap();
+        cpu.bus.write(0x0200, 0x5A).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "ADC ($80,X) @ FF = 0200 = 5A");
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 6);
+        assert_eq!(cpu.program_counter, 0x8002);
+        assert_eq!(cpu.accumulator, 0x5B);
+    }
+
+    #[test]
+    fn test_adc_indirect_y_page_cross_penalty() {
+        let cartridge = FlatCartridge::new(vec![
+            // ADC ($33),Y
+            0x71, 0x33,
+
+            // ADC ($33),Y
+            0x71, 0x33,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_y = 0x00;
+        cpu.bus.write(0x0033, 0xFF).unwrap();
+        cpu.bus.write(0x0034, 0x03).unwrap();
+        cpu.bus.write(0x03FF, 0x01).unwrap();
+        cpu.bus.write(0x0400, 0x02).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "ADC ($33),Y = 03FF @ 03FF = 01");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 5);
+        assert_eq!(cpu.accumulator, 0x01);
+
+        cpu.register_y = 0x01;
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "ADC ($33),Y = 03FF @ 0400 = 02");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 6);
+        assert_eq!(cpu.accumulator, 0x03);
+    }
+}
@@ -0,0 +1,584 @@
+//! Holds the operand fetching logic shared between the instructions of the CPU.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::{Cpu, CycleError, Mnemonic};
+use crate::{build_address, crosses_page, offset_address, relative_address};
+
+/// The different ways an instruction can get its operand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AddressingMode {
+    /// The instruction has no operand, or it is implied by the instruction itself.
+    Implied,
+
+    /// The operand is the accumulator.
+    Accumulator,
+
+    /// The operand is the byte following the opcode.
+    Immediate,
+
+    /// The operand is on the zero page, at the address given by the byte following the opcode.
+    ZeroPage,
+
+    /// Like [AddressingMode::ZeroPage] but adding the X register to the address, wrapping inside the zero page.
+    ZeroPageX,
+
+    /// Like [AddressingMode::ZeroPage] but adding the Y register to the address, wrapping inside the zero page.
+    ZeroPageY,
+
+    /// The operand is at the address given by the two bytes following the opcode.
+    Absolute,
+
+    /// Like [AddressingMode::Absolute] but adding the X register to the address.
+    AbsoluteX,
+
+    /// Like [AddressingMode::Absolute] but adding the Y register to the address.
+    AbsoluteY,
+
+    /// The operand is at the address stored on the pointer given by the two bytes following the opcode,
+    /// only used by `JMP`.
+    Indirect,
+
+    /// The operand is at the address stored on the zero page pointer given by the byte following
+    /// the opcode plus the X register, also know as `(indirect,X)`.
+    IndirectX,
+
+    /// The operand is at the address stored on the zero page pointer given by the byte following
+    /// the opcode, plus the Y register, also know as `(indirect),Y`.
+    IndirectY,
+
+    /// The operand is a signed offset from the program counter given by the byte following the opcode,
+    /// only used by the branches.
+    Relative,
+}
+
+impl AddressingMode {
+    /// Get the cycle of the instruction on which a memory operand is accessed, when the indexed modes
+    /// spend the cycle fixing the upper byte of the address.
+    fn operand_cycle(self) -> u8 {
+        match self {
+            AddressingMode::ZeroPage => 3,
+            AddressingMode::ZeroPageX | AddressingMode::ZeroPageY | AddressingMode::Absolute => 4,
+            AddressingMode::AbsoluteX | AddressingMode::AbsoluteY => 5,
+            AddressingMode::IndirectX | AddressingMode::IndirectY => 6,
+            _ => unreachable!("{self:?} addressing has no operand on memory"),
+        }
+    }
+
+    /// Get the number of bytes taken by an instruction using the addressing mode, including the opcode.
+    pub fn length(self) -> u8 {
+        match self {
+            AddressingMode::Implied | AddressingMode::Accumulator => 1,
+            AddressingMode::Absolute | AddressingMode::AbsoluteX | AddressingMode::AbsoluteY | AddressingMode::Indirect => 3,
+            _ => 2,
+        }
+    }
+}
+
+impl<C: Cartridge> Cpu<C> {
+    /// Format the assembly of an instruction with the given mnemonic and addressing mode about to run at
+    /// the program counter, like the nestest logs do.
+    pub(super) fn operand_assembly(&self, mnemonic: Mnemonic, official: bool, mode: AddressingMode) -> String {
+        let assembly_mnemonic = mnemonic.assembly(official);
+
+        // The single byte instructions have no operand to read
+        let arg_1 = match mode.length() {
+            1 => 0x00,
+            _ => self.bus.peek(self.program_counter.wrapping_add(1)),
+        };
+
+        match mode {
+            AddressingMode::Implied => assembly_mnemonic,
+            AddressingMode::Accumulator => format!("{assembly_mnemonic} A"),
+            AddressingMode::Immediate => format!("{assembly_mnemonic} #${arg_1:02X}"),
+
+            AddressingMode::ZeroPage => {
+                format!("{assembly_mnemonic} ${arg_1:02X} = {:02X}", self.bus.peek(build_address(arg_1, 0x00)))
+            }
+
+            AddressingMode::ZeroPageX | AddressingMode::ZeroPageY => {
+                let (index, index_name) = self.index_register(mode);
+
+                // The address wraps around inside the zero page
+                let address = arg_1.wrapping_add(index);
+
+                format!(
+                    "{assembly_mnemonic} ${arg_1:02X},{index_name} @ {address:02X} = {:02X}",
+                    self.bus.peek(build_address(address, 0x00))
+                )
+            }
+
+            AddressingMode::Absolute => {
+                let address = self.bus.peek_u16(self.program_counter.wrapping_add(1));
+
+                // The jumps use the address itself instead of the value stored there
+                match mnemonic {
+                    Mnemonic::Jmp | Mnemonic::Jsr => format!("{assembly_mnemonic} ${address:04X}"),
+                    _ => format!("{assembly_mnemonic} ${address:04X} = {:02X}", self.bus.peek(address)),
+                }
+            }
+
+            AddressingMode::AbsoluteX | AddressingMode::AbsoluteY => {
+                let (index, index_name) = self.index_register(mode);
+
+                let base_address = self.bus.peek_u16(self.program_counter.wrapping_add(1));
+                let (_, address) = offset_address(base_address, index);
+
+                format!(
+                    "{assembly_mnemonic} ${base_address:04X},{index_name} @ {address:04X} = {:02X}",
+                    self.bus.peek(address)
+                )
+            }
+
+            AddressingMode::Indirect => {
+                let pointer = self.bus.peek_u16(self.program_counter.wrapping_add(1));
+
+                // The upper byte is read without carrying into the next page
+                let address = self.bus.peek_u16_page_wrapped(pointer);
+
+                format!("{assembly_mnemonic} (${pointer:04X}) = {address:04X}")
+            }
+
+            AddressingMode::IndirectX => {
+                // The pointer wraps around inside the zero page
+                let pointer = arg_1.wrapping_add(self.register_x);
+
+                let address = self.bus.peek_u16_page_wrapped(build_address(pointer, 0x00));
+
+                format!(
+                    "{assembly_mnemonic} (${arg_1:02X},X) @ {pointer:02X} = {address:04X} = {:02X}",
+                    self.bus.peek(address)
+                )
+            }
+
+            AddressingMode::IndirectY => {
+                let base_address = self.indirect_indexed_base_address(arg_1);
+                let (_, address) = offset_address(base_address, self.register_y);
+
+                format!(
+                    "{assembly_mnemonic} (${arg_1:02X}),Y = {base_address:04X} @ {address:04X} = {:02X}",
+                    self.bus.peek(address)
+                )
+            }
+
+            AddressingMode::Relative => {
+                let (_, address) = relative_address(self.program_counter.wrapping_add(2), arg_1);
+
+                format!("{assembly_mnemonic} ${address:04X}")
+            }
+        }
+    }
+
+    /// Check if the operand of the instruction about to run at the program counter crosses a page boundary
+    /// when indexed, or when branching for the relative addressing.
+    pub(super) fn operand_crosses_page(&self, mode: AddressingMode) -> bool {
+        let arg_1 = self.bus.peek(self.program_counter.wrapping_add(1));
+
+        match mode {
+            AddressingMode::AbsoluteX | AddressingMode::AbsoluteY => crosses_page(
+                self.bus.peek_u16(self.program_counter.wrapping_add(1)),
+                self.index_register(mode).0,
+            ),
+
+            AddressingMode::IndirectY => crosses_page(self.indirect_indexed_base_address(arg_1), self.register_y),
+            AddressingMode::Relative => {
+                let (unfixed_address, address) = relative_address(self.program_counter.wrapping_add(2), arg_1);
+
+                unfixed_address != address
+            },
+            _ => false,
+        }
+    }
+
+    /// Read the base address of an indirect indexed operand from the given zero page pointer,
+    /// which wraps around inside the zero page.
+    fn indirect_indexed_base_address(&self, pointer: u8) -> u16 {
+        self.bus.peek_u16_page_wrapped(build_address(pointer, 0x00))
+    }
+
+    /// Get the value and name of the index register used by the given indexed addressing mode.
+    fn index_register(&self, mode: AddressingMode) -> (u8, char) {
+        match mode {
+            AddressingMode::ZeroPageX | AddressingMode::AbsoluteX | AddressingMode::IndirectX => (self.register_x, 'X'),
+            AddressingMode::ZeroPageY | AddressingMode::AbsoluteY | AddressingMode::IndirectY => (self.register_y, 'Y'),
+            _ => unreachable!("{mode:?} addressing is not indexed"),
+        }
+    }
+
+    /// Run a cycle resolving the address of the operand using the given addressing mode, returning
+    /// the address from the cycle the operand can be accessed on.
+    ///
+    /// The zero page addresses and pointers wrap around inside the zero page. The indexed modes read
+    /// first from the address with the upper byte still unfixed, spending an extra cycle to fix it
+    /// when the index crosses a page boundary, or always if `always_fix_address` is set, as writes
+    /// cannot undo accessing the wrong address.
+    pub(super) fn operand_address_cycles(
+        &mut self,
+        mode: AddressingMode,
+        always_fix_address: bool,
+    ) -> Result<Option<u16>, CycleError> {
+        if self.current_instruction_cycle >= mode.operand_cycle() {
+            return Ok(Some(self.operand_address(mode)));
+        }
+
+        match (mode, self.current_instruction_cycle) {
+            (_, 2)
+            | (AddressingMode::Absolute | AddressingMode::AbsoluteX | AddressingMode::AbsoluteY, 3) => {
+                let value = self.read_program_counter()?;
+                self.cache.push(value);
+                self.advance_program_counter(1);
+            }
+
+            (AddressingMode::ZeroPageX | AddressingMode::ZeroPageY | AddressingMode::IndirectX, 3) => {
+                // Dummy read while the index is added
+                let _ = self.bus.read(build_address(self.cache[0], 0x00))?;
+            }
+
+            (AddressingMode::IndirectX, 4 | 5) => {
+                let pointer = self.cache[0]
+                    .wrapping_add(self.register_x)
+                    .wrapping_add(self.current_instruction_cycle - 4);
+
+                self.cache.push(self.bus.read(build_address(pointer, 0x00))?);
+            }
+
+            (AddressingMode::IndirectY, 3 | 4) => {
+                let pointer = self.cache[0].wrapping_add(self.current_instruction_cycle - 3);
+
+                self.cache.push(self.bus.read(build_address(pointer, 0x00))?);
+            }
+
+            (AddressingMode::AbsoluteX | AddressingMode::AbsoluteY, 4) | (AddressingMode::IndirectY, 5) => {
+                let base_address = match mode {
+                    AddressingMode::IndirectY => build_address(self.cache[1], self.cache[2]),
+                    _ => build_address(self.cache[0], self.cache[1]),
+                };
+
+                let (unfixed_address, address) = offset_address(base_address, self.index_register(mode).0);
+
+                if !always_fix_address && unfixed_address == address {
+                    return Ok(Some(address));
+                }
+
+                // Dummy read with the upper byte of the address unfixed
+                let _ = self.bus.read(unfixed_address)?;
+            }
+
+            _ => return Err(CycleError::InstructionCycleOutOfBounds),
+        }
+
+        Ok(None)
+    }
+
+    /// Get the address of the operand from the bytes cached while resolving it.
+    fn operand_address(&self, mode: AddressingMode) -> u16 {
+        match mode {
+            AddressingMode::ZeroPage => build_address(self.cache[0], 0x00),
+            AddressingMode::ZeroPageX => build_address(self.cache[0].wrapping_add(self.register_x), 0x00),
+            AddressingMode::ZeroPageY => build_address(self.cache[0].wrapping_add(self.register_y), 0x00),
+            AddressingMode::Absolute => build_address(self.cache[0], self.cache[1]),
+            AddressingMode::AbsoluteX => build_address(self.cache[0], self.cache[1]).wrapping_add(self.register_x as u16),
+            AddressingMode::AbsoluteY => build_address(self.cache[0], self.cache[1]).wrapping_add(self.register_y as u16),
+            AddressingMode::IndirectX => build_address(self.cache[1], self.cache[2]),
+            AddressingMode::IndirectY => build_address(self.cache[1], self.cache[2]).wrapping_add(self.register_y as u16),
+            _ => unreachable!("{mode:?} addressing has no operand on memory"),
+        }
+    }
+
+    /// Run a cycle reading the operand using the given addressing mode, returning the read value
+    /// on the cycle that ends the instruction.
+    pub(super) fn read_operand_cycles(&mut self, mode: AddressingMode) -> Result<Option<u8>, CycleError> {
+        if mode == AddressingMode::Immediate {
+            if self.current_instruction_cycle != 2 {
+                return Err(CycleError::InstructionCycleOutOfBounds);
+            }
+
+            let value = self.read_program_counter()?;
+            self.advance_program_counter(1);
+
+            return Ok(Some(value));
+        }
+
+        let Some(address) = self.operand_address_cycles(mode, false)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(self.bus.read(address)?))
+    }
+
+    /// Run a cycle writing the given value using the given addressing mode, returning if the instruction ended.
+    pub(super) fn write_operand_cycles(&mut self, mode: AddressingMode, value: u8) -> Result<bool, CycleError> {
+        let Some(address) = self.operand_address_cycles(mode, true)? else {
+            return Ok(false);
+        };
+
+        self.bus.write(address, value)?;
+
+        Ok(true)
+    }
+
+    /// Run a cycle of a read-modify-write instruction using the given addressing mode and operation,
+    /// returning if the instruction ended.
+    ///
+    /// The unmodified operand is written back before the result of the operation, which is observable
+    /// on memory mapped registers.
+    pub(super) fn read_modify_write_cycles(
+        &mut self,
+        mode: AddressingMode,
+        operation: fn(&mut Self, u8) -> u8,
+    ) -> Result<bool, CycleError> {
+        let Some(address) = self.operand_address_cycles(mode, true)? else {
+            return Ok(false);
+        };
+
+        match self.current_instruction_cycle - mode.operand_cycle() {
+            0 => {
+                self.cache.push(self.bus.read(address)?);
+
+                Ok(false)
+            }
+
+            1 => {
+                let value = self.cache.last();
+
+                // Dummy write of the unmodified value while the operation is done
+                self.bus.write(address, value)?;
+
+                let result = operation(self, value);
+                self.cache.push(result);
+
+                Ok(false)
+            }
+
+            2 => {
+                self.bus.write(address, self.cache.last())?;
+
+                Ok(true)
+            }
+
+            _ => Err(CycleError::InstructionCycleOutOfBounds),
+        }
+    }
+
+    /// Run a cycle of an instruction applying the given operation to the accumulator, returning
+    /// if the instruction ended.
+    pub(super) fn accumulator_modify_cycles(&mut self, operation: fn(&mut Self, u8) -> u8) -> Result<bool, CycleError> {
+        match self.current_instruction_cycle {
+            2 => {
+                // Dummy read of the next opcode
+                let _ = self.read_program_counter()?;
+
+                self.accumulator = operation(self, self.accumulator);
+
+                Ok(true)
+            }
+
+            _ => Err(CycleError::InstructionCycleOutOfBounds),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+
+    /// Run the resolver from the first cycle after the opcode, returning the resolved address and
+    /// the cycle it was resolved on.
+    fn resolve(cpu: &mut Cpu, mode: AddressingMode, always_fix_address: bool) -> (u16, u8) {
+        // Skip the opcode, the operand starts on the second byte of the program
+        cpu.program_counter = 0x8001;
+
+        for cycle in 2..=6 {
+            cpu.current_instruction_cycle = cycle;
+
+            if let Some(address) = cpu.operand_address_cycles(mode, always_fix_address).unwrap() {
+                return (address, cycle);
+            }
+        }
+
+        panic!("The address was not resolved");
+    }
+
+    #[test]
+    fn test_resolve_zero_page() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(vec![0xEA, 0x44]))).unwrap();
+
+        assert_eq!(resolve(&mut cpu, AddressingMode::ZeroPage, false), (0x0044, 3));
+        assert_eq!(cpu.program_counter, 0x8002);
+    }
+
+    #[test]
+    fn test_resolve_zero_page_indexed_wraps() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(vec![0xEA, 0xF0]))).unwrap();
+        cpu.register_x = 0x20;
+        cpu.register_y = 0x11;
+
+        assert_eq!(resolve(&mut cpu, AddressingMode::ZeroPageX, false), (0x0010, 4));
+
+        cpu.cache.clear();
+        assert_eq!(resolve(&mut cpu, AddressingMode::ZeroPageY, false), (0x0001, 4));
+    }
+
+    #[test]
+    fn test_resolve_absolute() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(vec![0xEA, 0x34, 0x12]))).unwrap();
+
+        assert_eq!(resolve(&mut cpu, AddressingMode::Absolute, false), (0x1234, 4));
+        assert_eq!(cpu.program_counter, 0x8003);
+    }
+
+    #[test]
+    fn test_resolve_absolute_indexed_same_page() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(vec![0xEA, 0x10, 0x02]))).unwrap();
+        cpu.register_x = 0x05;
+
+        assert_eq!(resolve(&mut cpu, AddressingMode::AbsoluteX, false), (0x0215, 4));
+
+        // Writes always spend the cycle fixing the address
+        cpu.cache.clear();
+        assert_eq!(resolve(&mut cpu, AddressingMode::AbsoluteX, true), (0x0215, 5));
+    }
+
+    #[test]
+    fn test_resolve_absolute_indexed_page_cross() {
+        let cartridge = FlatCartridge::new(vec![0xEA, 0xF0, 0x80]);
+        let accesses = cartridge.accesses();
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_y = 0x20;
+
+        assert_eq!(resolve(&mut cpu, AddressingMode::AbsoluteY, false), (0x8110, 5));
+
+        // The dummy read is done with the upper byte of the address unfixed
+        assert_eq!(accesses.borrow().last(), Some(&FlatAccess::Read(0x8010)));
+    }
+
+    #[test]
+    fn test_resolve_absolute_indexed_wraps_around_memory() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(vec![0xEA, 0xFF, 0xFF]))).unwrap();
+        cpu.register_x = 0x02;
+
+        assert_eq!(resolve(&mut cpu, AddressingMode::AbsoluteX, false), (0x0001, 5));
+    }
+
+    #[test]
+    fn test_resolve_indexed_indirect_pointer_wraps() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(vec![0xEA, 0xFE]))).unwrap();
+        cpu.register_x = 0x01;
+
+        cpu.bus.write(0x00FF, 0x34).unwrap();
+        cpu.bus.write(0x0000, 0x12).unwrap();
+        cpu.bus.write(0x0100, 0x56).unwrap();
+
+        // The upper byte of the pointer is read from $0000 instead of $0100
+        assert_eq!(resolve(&mut cpu, AddressingMode::IndirectX, false), (0x1234, 6));
+    }
+
+    #[test]
+    fn test_resolve_indirect_indexed_pointer_wraps() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(vec![0xEA, 0xFF]))).unwrap();
+        cpu.register_y = 0x04;
+
+        cpu.bus.write(0x00FF, 0x00).unwrap();
+        cpu.bus.write(0x0000, 0x03).unwrap();
+        cpu.bus.write(0x0100, 0x05).unwrap();
+
+        assert_eq!(resolve(&mut cpu, AddressingMode::IndirectY, false), (0x0304, 5));
+    }
+
+    #[test]
+    fn test_resolve_indirect_indexed_page_cross() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(vec![0xEA, 0x10]))).unwrap();
+        cpu.register_y = 0x80;
+
+        cpu.bus.write(0x0010, 0x90).unwrap();
+        cpu.bus.write(0x0011, 0x02).unwrap();
+
+        assert_eq!(resolve(&mut cpu, AddressingMode::IndirectY, false), (0x0310, 6));
+
+        cpu.cache.clear();
+        assert_eq!(resolve(&mut cpu, AddressingMode::IndirectY, true), (0x0310, 6));
+    }
+
+    #[test]
+    fn test_resolved_address_is_kept() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(vec![0xEA, 0x34, 0x12]))).unwrap();
+        cpu.register_x = 0x01;
+
+        let (address, cycle) = resolve(&mut cpu, AddressingMode::AbsoluteX, true);
+
+        // The cycles after resolving the address keep returning it without touching the program counter
+        cpu.current_instruction_cycle = cycle + 1;
+        assert_eq!(cpu.operand_address_cycles(AddressingMode::AbsoluteX, true).unwrap(), Some(address));
+        assert_eq!(cpu.program_counter, 0x8003);
+    }
+
+    #[test]
+    fn test_operand_assembly_absolute() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(vec![0xEA, 0x34, 0x02]))).unwrap();
+        cpu.bus.write(0x0234, 0xAB).unwrap();
+
+        assert_eq!(cpu.operand_assembly(Mnemonic::Lda, true, AddressingMode::Absolute), "LDA $0234 = AB");
+
+        // The jumps show the target address without reading it
+        assert_eq!(cpu.operand_assembly(Mnemonic::Jmp, true, AddressingMode::Absolute), "JMP $0234");
+    }
+
+    #[test]
+    fn test_operand_assembly_low_addresses() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(vec![0xEA, 0x05, 0x00]))).unwrap();
+        cpu.register_x = 0x01;
+        cpu.register_y = 0x02;
+
+        // The pointers at $05 and $06 hold $0007
+        cpu.bus.write(0x0005, 0x07).unwrap();
+        cpu.bus.write(0x0006, 0x00).unwrap();
+        cpu.bus.write(0x0007, 0x00).unwrap();
+
+        // The addresses keep their leading zeros, two digits on the zero page and four elsewhere
+        for (mnemonic, mode, assembly) in [
+            (Mnemonic::Stx, AddressingMode::ZeroPage, "STX $05 = 07"),
+            (Mnemonic::Lda, AddressingMode::ZeroPageX, "LDA $05,X @ 06 = 00"),
+            (Mnemonic::Lda, AddressingMode::Absolute, "LDA $0005 = 07"),
+            (Mnemonic::Jmp, AddressingMode::Absolute, "JMP $0005"),
+            (Mnemonic::Jsr, AddressingMode::Absolute, "JSR $0005"),
+            (Mnemonic::Lda, AddressingMode::AbsoluteY, "LDA $0005,Y @ 0007 = 00"),
+            (Mnemonic::Jmp, AddressingMode::Indirect, "JMP ($0005) = 0007"),
+            (Mnemonic::Lda, AddressingMode::IndirectX, "LDA ($05,X) @ 06 = 0000 = 00"),
+            (Mnemonic::Lda, AddressingMode::IndirectY, "LDA ($05),Y = 0007 @ 0009 = 00"),
+        ] {
+            assert_eq!(cpu.operand_assembly(mnemonic, true, mode), assembly);
+        }
+    }
+
+    #[test]
+    fn test_read_modify_write_dummy_write_on_ram() {
+        // ASL, ROL, ROR, INC, DEC, SLO, RLA, SRE, RRA, DCP and ISB on the zero page
+        for opcode in [0x06, 0x26, 0x66, 0xE6, 0xC6, 0x07, 0x27, 0x47, 0x67, 0xC7, 0xE7] {
+            let cartridge = FlatCartridge::new(vec![
+                // XXX $10
+                opcode, 0x10,
+            ]);
+
+            let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+            cpu.bus.write(0x0010, 0x41).unwrap();
+
+            let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+            assert_eq!(instruction_data.idle_cycles, 4);
+
+            // Fetch the operand address and read the operand
+            cpu.cycle().unwrap();
+            cpu.cycle().unwrap();
+            assert_eq!(cpu.bus.read(0x0010).unwrap(), 0x41);
+
+            // Clobber the operand so the dummy write is observable
+            cpu.bus.write(0x0010, 0x00).unwrap();
+
+            cpu.cycle().unwrap();
+            assert_eq!(cpu.bus.read(0x0010).unwrap(), 0x41, "the opcode {opcode:02X} skipped the dummy write");
+
+            cpu.cycle().unwrap();
+            assert_ne!(cpu.bus.read(0x0010).unwrap(), 0x41, "the opcode {opcode:02X} did not write the result");
+        }
+    }
+}
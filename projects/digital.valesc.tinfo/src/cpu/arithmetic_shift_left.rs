@@ -0,0 +1,170 @@
+//! Holds the implementation of the `ASL` instruction.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::addressing::AddressingMode;
+use crate::cpu::Cpu;
+use crate::cpu::CpuStatusFlags;
+use crate::cpu::CycleError;
+
+impl<C: Cartridge> Cpu<C> {
+    /// Shift the given value one bit to the left, moving the old bit 7 into the carry flag and
+    /// setting the zero and negative flags from the result.
+    pub(super) fn arithmetic_shift_left(&mut self, value: u8) -> u8 {
+        let result = value << 1;
+
+        self.status.set(CpuStatusFlags::Carry, value & 0x80 != 0);
+        self.set_signedness(result);
+
+        result
+    }
+
+    /// Implements the accumulator arithmetic shift left instruction cycles.
+    pub(super) fn arithmetic_shift_left_accumulator_cycles(&mut self) -> Result<bool, CycleError> {
+        self.accumulator_modify_cycles(Cpu::arithmetic_shift_left)
+    }
+
+    /// Implements the arithmetic shift left instruction cycles for the given addressing mode.
+    pub(super) fn arithmetic_shift_left_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        self.read_modify_write_cycles(mode, Cpu::arithmetic_shift_left)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+    use crate::cpu::CpuBuilder;
+
+    #[test]
+    fn test_asl_accumulator() {
+        let cartridge = FlatCartridge::new(vec![
+            // ASL A
+            0x0A,
+        ]);
+
+        let mut cpu = CpuBuilder::new().cartridge(cartridge).accumulator(0x81).build().unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "ASL A");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 2);
+        assert_eq!(cpu.program_counter, 0x8001);
+
+        assert_eq!(cpu.accumulator, 0x02);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_asl_zero_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // ASL $10
+            0x06, 0x10,
+        ]);
+
+        let mut cpu = CpuBuilder::new().cartridge(cartridge).ram(0x0010, &[0x41]).build().unwrap();
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "ASL $10 = 41");
+        assert_eq!(instruction_data.idle_cycles, 4);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8002);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.bus.read(0x0010).unwrap(), 0x41);
+
+        // Dummy write of the unmodified value
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.bus.read(0x0010).unwrap(), 0x41);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.bus.read(0x0010).unwrap(), 0x82);
+
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_asl_zero_page_x() {
+        let cartridge = FlatCartridge::new(vec![
+            // ASL $FF,X
+            0x16, 0xFF,
+        ]);
+
+        let mut cpu = CpuBuilder::new()
+            .cartridge(cartridge)
+            .x(0x02)
+            .ram(0x0001, &[0x80])
+            .build()
+            .unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "ASL $FF,X @ 01 = 80");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 6);
+
+        assert_eq!(cpu.bus.read(0x0001).unwrap(), 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_asl_absolute_dummy_write() {
+        let cartridge = FlatCartridge::new(vec![
+            // ASL $8010
+            0x0E, 0x10, 0x80,
+        ]);
+
+        let accesses = cartridge.accesses();
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "ASL $8010 = EA");
+        assert_eq!(instruction_data.idle_cycles, 5);
+
+        accesses.borrow_mut().clear();
+
+        for _ in 0..instruction_data.idle_cycles {
+            cpu.cycle().unwrap();
+        }
+
+        assert_eq!(
+            *accesses.borrow(),
+            vec![
+                FlatAccess::Read(0x8001),
+                FlatAccess::Read(0x8002),
+                FlatAccess::Read(0x8010),
+                FlatAccess::Write(0x8010, 0xEA),
+                FlatAccess::Write(0x8010, 0xD4),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_asl_absolute_x() {
+        let cartridge = FlatCartridge::new(vec![
+            // ASL $01FF,X
+            0x1E, 0xFF, 0x01,
+        ]);
+
+        let mut cpu = CpuBuilder::new()
+            .cartridge(cartridge)
+            .x(0x01)
+            .ram(0x0200, &[0x01])
+            .build()
+            .unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "ASL $01FF,X @ 0200 = 01");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 7);
+        assert_eq!(cpu.program_counter, 0x8003);
+
+        assert_eq!(cpu.bus.read(0x0200).unwrap(), 0x02);
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+    }
+}
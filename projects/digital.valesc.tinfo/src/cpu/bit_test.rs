@@ -0,0 +1,113 @@
+//! Holds the implementation of the `BIT` instruction.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::addressing::AddressingMode;
+use crate::cpu::Cpu;
+use crate::cpu::CpuStatusFlags;
+use crate::cpu::CycleError;
+
+impl<C: Cartridge> Cpu<C> {
+    /// Implements the bit test instruction cycles for the given addressing mode.
+    ///
+    /// The operand is read through the bus, so reading registers like `PPUSTATUS` triggers their side effects.
+    pub(super) fn bit_test_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        let Some(value) = self.read_operand_cycles(mode)? else {
+            return Ok(false);
+        };
+
+        self.status.set(CpuStatusFlags::Zero, self.accumulator & value == 0);
+
+        // Bits 6 and 7 of the operand are copied as is
+        self.status.set(CpuStatusFlags::Overflow, value & CpuStatusFlags::Overflow.bits() != 0);
+        self.status.set(CpuStatusFlags::Negative, value & CpuStatusFlags::Negative.bits() != 0);
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+
+    fn bit_test_zero_page(accumulator: u8, value: u8) -> Cpu {
+        let cartridge = FlatCartridge::new(vec![
+            // BIT $01
+            0x24, 0x01,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = accumulator;
+        cpu.bus.write(0x0001, value).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, format!("BIT $01 = {value:02X}"));
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 3);
+        assert_eq!(cpu.program_counter, 0x8002);
+
+        // The accumulator is never modified
+        assert_eq!(cpu.accumulator, accumulator);
+
+        cpu
+    }
+
+    #[test]
+    fn test_bit_zero_page_bit_7_set() {
+        let cpu = bit_test_zero_page(0xFF, 0x80);
+
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(!cpu.status.contains(CpuStatusFlags::Overflow));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_bit_zero_page_bit_6_set() {
+        let cpu = bit_test_zero_page(0xFF, 0x40);
+
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(cpu.status.contains(CpuStatusFlags::Overflow));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_bit_zero_page_both_clear() {
+        let cpu = bit_test_zero_page(0xFF, 0x3F);
+
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(!cpu.status.contains(CpuStatusFlags::Overflow));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_bit_zero_page_zero_result() {
+        // Bits 6 and 7 are still copied even if the result is zero
+        let cpu = bit_test_zero_page(0x0F, 0xF0);
+
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(cpu.status.contains(CpuStatusFlags::Overflow));
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_bit_absolute() {
+        let cartridge = FlatCartridge::new(vec![
+            // BIT $0180
+            0x2C, 0x80, 0x01,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x01;
+        cpu.bus.write(0x0180, 0xC1).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "BIT $0180 = C1");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.program_counter, 0x8003);
+
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(cpu.status.contains(CpuStatusFlags::Overflow));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+    }
+}
@@ -1,71 +1,37 @@
 //! Implements the instructions related to branching the code flow in CPU.
 
-use crate::bus::BusError;
+use crate::cartridge::Cartridge;
 use crate::cpu::Cpu;
 use crate::cpu::CycleError;
 use crate::U16Ex;
-use crate::{build_address, cpu::impl_instruction_cycles};
-use crate::cpu::InstructionData;
+use crate::{build_address, relative_address};
 
 use super::CpuStatusFlags;
 
-impl Cpu {
-    /// Implements a generic implied branching instruction data.
-    pub(super) fn branch_instruction(&mut self, status_flag: CpuStatusFlags, not: bool) -> Result<InstructionData, BusError> {
-        let arg_1 = self.bus.read(self.program_counter + 1)?;
-        
-        let new_program_counter = self.program_counter + 2 + arg_1 as u16;
-
-        let mut idle_cycles = 1;
-
-        let contains_status_flag = self.status.contains(status_flag);
-        if (contains_status_flag && !not) || (!contains_status_flag && not) {
-            idle_cycles += 1;
-
-            if self.program_counter.upper_byte() != new_program_counter.upper_byte() {
-                idle_cycles += 1;
-            }
-        }
-
-        let prefix = match not {
-            false => {
-                match status_flag {
-                    CpuStatusFlags::Carry => "BCS",
-                    CpuStatusFlags::Zero => "BEQ",
-                    CpuStatusFlags::Overflow => "BVS",
-                    CpuStatusFlags::Negative => "BMI",
-                    _ => unimplemented!(),
-                }
-            },
-
-            true => {
-                match status_flag {
-                    CpuStatusFlags::Carry => "BCC",
-                    CpuStatusFlags::Zero => "BNE",
-                    CpuStatusFlags::Overflow => "BVC",
-                    CpuStatusFlags::Negative => "BPL",
-                    _ => unimplemented!(),
-                }
-            }
+impl<C: Cartridge> Cpu<C> {
+    /// Check if the branch of the current opcode is taken.
+    ///
+    /// The upper two bits of the branch opcodes select the flag to test, and the next one the value
+    /// the flag must have to take the branch.
+    pub(super) fn branch_taken(&self) -> bool {
+        let status_flag = match self.current_opcode >> 6 {
+            0 => CpuStatusFlags::Negative,
+            1 => CpuStatusFlags::Overflow,
+            2 => CpuStatusFlags::Carry,
+            _ => CpuStatusFlags::Zero,
         };
 
-        Ok(InstructionData {
-            arg_1: Some(arg_1),
-            arg_2: None,
-            assembly: format!("{prefix} ${new_program_counter:04X}"),
-            idle_cycles,
-        })
+        self.status.contains(status_flag) == (self.current_opcode & 0x20 != 0)
     }
 
-    /// Implements the implied set carry flag instruction cycles.
-    pub(super) fn branch_cycles(&mut self, status_flag: CpuStatusFlags, not: bool) -> Result<bool, CycleError> {
+    /// Implements the relative branch instruction cycles.
+    pub(super) fn branch_cycles(&mut self) -> Result<bool, CycleError> {
         match self.current_instruction_cycle {
             2 => {
                 let offset = self.read_program_counter()?;
-                self.program_counter += 1;
+                self.advance_program_counter(1);
 
-                let contains_status_flag = self.status.contains(status_flag);
-                if !((contains_status_flag && !not) || (!contains_status_flag && not)) {
+                if !self.branch_taken() {
                     return Ok(true);
                 }
 
@@ -75,29 +41,27 @@ impl Cpu {
             },
 
             3 => {
-                let _ = self.bus.read(self.program_counter + 1);
-                let new_program_counter = self.program_counter + self.cache[0] as u16;
+                let _ = self.bus.read(self.program_counter.wrapping_add(1));
+                let (broken_program_counter, new_program_counter) = relative_address(self.program_counter, self.cache[0]);
 
-                if new_program_counter.upper_byte() == self.program_counter.upper_byte() {
+                if broken_program_counter == new_program_counter {
                     self.program_counter = new_program_counter;
                     return Ok(true)
                 }
 
                 // Force broken PC
-                self.program_counter = build_address(
-                    new_program_counter.lower_byte(),
-                    self.program_counter.upper_byte()
-                );
+                self.program_counter = broken_program_counter;
+                self.cache.push(new_program_counter.upper_byte());
 
                 Ok(false)
             }
 
             4 => {
                 let _ = self.read_program_counter();
-                // Fix PCH.
+                // Fix PCH, which is incremented or decremented depending on the sign of the offset
                 self.program_counter = build_address(
                     self.program_counter.lower_byte(),
-                    self.program_counter.upper_byte() + 1
+                    self.cache[1]
                 );
 
                 Ok(true)
@@ -114,18 +78,18 @@ mod tests {
     use crate::cpu::tests::*;
 
     fn branching_relative_no_branching(opcode: u8, assembly_text: &str, not: bool, status_flag: CpuStatusFlags) {
-        let cartridge = MockCartridge::new(vec![
+        let cartridge = FlatCartridge::new(vec![
             opcode,
             0x20,
         ]);
 
-        let mut cpu = Cpu::new(Box::new(cartridge));
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
 
         if not {
             cpu.status |= status_flag;
         }
 
-        let instruction_data = cpu.cycle().unwrap().unwrap().instruction_data;
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
         assert_eq!(instruction_data.assembly, format!("{assembly_text} $8022"));
         assert_eq!(instruction_data.idle_cycles, 1);
 
@@ -176,18 +140,18 @@ mod tests {
     }
 
     fn branching_relative_branching_same_page(opcode: u8, assembly_text: &str, not: bool, status_flag: CpuStatusFlags) {
-        let cartridge = MockCartridge::new(vec![
+        let cartridge = FlatCartridge::new(vec![
             opcode,
             0x20,
         ]);
 
-        let mut cpu = Cpu::new(Box::new(cartridge));
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
 
         if !not {
             cpu.status |= status_flag;
         }
 
-        let instruction_data = cpu.cycle().unwrap().unwrap().instruction_data;
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
         assert_eq!(instruction_data.assembly, format!("{assembly_text} $8022"));
         assert_eq!(instruction_data.idle_cycles, 2);
 
@@ -241,32 +205,34 @@ mod tests {
     }
 
     fn branching_relative_branching_page_change(opcode: u8, assembly_text: &str, not: bool, status_flag: CpuStatusFlags) {
-        let cartridge = MockCartridge::new(vec![
-            opcode,
-            0xFE,
-        ]);
+        let mut prg_data = vec![0xEA; 0x200];
+        prg_data[0x00F0] = opcode;
+        prg_data[0x00F1] = 0x20;
 
-        let mut cpu = Cpu::new(Box::new(cartridge));
+        let cartridge = FlatCartridge::new(prg_data);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.program_counter = 0x80F0;
 
         if !not {
             cpu.status |= status_flag;
         }
 
-        let instruction_data = cpu.cycle().unwrap().unwrap().instruction_data;
-        assert_eq!(instruction_data.assembly, format!("{assembly_text} $8100"));
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, format!("{assembly_text} $8112"));
         assert_eq!(instruction_data.idle_cycles, 3);
 
-        assert_eq!(cpu.program_counter, 0x8001);
+        assert_eq!(cpu.program_counter, 0x80F1);
 
         cpu.cycle().unwrap();
-        assert_eq!(cpu.program_counter, 0x8002);
+        assert_eq!(cpu.program_counter, 0x80F2);
 
         cpu.cycle().unwrap();
         // Check if the incorrect value is being saved in propose
-        assert_eq!(cpu.program_counter, 0x8000);
+        assert_eq!(cpu.program_counter, 0x8012);
 
         cpu.cycle().unwrap();
-        assert_eq!(cpu.program_counter, 0x8100);
+        assert_eq!(cpu.program_counter, 0x8112);
     }
 
     #[test]
@@ -308,4 +274,80 @@ mod tests {
     fn test_branching_relative_branching_page_change_bpl() {
         branching_relative_branching_page_change(0x10, "BPL", true, CpuStatusFlags::Negative);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_branching_relative_backwards_same_page() {
+        let mut prg_data = vec![0xEA; 0x20];
+
+        // BNE $8008
+        prg_data[0x0010] = 0xD0;
+        prg_data[0x0011] = 0xF6;
+
+        let cartridge = FlatCartridge::new(prg_data);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.program_counter = 0x8010;
+        cpu.status = CpuStatusFlags::empty();
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "BNE $8008");
+        assert_eq!(instruction_data.idle_cycles, 2);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8012);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8008);
+    }
+
+    #[test]
+    fn test_branching_relative_backwards_page_change() {
+        let mut prg_data = vec![0xEA; 0x200];
+
+        // BCS $80FE
+        prg_data[0x0100] = 0xB0;
+        prg_data[0x0101] = 0xFC;
+
+        let cartridge = FlatCartridge::new(prg_data);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.program_counter = 0x8100;
+        cpu.status = CpuStatusFlags::Carry;
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "BCS $80FE");
+        assert_eq!(instruction_data.idle_cycles, 3);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8102);
+
+        cpu.cycle().unwrap();
+        // The lower byte wraps around while the upper byte is still the old page one
+        assert_eq!(cpu.program_counter, 0x81FE);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x80FE);
+    }
+
+    #[test]
+    fn test_branching_relative_wraps_around() {
+        // BNE $0010
+        let cartridge = FlatCartridge::new(vec![0xD0, 0x1E]).with_base_address(0xFFF0);
+
+        let mut cpu = Cpu::new_with_program_counter(Box::new(cartridge), 0xFFF0);
+        cpu.status = CpuStatusFlags::empty();
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "BNE $0010");
+        assert_eq!(instruction_data.idle_cycles, 3);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0xFFF2);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0xFF10);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x0010);
+    }
+}
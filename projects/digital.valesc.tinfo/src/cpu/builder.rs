@@ -0,0 +1,222 @@
+//! Holds the builder of the CPU, which primes its registers and its memory before the first
+//! instruction to set up the scenarios of the tests and the debugging sessions.
+
+use thiserror::Error;
+
+use crate::bus::{BusError, RamInitPattern};
+use crate::cartridge::Cartridge;
+use crate::cpu::{Cpu, CpuStatusFlags};
+
+#[derive(Error, Debug)]
+/// Errors that may happen when building a CPU with a [CpuBuilder].
+pub enum CpuBuilderError {
+    #[error("No cartridge was given to the builder")]
+    /// No cartridge was given to the builder.
+    MissingCartridge,
+
+    #[error("Accessing the bus failed: {0}")]
+    /// Accessing the bus failed, reading the reset vector or writing the memory.
+    BusError(#[from] BusError),
+}
+
+/// Builds a [Cpu] with the given registers and memory, leaving the rest as they are on power-on.
+///
+/// The program counter is read from the reset vector of the cartridge unless it's given, and the
+/// memory is written through the bus before the first instruction, in the order it was given.
+#[derive(Debug)]
+pub struct CpuBuilder<C: Cartridge = Box<dyn Cartridge>> {
+    /// The cartridge attached to the bus.
+    cartridge: Option<C>,
+
+    /// The program counter register, if not the one of the reset vector.
+    program_counter: Option<u16>,
+
+    /// The accumulator register.
+    accumulator: Option<u8>,
+
+    /// The X index register.
+    register_x: Option<u8>,
+
+    /// The Y index register.
+    register_y: Option<u8>,
+
+    /// The status register.
+    status: Option<CpuStatusFlags>,
+
+    /// The stack pointer register.
+    stack_pointer: Option<u8>,
+
+    /// The values the RAM of the CPU holds before the memory is written.
+    ram_init: RamInitPattern,
+
+    /// The blocks of bytes written to the memory, along with the address of their first byte.
+    memory: Vec<(u16, Vec<u8>)>,
+
+    /// Whether the instructions are disassembled in the snapshots.
+    trace: Option<bool>,
+}
+
+impl<C: Cartridge> CpuBuilder<C> {
+    /// Create a new [CpuBuilder] with no cartridge, leaving every register as it is on power-on.
+    pub fn new() -> CpuBuilder<C> {
+        CpuBuilder {
+            cartridge: None,
+            program_counter: None,
+            accumulator: None,
+            register_x: None,
+            register_y: None,
+            status: None,
+            stack_pointer: None,
+            ram_init: RamInitPattern::default(),
+            memory: vec![],
+            trace: None,
+        }
+    }
+
+    /// Set the cartridge attached to the bus, required to build the CPU.
+    pub fn cartridge(mut self, cartridge: C) -> CpuBuilder<C> {
+        self.cartridge = Some(cartridge);
+        self
+    }
+
+    /// Set the program counter register, in place of the address in the reset vector.
+    pub fn program_counter(mut self, address: u16) -> CpuBuilder<C> {
+        self.program_counter = Some(address);
+        self
+    }
+
+    /// Set the accumulator register.
+    pub fn accumulator(mut self, value: u8) -> CpuBuilder<C> {
+        self.accumulator = Some(value);
+        self
+    }
+
+    /// Set the X index register.
+    pub fn x(mut self, value: u8) -> CpuBuilder<C> {
+        self.register_x = Some(value);
+        self
+    }
+
+    /// Set the Y index register.
+    pub fn y(mut self, value: u8) -> CpuBuilder<C> {
+        self.register_y = Some(value);
+        self
+    }
+
+    /// Set the flags of the status register.
+    pub fn status(mut self, status: CpuStatusFlags) -> CpuBuilder<C> {
+        self.status = Some(status);
+        self
+    }
+
+    /// Set the offset of the top of the stack from the start of the stack page.
+    pub fn stack_pointer(mut self, value: u8) -> CpuBuilder<C> {
+        self.stack_pointer = Some(value);
+        self
+    }
+
+    /// Set the values the RAM of the CPU holds on power-on, zeroed by default, before the memory
+    /// given with [CpuBuilder::ram] is written.
+    pub fn ram_init(mut self, ram_init: RamInitPattern) -> CpuBuilder<C> {
+        self.ram_init = ram_init;
+        self
+    }
+
+    /// Write the given bytes to the memory starting at the given address, wrapping around the end
+    /// of the address space.
+    ///
+    /// The bytes are written through the bus like the CPU would, so they reach the cartridge or the
+    /// mirrors of the internal RAM depending on the address.
+    pub fn ram(mut self, address: u16, bytes: &[u8]) -> CpuBuilder<C> {
+        self.memory.push((address, bytes.to_vec()));
+        self
+    }
+
+    /// Set whether the instructions are disassembled in the snapshots, see [Cpu::set_trace].
+    pub fn trace(mut self, enabled: bool) -> CpuBuilder<C> {
+        self.trace = Some(enabled);
+        self
+    }
+
+    /// Build the [Cpu], ready to fetch the instruction at its program counter.
+    pub fn build(self) -> Result<Cpu<C>, CpuBuilderError> {
+        let cartridge = self.cartridge.ok_or(CpuBuilderError::MissingCartridge)?;
+
+        let mut cpu = match self.program_counter {
+            Some(program_counter) => Cpu::from_cartridge_with_program_counter(cartridge, program_counter),
+            None => Cpu::from_cartridge(cartridge)?,
+        };
+
+        cpu.bus.init_ram(self.ram_init);
+
+        for (address, bytes) in &self.memory {
+            for (offset, &byte) in bytes.iter().enumerate() {
+                cpu.bus.write(address.wrapping_add(offset as u16), byte)?;
+            }
+        }
+
+        cpu.accumulator = self.accumulator.unwrap_or(cpu.accumulator);
+        cpu.register_x = self.register_x.unwrap_or(cpu.register_x);
+        cpu.register_y = self.register_y.unwrap_or(cpu.register_y);
+        cpu.status = self.status.unwrap_or(cpu.status);
+        cpu.stack_pointer = self.stack_pointer.unwrap_or(cpu.stack_pointer);
+        cpu.trace = self.trace.unwrap_or(cpu.trace);
+
+        Ok(cpu)
+    }
+}
+
+impl<C: Cartridge> Default for CpuBuilder<C> {
+    fn default() -> Self {
+        CpuBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+
+    #[test]
+    fn test_builder_defaults() {
+        let cpu = CpuBuilder::new().cartridge(FlatCartridge::new(vec![0xEA])).build().unwrap();
+
+        // The same state as a CPU just powered on
+        let powered_on = Cpu::new(Box::new(FlatCartridge::new(vec![0xEA]))).unwrap();
+        assert_eq!(cpu.program_counter, powered_on.program_counter);
+        assert_eq!(cpu.accumulator, powered_on.accumulator);
+        assert_eq!(cpu.status, powered_on.status);
+        assert_eq!(cpu.stack_pointer, powered_on.stack_pointer);
+        assert_eq!(cpu.cpu_cycles, powered_on.cpu_cycles);
+    }
+
+    #[test]
+    fn test_builder_ram() {
+        let cpu = CpuBuilder::new()
+            .cartridge(FlatCartridge::new(vec![0xEA]))
+            .ram(0x07FF, &[0x12, 0x34])
+            .build()
+            .unwrap();
+
+        // The second byte lands on the first mirror of the internal RAM
+        assert_eq!(cpu.bus.peek(0x07FF), 0x12);
+        assert_eq!(cpu.bus.peek(0x0000), 0x34);
+    }
+
+    #[test]
+    fn test_builder_ram_init() {
+        let cpu = CpuBuilder::new()
+            .cartridge(FlatCartridge::new(vec![0xEA]))
+            .ram_init(RamInitPattern::AllOnes)
+            .ram(0x0010, &[0x00])
+            .build()
+            .unwrap();
+
+        assert_eq!(cpu.peek_range(0x000F, 3), [0xFF, 0x00, 0xFF]);
+    }
+
+    #[test]
+    fn test_builder_missing_cartridge() {
+        assert!(matches!(CpuBuilder::<FlatCartridge>::new().build(), Err(CpuBuilderError::MissingCartridge)));
+    }
+}
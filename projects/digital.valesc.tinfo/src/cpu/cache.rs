@@ -0,0 +1,100 @@
+//! Holds the buffer where the cycles of an instruction keep the data later cycles need.
+
+use std::ops::Index;
+
+/// The most bytes an instruction caches, taken by the indirect read-modify-write instructions that
+/// cache the pointer, the two bytes of the address, the read value and the result.
+const CACHE_CAPACITY: usize = 5;
+
+/// A fixed-size stack of the bytes cached by the cycles of the running instruction, avoiding any
+/// allocation on the hot path of the CPU.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(super) struct InstructionCache {
+    /// The cached bytes, the ones past the length are stale.
+    bytes: [u8; CACHE_CAPACITY],
+
+    /// The number of bytes cached.
+    length: usize,
+}
+
+impl InstructionCache {
+    /// Cache a byte after the ones already cached.
+    pub(super) fn push(&mut self, value: u8) {
+        debug_assert!(self.length < CACHE_CAPACITY, "The instruction cache overflowed");
+
+        self.bytes[self.length] = value;
+        self.length += 1;
+    }
+
+    /// Get the last cached byte.
+    pub(super) fn last(&self) -> u8 {
+        self[self.length.wrapping_sub(1)]
+    }
+
+    /// Forget the cached bytes, ready for the next instruction.
+    pub(super) fn clear(&mut self) {
+        self.length = 0;
+    }
+}
+
+impl Index<usize> for InstructionCache {
+    type Output = u8;
+
+    fn index(&self, index: usize) -> &u8 {
+        &self.bytes[..self.length][index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_and_index() {
+        let mut cache = InstructionCache::default();
+
+        cache.push(0x12);
+        cache.push(0x34);
+
+        assert_eq!(cache[0], 0x12);
+        assert_eq!(cache[1], 0x34);
+        assert_eq!(cache.last(), 0x34);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut cache = InstructionCache::default();
+
+        for value in 0..CACHE_CAPACITY as u8 {
+            cache.push(value);
+        }
+
+        cache.clear();
+        cache.push(0xFF);
+
+        assert_eq!(cache[0], 0xFF);
+        assert_eq!(cache.last(), 0xFF);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_stale_bytes_are_not_readable() {
+        let mut cache = InstructionCache::default();
+        cache.push(0x12);
+        cache.clear();
+
+        let _ = cache[0];
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "The instruction cache overflowed")]
+    fn test_overflow() {
+        let mut cache = InstructionCache::default();
+
+        for value in 0..=CACHE_CAPACITY as u8 {
+            cache.push(value);
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Holds the shadow call stack, which follows the subroutine calls and the interrupts to give a
+//! stack trace of the running program.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::{Cpu, CpuEvent};
+
+/// The number of innermost frames of the call stack attached to the errors of the CPU.
+pub(super) const ERROR_CALL_STACK_FRAMES: usize = 8;
+
+/// The way a [CallFrame] was entered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallKind {
+    /// A `JSR`, left by a `RTS`.
+    Subroutine,
+
+    /// A `BRK` or a hardware interrupt, left by a `RTI`.
+    Interrupt,
+}
+
+/// A call followed by the shadow call stack, given by [Cpu::call_stack].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CallFrame {
+    /// The way the call was entered.
+    pub kind: CallKind,
+
+    /// The address the call is expected to return to.
+    pub return_address: u16,
+
+    /// The address of the called subroutine or interrupt handler.
+    pub target: u16,
+
+    /// The number of cycles the CPU had run when the call was made.
+    pub cycles: u64,
+}
+
+impl<C: Cartridge> Cpu<C> {
+    /// Start following the calls made by `JSR` and the interrupts in a shadow call stack, starting
+    /// empty.
+    ///
+    /// Every return pops the innermost frame. A return to an address other than the one expected
+    /// by the frame, because the program changed the stack, or with no frame to pop raises a
+    /// [CpuEvent::CallStackMismatch].
+    pub fn enable_call_stack(&mut self) {
+        self.call_stack.get_or_insert_with(Vec::new);
+    }
+
+    /// Stop following the calls, dropping the shadow call stack.
+    pub fn disable_call_stack(&mut self) {
+        self.call_stack = None;
+    }
+
+    /// Get the frames of the shadow call stack, from the outermost to the innermost one, empty
+    /// unless it was enabled with [Cpu::enable_call_stack].
+    pub fn call_stack(&self) -> &[CallFrame] {
+        self.call_stack.as_deref().unwrap_or_default()
+    }
+
+    /// Push a frame to the shadow call stack, if it's enabled.
+    pub(super) fn push_call_frame(&mut self, kind: CallKind, return_address: u16, target: u16) {
+        if let Some(call_stack) = &mut self.call_stack {
+            call_stack.push(CallFrame {
+                kind,
+                return_address,
+                target,
+                cycles: self.cpu_cycles,
+            });
+        }
+    }
+
+    /// Pop the innermost frame of the shadow call stack once a return jumped to the given address,
+    /// if it's enabled, raising an event if it's not the expected one.
+    pub(super) fn pop_call_frame(&mut self, return_address: u16) {
+        let Some(call_stack) = &mut self.call_stack else {
+            return;
+        };
+
+        let expected = call_stack.pop().map(|frame| frame.return_address);
+
+        if expected != Some(return_address) {
+            self.events.push(CpuEvent::CallStackMismatch {
+                program_counter: self.current_instruction_address,
+                expected,
+                actual: return_address,
+                cycles: self.cpu_cycles,
+            });
+        }
+    }
+
+    /// Get the innermost frames of the shadow call stack attached to the errors, from the innermost
+    /// to the outermost one.
+    pub(super) fn error_call_stack(&self) -> Vec<CallFrame> {
+        self.call_stack().iter().rev().take(ERROR_CALL_STACK_FRAMES).copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+    use crate::cpu::CpuError;
+
+    /// Build a program nesting three subroutines, each one calling the next.
+    fn nested_subroutines() -> FlatCartridge {
+        let mut prg_data = vec![0xEA; 0x40];
+
+        // JSR $8010
+        prg_data[0x00..0x03].copy_from_slice(&[0x20, 0x10, 0x80]);
+
+        // JSR $8020, RTS
+        prg_data[0x10..0x14].copy_from_slice(&[0x20, 0x20, 0x80, 0x60]);
+
+        // JSR $8030, RTS
+        prg_data[0x20..0x24].copy_from_slice(&[0x20, 0x30, 0x80, 0x60]);
+
+        // NOP, RTS
+        prg_data[0x30..0x32].copy_from_slice(&[0xEA, 0x60]);
+
+        FlatCartridge::new(prg_data)
+    }
+
+    #[test]
+    fn test_call_stack() {
+        let mut cpu = Cpu::new(Box::new(nested_subroutines())).unwrap();
+        cpu.enable_call_stack();
+
+        cpu.batch_run_full_instruction(3);
+
+        let frame = |return_address, target, cycles| CallFrame {
+            kind: CallKind::Subroutine,
+            return_address,
+            target,
+            cycles,
+        };
+
+        assert_eq!(
+            cpu.call_stack(),
+            [frame(0x8003, 0x8010, 13), frame(0x8013, 0x8020, 19), frame(0x8023, 0x8030, 25)]
+        );
+
+        // NOP, RTS and RTS
+        cpu.batch_run_full_instruction(3);
+
+        assert_eq!(cpu.program_counter, 0x8013);
+        assert_eq!(cpu.call_stack(), [frame(0x8003, 0x8010, 13)]);
+        assert!(cpu.take_events().is_empty());
+    }
+
+    #[test]
+    fn test_call_stack_disabled() {
+        let mut cpu = Cpu::new(Box::new(nested_subroutines())).unwrap();
+        cpu.batch_run_full_instruction(3);
+
+        assert!(cpu.call_stack().is_empty());
+    }
+
+    #[test]
+    fn test_call_stack_mismatch() {
+        let mut prg_data = vec![0xEA; 0x20];
+
+        // JSR $8010
+        prg_data[0x00..0x03].copy_from_slice(&[0x20, 0x10, 0x80]);
+
+        // PLA, RTS
+        prg_data[0x10..0x12].copy_from_slice(&[0x68, 0x60]);
+
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(prg_data))).unwrap();
+        cpu.enable_call_stack();
+
+        // The extra pull drops the lower byte of the return address
+        cpu.batch_run_full_instruction(3);
+
+        assert_eq!(
+            cpu.take_events(),
+            [CpuEvent::CallStackMismatch { program_counter: 0x8011, expected: Some(0x8003), actual: 0x0081, cycles: 23 }]
+        );
+
+        assert!(cpu.call_stack().is_empty());
+    }
+
+    #[test]
+    fn test_call_stack_empty_return() {
+        // RTS
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(vec![0x60]))).unwrap();
+        cpu.enable_call_stack();
+
+        cpu.run_full_instruction();
+
+        assert_eq!(
+            cpu.take_events(),
+            [CpuEvent::CallStackMismatch { program_counter: 0x8000, expected: None, actual: 0x0001, cycles: 13 }]
+        );
+    }
+
+    #[test]
+    fn test_call_stack_interrupt() {
+        let mut prg_data = vec![0xEA; 0x4000];
+
+        // JSR $9000
+        prg_data[0x0000..0x0003].copy_from_slice(&[0x20, 0x00, 0x90]);
+
+        // NOP, NOP, RTI
+        prg_data[0x1000..0x1003].copy_from_slice(&[0xEA, 0xEA, 0x40]);
+
+        let cartridge = FlatCartridge::new(prg_data).with_nmi_vector(0x9001);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.enable_call_stack();
+
+        cpu.run_full_instruction();
+        cpu.raise_nmi();
+        cpu.run_full_instruction();
+
+        assert_eq!(cpu.call_stack()[1].kind, CallKind::Interrupt);
+        assert_eq!(cpu.call_stack()[1].return_address, 0x9000);
+        assert_eq!(cpu.call_stack()[1].target, 0x9001);
+
+        // NOP, RTI
+        cpu.batch_run_full_instruction(2);
+
+        assert_eq!(cpu.program_counter, 0x9000);
+        assert_eq!(cpu.call_stack().len(), 1);
+        assert!(cpu.take_events().is_empty());
+    }
+
+    #[test]
+    fn test_call_stack_in_errors() {
+        let mut prg_data = vec![0xEA; 0x20];
+
+        // JSR $8010
+        prg_data[0x00..0x03].copy_from_slice(&[0x20, 0x10, 0x80]);
+
+        // An opcode that jams the CPU
+        prg_data[0x10] = 0x02;
+
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(prg_data))).unwrap();
+        cpu.enable_call_stack();
+        cpu.run_full_instruction();
+
+        let Err(CpuError::Jammed { call_stack, .. }) = cpu.cycle() else {
+            panic!("The CPU didn't jam");
+        };
+
+        assert_eq!(call_stack, cpu.call_stack());
+    }
+}
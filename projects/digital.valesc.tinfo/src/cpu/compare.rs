@@ -0,0 +1,139 @@
+//! Holds the implementation of the `CPX` and `CPY` instructions.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::addressing::AddressingMode;
+use crate::cpu::Cpu;
+use crate::cpu::CpuStatusFlags;
+use crate::cpu::CycleError;
+
+impl<C: Cartridge> Cpu<C> {
+    /// Compare the given register value against the given value, setting the carry flag if the register
+    /// is greater or equal and the zero and negative flags from the subtraction of both.
+    pub(super) fn compare(&mut self, register: u8, value: u8) {
+        self.status.set(CpuStatusFlags::Carry, register >= value);
+        self.set_signedness(register.wrapping_sub(value));
+    }
+
+    /// Implements the compare X register instruction cycles for the given addressing mode.
+    pub(super) fn compare_x_register_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        let Some(value) = self.read_operand_cycles(mode)? else {
+            return Ok(false);
+        };
+
+        self.compare(self.register_x, value);
+
+        Ok(true)
+    }
+
+    /// Implements the compare Y register instruction cycles for the given addressing mode.
+    pub(super) fn compare_y_register_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        let Some(value) = self.read_operand_cycles(mode)? else {
+            return Ok(false);
+        };
+
+        self.compare(self.register_y, value);
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+
+    #[test]
+    fn test_cpx_immediate_loop() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDX #$10
+            0xA2, 0x10,
+
+            // CPX #$10
+            0xE0, 0x10,
+
+            // BEQ $8008
+            0xF0, 0x02,
+
+            // LDX #$00
+            0xA2, 0x00,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+
+        cpu.run_full_instruction();
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "CPX #$10");
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "BEQ $8008");
+        assert_eq!(cpu.program_counter, 0x8008);
+        assert_eq!(cpu.register_x, 0x10);
+    }
+
+    #[test]
+    fn test_cpx_zero_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // CPX $20
+            0xE4, 0x20,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_x = 0x10;
+        cpu.bus.write(0x0020, 0x20).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "CPX $20 = 20");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 3);
+
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_cpy_absolute() {
+        let cartridge = FlatCartridge::new(vec![
+            // CPY $0180
+            0xCC, 0x80, 0x01,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_y = 0x40;
+        cpu.bus.write(0x0180, 0x01).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "CPY $0180 = 01");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.program_counter, 0x8003);
+
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_cpy_immediate_equal() {
+        let cartridge = FlatCartridge::new(vec![
+            // CPY #$80
+            0xC0, 0x80,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_y = 0x80;
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "CPY #$80");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 2);
+
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+}
@@ -0,0 +1,78 @@
+//! Holds the recording of the coverage, which tells the addresses ever executed, read or written.
+
+use crate::cartridge::Cartridge;
+use crate::coverage::CoverageRecorder;
+use crate::cpu::Cpu;
+
+impl<C: Cartridge> Cpu<C> {
+    /// Start recording the addresses executed by the instructions and the ones read or written
+    /// through the bus, keeping the coverage already recorded if it was enabled.
+    ///
+    /// The bytes of an instruction are recorded as executed once its opcode is fetched, the
+    /// interrupt sequences execute none.
+    pub fn enable_coverage(&mut self) {
+        self.bus.enable_coverage();
+    }
+
+    /// Stop recording the coverage, returning the one recorded if it was enabled.
+    pub fn disable_coverage(&mut self) -> Option<CoverageRecorder> {
+        self.bus.take_coverage()
+    }
+
+    /// Get the coverage recorded since it was enabled, if it is.
+    pub fn coverage(&self) -> Option<&CoverageRecorder> {
+        self.bus.coverage()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+
+    #[test]
+    fn test_coverage() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDX #$00
+            0xA2, 0x00,
+
+            // BEQ $8006
+            0xF0, 0x02,
+
+            // Padding skipped by the taken branch
+            0xFF, 0xFF,
+
+            // BNE $800A
+            0xD0, 0x02,
+
+            // STX $10
+            0x86, 0x10,
+
+            // JMP $800A
+            0x4C, 0x0A, 0x80,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        assert!(cpu.coverage().is_none());
+
+        cpu.enable_coverage();
+        cpu.batch_run_full_instruction(6);
+
+        let coverage = cpu.coverage().unwrap();
+
+        // Both the taken and the not taken branches ran, but not the padding
+        let executed: Vec<u16> = (0x8000..=0x800F).filter(|&address| coverage.is_executed(address)).collect();
+        assert_eq!(executed, [0x8000, 0x8001, 0x8002, 0x8003, 0x8006, 0x8007, 0x8008, 0x8009, 0x800A, 0x800B, 0x800C]);
+
+        // The opcode fetches are reads too
+        assert!(coverage.is_read(0x8000));
+        assert!(coverage.is_written(0x0010));
+        assert!(!coverage.is_read(0x0010));
+        assert!(!coverage.is_written(0x8000));
+
+        // Disabling the coverage hands over the recorded one
+        let coverage = cpu.disable_coverage().unwrap();
+        assert!(coverage.is_executed(0x8000));
+        assert!(cpu.coverage().is_none());
+    }
+}
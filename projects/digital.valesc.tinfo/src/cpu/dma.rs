@@ -0,0 +1,197 @@
+//! Holds the OAM DMA, which copies a page of the CPU memory space to the OAM of the PPU while the
+//! CPU is stalled, writing OAMDATA, so the copy starts at OAMADDR and wraps around the OAM.
+
+use crate::build_address;
+use crate::bus::BusError;
+use crate::cartridge::Cartridge;
+use crate::cpu::Cpu;
+use crate::ppu::OAM_SIZE;
+
+/// The number of cycles the OAM DMA stalls the CPU when it's requested on an even cycle, one
+/// halt cycle followed by a read and a write for every byte.
+const OAM_DMA_CYCLES: u16 = 1 + 2 * OAM_SIZE as u16;
+
+/// An OAM DMA in progress.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(super) struct OamDma {
+    /// The upper byte of the addresses copied.
+    page: u8,
+
+    /// The number of cycles the copy stalls the CPU.
+    length: u16,
+
+    /// The cycle of the copy that is going to be run next, starting at 0.
+    cycle: u16,
+
+    /// The byte read on the last read cycle, written to the OAM on the next cycle.
+    value: u8,
+}
+
+impl OamDma {
+    /// Create a new [OamDma] of the given page, given the number of cycles the CPU had run when
+    /// the copy was requested.
+    ///
+    /// The reads must land on even cycles, so a request on an odd cycle takes an extra cycle to
+    /// align them.
+    fn new(page: u8, cycles: u64) -> OamDma {
+        OamDma {
+            page,
+            length: OAM_DMA_CYCLES + (cycles % 2) as u16,
+            cycle: 0,
+            value: 0,
+        }
+    }
+}
+
+impl<C: Cartridge> Cpu<C> {
+    /// Run a cycle of the OAM DMA in progress, starting the one requested to the bus when an
+    /// instruction ends, returning if the cycle was taken by the copy.
+    pub(super) fn oam_dma_cycle(&mut self) -> Result<bool, BusError> {
+        if self.oam_dma.is_none() && self.current_instruction_cycle == 1 {
+            self.oam_dma = self.bus.take_oam_dma_request().map(|page| OamDma::new(page, self.cpu_cycles));
+        }
+
+        let Some(mut oam_dma) = self.oam_dma else {
+            return Ok(false);
+        };
+
+        self.cpu_cycles += 1;
+
+        // The halt and alignment cycles come before the reads and writes
+        let transfer_cycle = oam_dma.cycle.checked_sub(oam_dma.length - 2 * OAM_SIZE as u16);
+
+        if let Some(transfer_cycle) = transfer_cycle {
+            let index = (transfer_cycle / 2) as u8;
+
+            match transfer_cycle % 2 {
+                0 => oam_dma.value = self.bus.read(build_address(index, oam_dma.page))?,
+                _ => self.bus.write_oam_data(oam_dma.value),
+            }
+        }
+
+        oam_dma.cycle += 1;
+
+        self.oam_dma = match oam_dma.cycle == oam_dma.length {
+            true => None,
+            false => Some(oam_dma),
+        };
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+
+    /// Copy the page $0A00, a mirror of $0200, to the OAM after an instruction taking the given
+    /// bytes and cycles, returning the number of cycles from the start of the store to the start
+    /// of the next instruction.
+    fn run_oam_dma(first_instruction: &[u8], first_instruction_cycles: u64) -> (Cpu, u64) {
+        let mut program = first_instruction.to_vec();
+
+        // STA $4014
+        program.extend_from_slice(&[0x8D, 0x14, 0x40]);
+
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(program))).unwrap();
+        cpu.accumulator = 0x0A;
+
+        for index in 0..=0xFF {
+            cpu.bus.write(0x0200 + index, index as u8 ^ 0xFF).unwrap();
+        }
+
+        let cpu_cycles = cpu.cpu_cycles;
+        cpu.run_full_instruction();
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, first_instruction_cycles);
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        // The write-only register peeks the opcode left on the data bus
+        assert_eq!(instruction_data.assembly, "STA $4014 = 8D");
+
+        let snapshot = cpu.run_until_next_snapshot();
+
+        (cpu, snapshot.cpu_cycles - cpu_cycles)
+    }
+
+    #[test]
+    fn test_oam_dma_even_cycle() {
+        // NOP $10, taking three cycles
+        let (cpu, cycles) = run_oam_dma(&[0x04, 0x10], 3);
+
+        // The store ends on the cycle 14
+        assert_eq!(cycles, 4 + 513);
+
+        // The attribute bytes lack the bits 2 to 4
+        let expected_oam: Vec<u8> = (0..=0xFF)
+            .map(|index: u8| match index % 4 {
+                2 => (index ^ 0xFF) & 0xE3,
+                _ => index ^ 0xFF,
+            })
+            .collect();
+
+        assert_eq!(cpu.bus.oam().to_vec(), expected_oam);
+    }
+
+    #[test]
+    fn test_oam_dma_odd_cycle() {
+        // NOP
+        let (cpu, cycles) = run_oam_dma(&[0xEA], 2);
+
+        // The store ends on the cycle 13
+        assert_eq!(cycles, 4 + 514);
+        assert_eq!(cpu.bus.oam()[0x00], 0xFF);
+        assert_eq!(cpu.bus.oam()[0xFF], 0x00);
+    }
+
+    #[test]
+    fn test_oam_dma_from_oamaddr() {
+        let cartridge = FlatCartridge::new(vec![
+            // STA $4014
+            0x8D, 0x14, 0x40,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x02;
+
+        for index in 0..=0xFF {
+            cpu.bus.write(0x0200 + index, index as u8).unwrap();
+        }
+
+        cpu.bus.write(0x2003, 0x81).unwrap();
+        cpu.step().unwrap();
+        cpu.step().unwrap();
+
+        // The copy starts at OAMADDR and wraps around, leaving it where it was
+        assert_eq!(cpu.bus.oam()[0x81], 0x00);
+        assert_eq!(cpu.bus.oam()[0xFF], 0x7E);
+        assert_eq!(cpu.bus.oam()[0x00], 0x7F);
+        assert_eq!(cpu.bus.oam()[0x80], 0xFF);
+        assert_eq!(cpu.bus.ppu().oam_address(), 0x81);
+
+        // The attribute bytes are the ones landing on them
+        assert_eq!(cpu.bus.oam()[0x82], 0x01);
+        assert_eq!(cpu.bus.oam()[0x02], 0x81 & 0xE3);
+    }
+
+    #[test]
+    fn test_oam_dma_step() {
+        let cartridge = FlatCartridge::new(vec![
+            // STA $4014
+            0x8D, 0x14, 0x40,
+
+            // NOP
+            0xEA,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.step().unwrap();
+
+        // The copy runs before the next instruction
+        let snapshot = cpu.step().unwrap();
+        assert_eq!(snapshot.program_counter, 0x8003);
+        assert_eq!(snapshot.cpu_cycles, 7 + 4 + 514);
+    }
+}
@@ -1,35 +1,11 @@
 //! Implements the instructions related to settings and clearing the flags of the CPU.
 
-use crate::bus::BusError;
 use crate::cpu::Cpu;
 use crate::cpu::CycleError;
-use crate::{build_address, cpu::impl_instruction_cycles};
-use crate::cpu::InstructionData;
+use crate::cpu::impl_instruction_cycles;
 
 use super::CpuStatusFlags;
 
-impl Cpu {
-    /// Implements the implied set carry flag instruction data.
-    pub(super) fn set_carry_flag_implied_instruction(&mut self) -> Result<InstructionData, BusError> {
-        Ok(InstructionData {
-            arg_1: None,
-            arg_2: None,
-            assembly: String::from("SEC"),
-            idle_cycles: 2,
-        })
-    }
-
-    /// Implements the implied clear carry flag instruction data.
-    pub(super) fn clear_carry_flag_implied_instruction(&mut self) -> Result<InstructionData, BusError> {
-        Ok(InstructionData {
-            arg_1: None,
-            arg_2: None,
-            assembly: String::from("CLC"),
-            idle_cycles: 2,
-        })
-    }
-}
-
 impl_instruction_cycles!(
     /// Implements the implied set carry flag instruction cycles.
     cpu, set_carry_flag_implied_cycles,
@@ -50,6 +26,49 @@ impl_instruction_cycles!(
     },
 );
 
+impl_instruction_cycles!(
+    /// Implements the implied set interrupt disable flag instruction cycles.
+    cpu, set_interrupt_disable_flag_implied_cycles,
+
+    2, true => {
+        let _ = cpu.read_program_counter();
+        cpu.status |= CpuStatusFlags::InterruptsDisabled;
+    },
+);
+
+impl_instruction_cycles!(
+    /// Implements the implied clear interrupt disable flag instruction cycles.
+    cpu, clear_interrupt_disable_flag_implied_cycles,
+
+    2, true => {
+        let _ = cpu.read_program_counter();
+        cpu.status -= CpuStatusFlags::InterruptsDisabled;
+    },
+);
+
+impl_instruction_cycles!(
+    /// Implements the implied set decimal flag instruction cycles.
+    ///
+    /// The flag is kept even though the 2A03 has no binary-coded decimal mode, so it can still be
+    /// pushed and pulled by the programs.
+    cpu, set_decimal_flag_implied_cycles,
+
+    2, true => {
+        let _ = cpu.read_program_counter();
+        cpu.status |= CpuStatusFlags::Decimal;
+    },
+);
+
+impl_instruction_cycles!(
+    /// Implements the implied clear decimal flag instruction cycles.
+    cpu, clear_decimal_flag_implied_cycles,
+
+    2, true => {
+        let _ = cpu.read_program_counter();
+        cpu.status -= CpuStatusFlags::Decimal;
+    },
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -57,16 +76,16 @@ mod tests {
 
     #[test]
     fn test_set_carry_flag_implied() {
-        let cartridge = MockCartridge::new(vec![
+        let cartridge = FlatCartridge::new(vec![
             // SEC
             0x38,
         ]);
 
-        let mut cpu = Cpu::new(Box::new(cartridge));
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
 
-        let instruction_data = cpu.cycle().unwrap().unwrap().instruction_data;
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
         assert_eq!(instruction_data.assembly, "SEC");
-        assert_eq!(instruction_data.idle_cycles, 2);
+        assert_eq!(instruction_data.idle_cycles, 1);
 
         assert_eq!(cpu.program_counter, 0x8001);
 
@@ -77,17 +96,17 @@ mod tests {
 
     #[test]
     fn test_clear_carry_flag_implied() {
-        let cartridge = MockCartridge::new(vec![
+        let cartridge = FlatCartridge::new(vec![
             // SEC
             0x18,
         ]);
 
-        let mut cpu = Cpu::new(Box::new(cartridge));
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
         cpu.status -= CpuStatusFlags::Carry;
 
-        let instruction_data = cpu.cycle().unwrap().unwrap().instruction_data;
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
         assert_eq!(instruction_data.assembly, "CLC");
-        assert_eq!(instruction_data.idle_cycles, 2);
+        assert_eq!(instruction_data.idle_cycles, 1);
 
         assert_eq!(cpu.program_counter, 0x8001);
 
@@ -95,4 +114,80 @@ mod tests {
         assert_eq!(cpu.program_counter, 0x8001);
         assert!(!cpu.status.contains(CpuStatusFlags::Carry));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_set_interrupt_disable_flag_implied() {
+        let cartridge = FlatCartridge::new(vec![
+            // SEI
+            0x78,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.status -= CpuStatusFlags::InterruptsDisabled;
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "SEI");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 2);
+
+        assert_eq!(cpu.program_counter, 0x8001);
+        assert!(cpu.status.contains(CpuStatusFlags::InterruptsDisabled));
+    }
+
+    #[test]
+    fn test_clear_interrupt_disable_flag_implied() {
+        let cartridge = FlatCartridge::new(vec![
+            // CLI
+            0x58,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.status |= CpuStatusFlags::InterruptsDisabled;
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "CLI");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 2);
+
+        assert_eq!(cpu.program_counter, 0x8001);
+        assert!(!cpu.status.contains(CpuStatusFlags::InterruptsDisabled));
+    }
+
+    #[test]
+    fn test_set_decimal_flag_implied() {
+        let cartridge = FlatCartridge::new(vec![
+            // SED
+            0xF8,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.status -= CpuStatusFlags::Decimal;
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "SED");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 2);
+
+        assert_eq!(cpu.program_counter, 0x8001);
+        assert!(cpu.status.contains(CpuStatusFlags::Decimal));
+    }
+
+    #[test]
+    fn test_clear_decimal_flag_implied() {
+        let cartridge = FlatCartridge::new(vec![
+            // CLD
+            0xD8,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.status |= CpuStatusFlags::Decimal;
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "CLD");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 2);
+
+        assert_eq!(cpu.program_counter, 0x8001);
+        assert!(!cpu.status.contains(CpuStatusFlags::Decimal));
+    }
+}
@@ -0,0 +1,159 @@
+//! Holds the pokes and the freezes of the memory, which patch the RAM behind the back of the
+//! program for the trainers and the cheat searches.
+
+use std::ops::RangeInclusive;
+
+use thiserror::Error;
+
+use crate::bus::BusError;
+use crate::cartridge::Cartridge;
+use crate::cpu::Cpu;
+
+/// The addresses of the RAM of the CPU, along with its mirrors.
+const CPU_RAM_ADDRESSES: RangeInclusive<u16> = 0x0000..=0x1FFF;
+
+/// The addresses of the PRG RAM of the cartridges having it.
+const PRG_RAM_ADDRESSES: RangeInclusive<u16> = 0x6000..=0x7FFF;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+/// Errors that may happen when freezing the memory.
+pub enum FreezeError {
+    #[error("Only the RAM can be frozen, ${0:04X} is not in the CPU RAM nor in the PRG RAM")]
+    /// The address is neither in the RAM of the CPU nor in the PRG RAM, like the registers and
+    /// the PRG ROM.
+    NotRam(u16),
+
+    #[error("The cartridge has no PRG RAM to freeze at ${0:04X}")]
+    /// The address is in the range of the PRG RAM, but the cartridge has none there, like NROM.
+    NoPrgRam(u16),
+}
+
+impl<C: Cartridge> Cpu<C> {
+    /// Write a value to the given address through the bus, like the CPU would.
+    pub fn poke(&mut self, address: u16, value: u8) -> Result<(), BusError> {
+        self.bus.write(address, value)
+    }
+
+    /// Rewrite the given value to the given address before every instruction, so the instructions
+    /// always see it no matter what the program writes there.
+    ///
+    /// Only the RAM of the CPU, `$0000` to `$1FFF`, and the PRG RAM, `$6000` to `$7FFF`, can be
+    /// frozen. The value is written right away, so an address of the PRG RAM is only frozen if the
+    /// cartridge takes the write. Freezing an address again replaces its value.
+    pub fn freeze(&mut self, address: u16, value: u8) -> Result<(), FreezeError> {
+        if !CPU_RAM_ADDRESSES.contains(&address) && !PRG_RAM_ADDRESSES.contains(&address) {
+            return Err(FreezeError::NotRam(address));
+        }
+
+        if self.bus.write_quietly(address, value).is_err() {
+            return Err(FreezeError::NoPrgRam(address));
+        }
+
+        self.frozen.insert(address, value);
+
+        Ok(())
+    }
+
+    /// Stop rewriting the value frozen at the given address, returning it if it was frozen.
+    pub fn unfreeze(&mut self, address: u16) -> Option<u8> {
+        self.frozen.remove(&address)
+    }
+
+    /// Get the frozen values, by address.
+    pub fn frozen(&self) -> impl Iterator<Item = (u16, u8)> + '_ {
+        self.frozen.iter().map(|(&address, &value)| (address, value))
+    }
+
+    /// Rewrite the frozen values, without the accesses being seen by the watchpoints.
+    ///
+    /// The addresses the cartridge stopped taking writes at, like after loading a state, are
+    /// unfrozen rather than failing every instruction.
+    pub(super) fn apply_frozen(&mut self) {
+        self.frozen.retain(|&address, &mut value| self.bus.write_quietly(address, value).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cartridge::nrom::Nrom;
+    use crate::cartridge::Mirroring;
+    use crate::cpu::tests::*;
+    use crate::rom::ines::InesFile;
+
+    /// The number of instructions of every iteration of the [counter_program].
+    const LOOP_INSTRUCTIONS: usize = 11;
+
+    /// Create a [Cpu] running the [counter_program], which increments the counter at `$10` and
+    /// loads it into the X register.
+    fn counter_loop() -> Cpu<FlatCartridge> {
+        Cpu::with_program(&counter_program())
+    }
+
+    #[test]
+    fn test_freeze_counter() {
+        let mut cpu = counter_loop();
+        cpu.freeze(0x0010, 0x05).unwrap();
+
+        for _ in 0..10 {
+            cpu.step_n(LOOP_INSTRUCTIONS).unwrap();
+
+            // The loaded counter is always the frozen value
+            assert_eq!(cpu.register_x, 0x05);
+        }
+
+        assert_eq!(cpu.unfreeze(0x0010), Some(0x05));
+
+        cpu.step_n(LOOP_INSTRUCTIONS).unwrap();
+        assert_eq!(cpu.register_x, 0x06);
+
+        cpu.step_n(LOOP_INSTRUCTIONS).unwrap();
+        assert_eq!(cpu.register_x, 0x07);
+    }
+
+    #[test]
+    fn test_freeze_only_ram() {
+        let mut cpu = counter_loop();
+
+        assert!(cpu.freeze(0x1FFF, 0x00).is_ok());
+        assert!(cpu.freeze(0x6000, 0x00).is_ok());
+        assert_eq!(cpu.freeze(0x2000, 0x00), Err(FreezeError::NotRam(0x2000)));
+        assert_eq!(cpu.freeze(0x8000, 0x00), Err(FreezeError::NotRam(0x8000)));
+
+        assert_eq!(cpu.frozen().collect::<Vec<_>>(), [(0x1FFF, 0x00), (0x6000, 0x00)]);
+    }
+
+    #[test]
+    fn test_freeze_without_prg_ram() {
+        let mut prg_rom = vec![0xEA; 16 * 1024];
+        // The reset vector points to $8000
+        prg_rom[0x3FFC..0x3FFE].copy_from_slice(&[0x00, 0x80]);
+
+        let rom = InesFile {
+            prg_rom,
+            prg_rom_size: 16 * 1024,
+            chr_rom: vec![],
+        };
+
+        let mut cpu = Cpu::from_cartridge(Nrom::new(false, Mirroring::Horizontal, rom)).unwrap();
+
+        assert_eq!(cpu.freeze(0x6000, 0x01), Err(FreezeError::NoPrgRam(0x6000)));
+        assert!(cpu.freeze(0x0010, 0x01).is_ok());
+        assert_eq!(cpu.frozen().collect::<Vec<_>>(), [(0x0010, 0x01)]);
+
+        // The CPU keeps running the NOPs
+        let cycles = cpu.cycles();
+        cpu.step_n(3).unwrap();
+        assert_eq!(cpu.cycles() - cycles, 6);
+    }
+
+    #[test]
+    fn test_poke() {
+        let mut cpu = counter_loop();
+        cpu.poke(0x0810, 0x42).unwrap();
+
+        // The poke lands on the mirrored counter
+        cpu.step_n(2).unwrap();
+        assert_eq!(cpu.register_x, 0x43);
+    }
+}
@@ -0,0 +1,283 @@
+//! Holds the history of the instructions recently run, dumped along the fatal errors to tell what
+//! the program was doing.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::cartridge::Cartridge;
+use crate::cpu::{Cpu, CpuStatusFlags};
+use crate::disasm::disassemble_slice;
+
+/// The number of newest entries of the history attached to the fatal errors of the CPU.
+pub(super) const ERROR_HISTORY_ENTRIES: usize = 16;
+
+/// An instruction fetched by the CPU, along with the registers before running it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistoryEntry {
+    /// The address of the opcode.
+    pub program_counter: u16,
+
+    /// The opcode followed by the two bytes after it, which hold the operand of the instructions
+    /// taking one.
+    pub bytes: [u8; 3],
+
+    /// The accumulator register.
+    pub accumulator: u8,
+
+    /// The X index register.
+    pub register_x: u8,
+
+    /// The Y index register.
+    pub register_y: u8,
+
+    /// The status register.
+    pub status: CpuStatusFlags,
+
+    /// The stack pointer register.
+    pub stack_pointer: u8,
+
+    /// The number of cycles the CPU had run before fetching the opcode.
+    pub cycles: u64,
+}
+
+impl HistoryEntry {
+    /// Get the opcode of the instruction.
+    pub fn opcode(&self) -> u8 {
+        self.bytes[0]
+    }
+}
+
+impl fmt::Display for HistoryEntry {
+    /// Format the entry as the address and the disassembly of the instruction followed by the
+    /// registers, like the nestest logs do.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let instruction = &disassemble_slice(&self.bytes, self.program_counter, 1)[0];
+        let bytes = instruction.bytes.iter().map(|byte| format!("{byte:02X}")).collect::<Vec<_>>().join(" ");
+
+        write!(
+            f,
+            "{:04X}  {bytes:<8} {:<14}A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X} CYC:{}",
+            self.program_counter,
+            instruction.to_string(),
+            self.accumulator,
+            self.register_x,
+            self.register_y,
+            self.status.bits(),
+            self.stack_pointer,
+            self.cycles,
+        )
+    }
+}
+
+/// The last instructions fetched by the CPU, kept in a bounded buffer allocated up front.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InstructionHistory {
+    /// The entries, from the oldest to the newest one.
+    entries: VecDeque<HistoryEntry>,
+
+    /// The maximum number of entries kept.
+    capacity: usize,
+}
+
+impl InstructionHistory {
+    /// Create a new empty [InstructionHistory] keeping up to the given number of entries.
+    ///
+    /// # Panics
+    /// If the capacity is zero.
+    fn new(capacity: usize) -> InstructionHistory {
+        assert!(capacity > 0, "The history needs a non-zero capacity");
+
+        InstructionHistory {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Get the maximum number of entries kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the number of entries kept.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if there is no entry kept.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over the entries, from the oldest to the newest one.
+    pub fn iter(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.entries.iter()
+    }
+
+    /// Add an entry, dropping the oldest one once the buffer is full.
+    fn push(&mut self, entry: HistoryEntry) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(entry);
+    }
+}
+
+/// Formats the entries of the history attached to an error, one per line after a blank one, or
+/// nothing if there are none.
+pub(super) struct HistoryDump<'a>(pub(super) &'a [HistoryEntry]);
+
+impl fmt::Display for HistoryDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            return Ok(());
+        }
+
+        write!(f, "\n\nLast instructions run:")?;
+
+        for entry in self.0 {
+            write!(f, "\n{entry}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<C: Cartridge> Cpu<C> {
+    /// Start keeping the history of the last instructions fetched, up to the given number of them,
+    /// dropping the one kept before if any.
+    ///
+    /// The history is attached to the fatal errors, and the buffer is allocated once here so
+    /// keeping it allocates nothing per instruction. The interrupt sequences are not kept.
+    ///
+    /// # Panics
+    /// If the capacity is zero.
+    pub fn enable_history(&mut self, capacity: usize) {
+        self.history = Some(InstructionHistory::new(capacity));
+    }
+
+    /// Stop keeping the history of the instructions, dropping it.
+    pub fn disable_history(&mut self) {
+        self.history = None;
+    }
+
+    /// Get the history of the last instructions fetched, if it's enabled.
+    pub fn history(&self) -> Option<&InstructionHistory> {
+        self.history.as_ref()
+    }
+
+    /// Add the instruction whose opcode has just been fetched to the history, if it's enabled,
+    /// given the number of cycles run before the fetch.
+    pub(super) fn record_history(&mut self, cycles: u64) {
+        let Some(history) = &mut self.history else {
+            return;
+        };
+
+        // The peeks answer zero wherever they can't read
+        let mut bytes = [0x00; 3];
+        for (offset, byte) in bytes.iter_mut().enumerate() {
+            *byte = self.bus.peek(self.program_counter.wrapping_add(offset as u16));
+        }
+
+        history.push(HistoryEntry {
+            program_counter: self.program_counter,
+            bytes,
+            accumulator: self.accumulator,
+            register_x: self.register_x,
+            register_y: self.register_y,
+            status: self.status,
+            stack_pointer: self.stack_pointer,
+            cycles,
+        });
+    }
+
+    /// Get the newest entries of the history attached to the errors, from the oldest to the newest
+    /// one.
+    pub(super) fn error_history(&self) -> Vec<HistoryEntry> {
+        let Some(history) = &self.history else {
+            return vec![];
+        };
+
+        history.iter().skip(history.len().saturating_sub(ERROR_HISTORY_ENTRIES)).copied().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+    use crate::cpu::CpuError;
+
+    /// Build a program adding one to the accumulator twenty times, followed by the given bytes.
+    fn counting_program(end: &[u8]) -> FlatCartridge {
+        let mut prg_data = vec![];
+
+        for _ in 0..20 {
+            // ADC #$01
+            prg_data.extend_from_slice(&[0x69, 0x01]);
+        }
+
+        prg_data.extend_from_slice(end);
+
+        FlatCartridge::new(prg_data)
+    }
+
+    #[test]
+    fn test_history() {
+        let mut cpu = Cpu::new(Box::new(counting_program(&[]))).unwrap();
+        cpu.enable_history(8);
+        cpu.batch_run_full_instruction(20);
+
+        let history = cpu.history().unwrap();
+        assert_eq!(history.len(), 8);
+        assert_eq!(history.capacity(), 8);
+
+        // Only the last eight instructions are kept, in order
+        let entries: Vec<(u16, &[u8], u8, u64)> = history
+            .iter()
+            .map(|entry| (entry.program_counter, &entry.bytes[..2], entry.accumulator, entry.cycles))
+            .collect();
+
+        let expected: Vec<(u16, &[u8], u8, u64)> = (12..20)
+            .map(|index| (0x8000 + 2 * index as u16, &[0x69, 0x01][..], index, 7 + 2 * index as u64))
+            .collect();
+
+        assert_eq!(entries, expected);
+    }
+
+    #[test]
+    fn test_history_disabled() {
+        let mut cpu = Cpu::new(Box::new(counting_program(&[]))).unwrap();
+        cpu.run_full_instruction();
+
+        assert!(cpu.history().is_none());
+    }
+
+    #[test]
+    fn test_history_in_errors() {
+        // An opcode that jams the CPU
+        let mut cpu = Cpu::new(Box::new(counting_program(&[0x02]))).unwrap();
+        cpu.enable_history(32);
+        cpu.batch_run_full_instruction(20);
+
+        let error = cpu.cycle().unwrap_err();
+        let CpuError::Jammed { history, .. } = &error else {
+            panic!("The CPU didn't jam");
+        };
+
+        // The jamming opcode is the newest entry
+        assert_eq!(history.len(), ERROR_HISTORY_ENTRIES);
+        assert_eq!(history.last().unwrap().program_counter, 0x8028);
+        assert_eq!(history.last().unwrap().opcode(), 0x02);
+
+        let message = error.to_string();
+        let mut lines = message.lines();
+
+        assert_eq!(lines.next(), Some("The CPU jammed running the opcode 02 at $8028"));
+        assert_eq!(lines.next(), Some(""));
+        assert_eq!(lines.next(), Some("Last instructions run:"));
+        assert_eq!(lines.clone().count(), ERROR_HISTORY_ENTRIES);
+        assert_eq!(lines.next(), Some("800A  69 01    ADC #$01      A:05 X:00 Y:00 P:24 SP:FD CYC:17"));
+        assert_eq!(lines.last(), Some("8028  02       *JAM          A:14 X:00 Y:00 P:24 SP:FD CYC:47"));
+    }
+}
@@ -0,0 +1,137 @@
+//! Holds the implementation of the `INC` and `DEC` instructions.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::addressing::AddressingMode;
+use crate::cpu::Cpu;
+use crate::cpu::CycleError;
+
+impl<C: Cartridge> Cpu<C> {
+    /// Increment the given value by one, wrapping around and setting the zero and negative flags from the result.
+    fn increment(&mut self, value: u8) -> u8 {
+        let result = value.wrapping_add(1);
+        self.set_signedness(result);
+
+        result
+    }
+
+    /// Decrement the given value by one, wrapping around and setting the zero and negative flags from the result.
+    fn decrement(&mut self, value: u8) -> u8 {
+        let result = value.wrapping_sub(1);
+        self.set_signedness(result);
+
+        result
+    }
+
+    /// Implements the increment memory instruction cycles for the given addressing mode.
+    pub(super) fn increment_memory_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        self.read_modify_write_cycles(mode, Cpu::increment)
+    }
+
+    /// Implements the decrement memory instruction cycles for the given addressing mode.
+    pub(super) fn decrement_memory_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        self.read_modify_write_cycles(mode, Cpu::decrement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+    use crate::cpu::CpuStatusFlags;
+
+    #[test]
+    fn test_inc_zero_page_wraparound() {
+        let cartridge = FlatCartridge::new(vec![
+            // INC $10
+            0xE6, 0x10,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.status = CpuStatusFlags::Carry;
+        cpu.bus.write(0x0010, 0xFF).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "INC $10 = FF");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 5);
+        assert_eq!(cpu.program_counter, 0x8002);
+
+        assert_eq!(cpu.bus.read(0x0010).unwrap(), 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+
+        // The carry flag is never touched
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+    }
+
+    #[test]
+    fn test_inc_absolute_x_negative() {
+        let cartridge = FlatCartridge::new(vec![
+            // INC $01FF,X
+            0xFE, 0xFF, 0x01,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_x = 0x01;
+        cpu.bus.write(0x0200, 0x7F).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "INC $01FF,X @ 0200 = 7F");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 7);
+        assert_eq!(cpu.program_counter, 0x8003);
+
+        assert_eq!(cpu.bus.read(0x0200).unwrap(), 0x80);
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_dec_zero_page_x_wraparound() {
+        let cartridge = FlatCartridge::new(vec![
+            // DEC $80,X
+            0xD6, 0x80,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_x = 0x90;
+        cpu.bus.write(0x0010, 0x00).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "DEC $80,X @ 10 = 00");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 6);
+
+        assert_eq!(cpu.bus.read(0x0010).unwrap(), 0xFF);
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+    }
+
+    #[test]
+    fn test_dec_absolute_zero() {
+        let cartridge = FlatCartridge::new(vec![
+            // DEC $0180
+            0xCE, 0x80, 0x01,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x0180, 0x01).unwrap();
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "DEC $0180 = 01");
+        assert_eq!(instruction_data.idle_cycles, 5);
+
+        for _ in 0..4 {
+            cpu.cycle().unwrap();
+        }
+
+        // Dummy write of the unmodified value
+        assert_eq!(cpu.bus.read(0x0180).unwrap(), 0x01);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.bus.read(0x0180).unwrap(), 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+}
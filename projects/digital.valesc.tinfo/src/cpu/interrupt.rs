@@ -0,0 +1,533 @@
+//! Holds the implementation of the `BRK` and `RTI` instructions, along with the interrupt sequence
+//! of the hardware interrupts.
+
+use crate::build_address;
+use crate::cartridge::Cartridge;
+use crate::cpu::CallKind;
+use crate::cpu::Cpu;
+use crate::cpu::CpuStatusFlags;
+use crate::cpu::CycleError;
+use crate::cpu::impl_instruction_cycles;
+use crate::cpu::{IRQ_VECTOR_ADDRESS, NMI_VECTOR_ADDRESS, STACK_ADDRESS};
+use crate::U16Ex;
+
+/// The sources that can make the CPU run the interrupt sequence.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(super) enum Interrupt {
+    /// The `BRK` instruction.
+    Break,
+
+    /// The Non Maskable Interrupt, raised by the PPU when the vertical blank starts.
+    Nmi,
+
+    /// The Interrupt Request, raised by the APU and some mappers while they hold the IRQ line low.
+    Irq,
+}
+
+impl Interrupt {
+    /// Get the address of the vector holding the address of the handler of the interrupt.
+    fn vector_address(self) -> u16 {
+        match self {
+            Interrupt::Break | Interrupt::Irq => IRQ_VECTOR_ADDRESS,
+            Interrupt::Nmi => NMI_VECTOR_ADDRESS,
+        }
+    }
+}
+
+impl<C: Cartridge> Cpu<C> {
+    /// Implements the cycles of the interrupt sequence, shared by `BRK` and the hardware interrupts.
+    ///
+    /// The hardware interrupts run the sequence in place of the instruction at the program counter,
+    /// so they don't skip the byte after it and push the status with the B flag clear.
+    pub(super) fn interrupt_cycles(&mut self, interrupt: Interrupt) -> Result<bool, CycleError> {
+        match self.current_instruction_cycle {
+            2 => {
+                // The byte after the opcode is fetched and discarded
+                let _ = self.read_program_counter()?;
+
+                if interrupt == Interrupt::Break {
+                    self.advance_program_counter(1);
+                }
+
+                Ok(false)
+            },
+
+            3 => {
+                self.stack_push(self.program_counter.upper_byte())?;
+
+                Ok(false)
+            },
+
+            4 => {
+                self.stack_push(self.program_counter.lower_byte())?;
+
+                Ok(false)
+            },
+
+            5 => {
+                self.push_status(interrupt == Interrupt::Break)?;
+                self.status |= CpuStatusFlags::InterruptsDisabled;
+
+                // The vector is selected now, so a NMI detected until here hijacks the sequence
+                // while the pushed status keeps the B flag of the original cause
+                let vector_address = match self.nmi_pending {
+                    true if interrupt != Interrupt::Nmi => {
+                        self.nmi_pending = false;
+                        NMI_VECTOR_ADDRESS
+                    },
+
+                    _ => interrupt.vector_address(),
+                };
+
+                self.cache.push(vector_address.lower_byte());
+                self.cache.push(vector_address.upper_byte());
+
+                Ok(false)
+            },
+
+            6 => {
+                let vector_address = build_address(self.cache[0], self.cache[1]);
+
+                let program_counter_low = self.bus.read(vector_address)?;
+                self.cache.push(program_counter_low);
+
+                Ok(false)
+            },
+
+            7 => {
+                let vector_address = build_address(self.cache[0], self.cache[1]);
+
+                let program_counter_high = self.bus.read(vector_address + 1)?;
+                let return_address = self.program_counter;
+
+                self.program_counter = build_address(self.cache[2], program_counter_high);
+                self.push_call_frame(CallKind::Interrupt, return_address, self.program_counter);
+
+                Ok(true)
+            },
+
+            _ => Err(CycleError::InstructionCycleOutOfBounds),
+        }
+    }
+}
+
+impl_instruction_cycles!(
+    /// Implements the implied return from interrupt instruction cycles.
+    cpu, return_from_interrupt_cycles,
+
+    2, false => {
+        // Dummy read
+        let _ = cpu.read_program_counter()?;
+    },
+
+    3, false => {
+        // Dummy read of the stack while the stack pointer is incremented
+        let _ = cpu.bus.read(STACK_ADDRESS + cpu.stack_pointer as u16)?;
+    },
+
+    4, false => {
+        cpu.pull_status()?;
+    },
+
+    5, false => {
+        let program_counter_low = cpu.stack_pull()?;
+        cpu.cache.push(program_counter_low);
+    },
+
+    6, true => {
+        // Unlike RTS, interrupts push the address of the next instruction to run
+        let program_counter_high = cpu.stack_pull()?;
+
+        cpu.program_counter = build_address(cpu.cache[0], program_counter_high);
+        cpu.pop_call_frame(cpu.program_counter);
+    },
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+
+    #[test]
+    fn test_brk() {
+        // BRK
+        let cartridge = FlatCartridge::new(vec![0x00]).with_irq_vector(0x9234);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.status = CpuStatusFlags::Carry;
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "BRK");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 7);
+
+        assert_eq!(cpu.program_counter, 0x9234);
+        assert_eq!(cpu.stack_pointer, 0xFA);
+
+        // The return address skips the byte after the opcode
+        assert_eq!(cpu.bus.read(0x01FD).unwrap(), 0x80);
+        assert_eq!(cpu.bus.read(0x01FC).unwrap(), 0x02);
+        assert_eq!(
+            cpu.bus.read(0x01FB).unwrap(),
+            (CpuStatusFlags::Carry | CpuStatusFlags::B | CpuStatusFlags::Stub).bits()
+        );
+
+        assert!(cpu.status.contains(CpuStatusFlags::InterruptsDisabled));
+    }
+
+    #[test]
+    fn test_rti() {
+        let cartridge = FlatCartridge::new(vec![
+            // RTI
+            0x40,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.status = CpuStatusFlags::empty();
+        cpu.stack_pointer = 0xFA;
+
+        cpu.bus.write(0x01FB, (CpuStatusFlags::Carry | CpuStatusFlags::Negative | CpuStatusFlags::B).bits()).unwrap();
+        cpu.bus.write(0x01FC, 0x34).unwrap();
+        cpu.bus.write(0x01FD, 0x12).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "RTI");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 6);
+
+        assert_eq!(cpu.program_counter, 0x1234);
+        assert_eq!(cpu.stack_pointer, 0xFD);
+
+        // The pulled B flag is ignored and the stub flag is set
+        assert_eq!(cpu.status, CpuStatusFlags::Carry | CpuStatusFlags::Negative | CpuStatusFlags::Stub);
+    }
+
+    #[test]
+    fn test_pushed_status_bytes() {
+        // The B flag is only set on the copies pushed by the instructions
+        for (program, interrupt, status_address, pushed_status) in [
+            // PHP
+            (vec![0x08], None, 0x01FD, 0x30),
+
+            // BRK
+            (vec![0x00], None, 0x01FB, 0x30),
+
+            (vec![], Some(Interrupt::Nmi), 0x01FB, 0x20),
+            (vec![], Some(Interrupt::Irq), 0x01FB, 0x20),
+        ] {
+            let mut cpu = Cpu::new(Box::new(FlatCartridge::new(program))).unwrap();
+            cpu.status = CpuStatusFlags::Stub;
+
+            match interrupt {
+                Some(Interrupt::Nmi) => cpu.raise_nmi(),
+                Some(Interrupt::Irq) => cpu.set_irq_line(true),
+                _ => {},
+            }
+
+            cpu.run_full_instruction();
+
+            assert_eq!(cpu.bus.read(status_address).unwrap(), pushed_status);
+        }
+    }
+
+    /// Build a program of `LDA`s whose NMI handler is at $9000.
+    fn nmi_cartridge() -> FlatCartridge {
+        let mut prg_data = vec![0xEA; 0x4000];
+
+        // LDA #$01
+        prg_data[0x0000..0x0002].copy_from_slice(&[0xA9, 0x01]);
+
+        // LDA #$02
+        prg_data[0x0002..0x0004].copy_from_slice(&[0xA9, 0x02]);
+
+        // LDA #$03
+        prg_data[0x1000..0x1002].copy_from_slice(&[0xA9, 0x03]);
+
+        FlatCartridge::new(prg_data).with_nmi_vector(0x9000)
+    }
+
+    #[test]
+    fn test_nmi() {
+        let mut cpu = Cpu::new(Box::new(nmi_cartridge())).unwrap();
+        cpu.status = CpuStatusFlags::Carry | CpuStatusFlags::InterruptsDisabled;
+
+        // The NMI raised in the middle of an instruction waits for it to end
+        cpu.cycle().unwrap();
+        cpu.raise_nmi();
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.accumulator, 0x01);
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "NMI");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 7);
+
+        // The NMI is serviced even with the interrupts disabled
+        assert_eq!(cpu.program_counter, 0x9000);
+        assert_eq!(cpu.stack_pointer, 0xFA);
+
+        // The return address is the one of the instruction that didn't run
+        assert_eq!(cpu.bus.read(0x01FD).unwrap(), 0x80);
+        assert_eq!(cpu.bus.read(0x01FC).unwrap(), 0x02);
+        assert_eq!(
+            cpu.bus.read(0x01FB).unwrap(),
+            (CpuStatusFlags::Carry | CpuStatusFlags::InterruptsDisabled | CpuStatusFlags::Stub).bits()
+        );
+
+        assert!(cpu.status.contains(CpuStatusFlags::InterruptsDisabled));
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDA #$03");
+        assert_eq!(cpu.accumulator, 0x03);
+    }
+
+    #[test]
+    fn test_nmi_is_latched_once() {
+        let mut cpu = Cpu::new(Box::new(nmi_cartridge())).unwrap();
+
+        cpu.raise_nmi();
+        cpu.raise_nmi();
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "NMI");
+        assert_eq!(cpu.program_counter, 0x9000);
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDA #$03");
+        assert_eq!(cpu.stack_pointer, 0xFA);
+    }
+
+    /// Build a program toggling the interrupt disable flag whose IRQ handler at $9000 just returns,
+    /// while the NMI handler is at $A000, to be served by [irq_cartridge].
+    fn irq_prg_data() -> Vec<u8> {
+        let mut prg_data = vec![0xEA; 0x4000];
+
+        // SEI
+        prg_data[0x0000] = 0x78;
+
+        // LDA #$01
+        prg_data[0x0001..0x0003].copy_from_slice(&[0xA9, 0x01]);
+
+        // CLI
+        prg_data[0x0003] = 0x58;
+
+        // LDA #$02
+        prg_data[0x0004..0x0006].copy_from_slice(&[0xA9, 0x02]);
+
+        // RTI
+        prg_data[0x1000] = 0x40;
+
+        // LDA #$03
+        prg_data[0x2000..0x2002].copy_from_slice(&[0xA9, 0x03]);
+
+        prg_data
+    }
+
+    /// Build a cartridge serving a program of [irq_prg_data], pointing the IRQ vector to $9000 and
+    /// the NMI one to $A000.
+    fn irq_cartridge(prg_data: Vec<u8>) -> FlatCartridge {
+        FlatCartridge::new(prg_data).with_irq_vector(0x9000).with_nmi_vector(0xA000)
+    }
+
+    #[test]
+    fn test_irq_masking() {
+        let mut cpu = Cpu::new(Box::new(irq_cartridge(irq_prg_data()))).unwrap();
+        cpu.status = CpuStatusFlags::Carry;
+
+        cpu.run_full_instruction();
+        cpu.set_irq_line(true);
+
+        // The IRQ is masked after SEI
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDA #$01");
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "CLI");
+
+        // The IRQ is serviced once CLI unmasks it
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "IRQ");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 7);
+
+        assert_eq!(cpu.program_counter, 0x9000);
+        assert_eq!(cpu.bus.read(0x01FD).unwrap(), 0x80);
+        assert_eq!(cpu.bus.read(0x01FC).unwrap(), 0x04);
+        assert_eq!(cpu.bus.read(0x01FB).unwrap(), (CpuStatusFlags::Carry | CpuStatusFlags::Stub).bits());
+        assert!(cpu.status.contains(CpuStatusFlags::InterruptsDisabled));
+
+        // The handler is entered again while the line stays asserted
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "RTI");
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "IRQ");
+
+        cpu.set_irq_line(false);
+
+        cpu.run_full_instruction();
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDA #$02");
+        assert_eq!(cpu.stack_pointer, 0xFD);
+    }
+
+    /// Build a program starting with a `LDX $10`, whose IRQ handler is at $9000.
+    fn irq_after_load_cartridge() -> FlatCartridge {
+        // LDX $10
+        FlatCartridge::new(vec![0xA6, 0x10]).with_irq_vector(0x9000)
+    }
+
+    #[test]
+    fn test_irq_raised_mid_instruction() {
+        let mut cpu = Cpu::new(Box::new(irq_after_load_cartridge())).unwrap();
+        cpu.status = CpuStatusFlags::Stub;
+        cpu.bus.write(0x0010, 0x42).unwrap();
+
+        // The opcode fetch of the LDX
+        cpu.cycle().unwrap();
+        cpu.set_irq_line(true);
+
+        let pending_interrupts = cpu.pending_interrupts();
+        assert!(pending_interrupts.irq_line);
+        assert!(!pending_interrupts.nmi_pending);
+        assert!(pending_interrupts.is_pending());
+
+        // The LDX retires before the IRQ is serviced
+        cpu.cycle().unwrap();
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.register_x, 0x42);
+        assert_eq!(cpu.program_counter, 0x8002);
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "IRQ");
+        assert_eq!(cpu.program_counter, 0x9000);
+        assert_eq!(cpu.bus.read(0x01FC).unwrap(), 0x02);
+
+        // The line stays asserted but the handler runs with the IRQ masked
+        assert!(!cpu.pending_interrupts().is_pending());
+    }
+
+    #[test]
+    fn test_irq_deasserted_before_boundary() {
+        let mut cpu = Cpu::new(Box::new(irq_after_load_cartridge())).unwrap();
+        cpu.status = CpuStatusFlags::Stub;
+
+        cpu.cycle().unwrap();
+        cpu.set_irq_line(true);
+        cpu.cycle().unwrap();
+        cpu.set_irq_line(false);
+        cpu.cycle().unwrap();
+
+        assert!(!cpu.pending_interrupts().is_pending());
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "NOP");
+        assert_eq!(cpu.program_counter, 0x8003);
+    }
+
+    #[test]
+    fn test_pending_nmi() {
+        let mut cpu = Cpu::new(Box::new(nmi_cartridge())).unwrap();
+        assert!(!cpu.pending_interrupts().is_pending());
+
+        cpu.raise_nmi();
+
+        // The NMI ignores the mask of the IRQ
+        let pending_interrupts = cpu.pending_interrupts();
+        assert!(pending_interrupts.nmi_pending);
+        assert!(pending_interrupts.irq_masked);
+        assert!(pending_interrupts.is_pending());
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "NMI");
+        assert!(!cpu.pending_interrupts().nmi_pending);
+    }
+
+    #[test]
+    fn test_nmi_priority_over_irq() {
+        let mut cpu = Cpu::new(Box::new(irq_cartridge(irq_prg_data()))).unwrap();
+        cpu.status = CpuStatusFlags::empty();
+
+        cpu.set_irq_line(true);
+        cpu.raise_nmi();
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "NMI");
+        assert_eq!(cpu.program_counter, 0xA000);
+
+        // The NMI handler runs with the IRQ masked
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDA #$03");
+    }
+
+    /// Run a `BRK` at the start of the program of [irq_prg_data], raising a NMI after the given
+    /// number of its cycles.
+    fn break_with_nmi_after_cycles(cycles: u8) -> Cpu {
+        let mut prg_data = irq_prg_data();
+        prg_data[0x0000] = 0x00;
+
+        let mut cpu = Cpu::new(Box::new(irq_cartridge(prg_data))).unwrap();
+
+        for _ in 0..cycles {
+            cpu.cycle().unwrap();
+        }
+
+        cpu.raise_nmi();
+
+        for _ in cycles..7 {
+            cpu.cycle().unwrap();
+        }
+
+        cpu
+    }
+
+    #[test]
+    fn test_nmi_hijacks_break() {
+        // The NMI detected before the vector is selected on the fifth cycle hijacks it
+        for cycles in 1..=4 {
+            let mut cpu = break_with_nmi_after_cycles(cycles);
+
+            assert_eq!(cpu.program_counter, 0xA000, "NMI raised after {cycles} cycles");
+            assert_eq!(cpu.bus.read(0x01FB).unwrap() & CpuStatusFlags::B.bits(), CpuStatusFlags::B.bits());
+
+            // The hijacked NMI is not serviced again
+            let instruction_data = cpu.run_full_instruction();
+            assert_eq!(instruction_data.assembly, "LDA #$03");
+        }
+    }
+
+    #[test]
+    fn test_nmi_after_break_vector_selection() {
+        for cycles in 5..=7 {
+            let mut cpu = break_with_nmi_after_cycles(cycles);
+
+            assert_eq!(cpu.program_counter, 0x9000, "NMI raised after {cycles} cycles");
+
+            // The NMI is serviced right after the BRK
+            let instruction_data = cpu.run_full_instruction();
+            assert_eq!(instruction_data.assembly, "NMI");
+            assert_eq!(cpu.program_counter, 0xA000);
+        }
+    }
+
+    #[test]
+    fn test_nmi_hijacks_irq() {
+        let mut cpu = Cpu::new(Box::new(irq_cartridge(irq_prg_data()))).unwrap();
+        cpu.status = CpuStatusFlags::empty();
+        cpu.set_irq_line(true);
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "IRQ");
+
+        cpu.raise_nmi();
+
+        for _ in 0..6 {
+            cpu.cycle().unwrap();
+        }
+
+        assert_eq!(cpu.program_counter, 0xA000);
+
+        // The pushed status keeps the B flag clear of the IRQ
+        assert_eq!(cpu.bus.read(0x01FB).unwrap(), CpuStatusFlags::Stub.bits());
+    }
+}
@@ -1,49 +1,54 @@
 //! Holds the implementation of the `JMP` instruction.
 
-use crate::bus::BusError;
+use crate::{build_address, next_address_in_page};
+use crate::cartridge::Cartridge;
+use crate::cpu::AddressingMode;
 use crate::cpu::Cpu;
 use crate::cpu::CycleError;
-use crate::{build_address, cpu::impl_instruction_cycles};
-use crate::cpu::InstructionData;
-
-impl Cpu {
-    /// Implements the absolute jump instruction data.
-    pub(super) fn jump_absolute_instruction(&mut self) -> Result<InstructionData, BusError> {
-        let arg_1 = self.bus.read(self.program_counter + 1)?;
-        let arg_2 = self.bus.read(self.program_counter + 2)?;
-        
-        let address = build_address(
-            arg_1,
-            arg_2
-        );
-
-        Ok(InstructionData {
-            arg_1: Some(arg_1),
-            arg_2: Some(arg_2),
-            assembly: format!("JMP ${address:02X}"),
-            idle_cycles: 2,
-        })
-    }
-}
 
-impl_instruction_cycles!(
+impl<C: Cartridge> Cpu<C> {
     /// Implements the absolute jump instruction cycles.
-    cpu, jump_absolute_cycles,
-
-    2, false => {
-        cpu.cache.push(cpu.read_program_counter()?);
-        cpu.program_counter += 1;
-    },
-
-    3, true => {
-        let program_counter_address_upper_byte = cpu.read_program_counter()?;
-        cpu.program_counter =
-            build_address(
-                cpu.cache[0],
-                program_counter_address_upper_byte
-            );
-    },
-);
+    ///
+    /// The resolved address is the jump target itself, so the jump happens as soon as its upper byte is fetched.
+    pub(super) fn jump_absolute_cycles(&mut self) -> Result<bool, CycleError> {
+        self.operand_address_cycles(AddressingMode::Absolute, false)?;
+
+        if self.current_instruction_cycle < 3 {
+            return Ok(false);
+        }
+
+        self.program_counter = build_address(self.cache[0], self.cache[1]);
+
+        Ok(true)
+    }
+
+    /// Implements the indirect jump instruction cycles.
+    ///
+    /// The pointer is resolved like an absolute address, but a pointer at the end of a page reads
+    /// its upper byte from the start of the same page.
+    pub(super) fn jump_indirect_cycles(&mut self) -> Result<bool, CycleError> {
+        let Some(pointer) = self.operand_address_cycles(AddressingMode::Absolute, false)? else {
+            return Ok(false);
+        };
+
+        match self.current_instruction_cycle {
+            4 => {
+                self.cache.push(self.bus.read(pointer)?);
+
+                Ok(false)
+            }
+
+            5 => {
+                let program_counter_high = self.bus.read(next_address_in_page(pointer))?;
+                self.program_counter = build_address(self.cache[2], program_counter_high);
+
+                Ok(true)
+            }
+
+            _ => Err(CycleError::InstructionCycleOutOfBounds),
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -52,14 +57,14 @@ mod tests {
 
     #[test]
     fn test_jmp_absolute() {
-        let cartridge = MockCartridge::new(vec![
+        let cartridge = FlatCartridge::new(vec![
             // JMP $5533
             0x4C, 0x33, 0x55
         ]);
 
-        let mut cpu = Cpu::new(Box::new(cartridge));
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
 
-        let instruction_data = cpu.cycle().unwrap().unwrap().instruction_data;
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
         assert_eq!(instruction_data.assembly, "JMP $5533");
         assert_eq!(instruction_data.idle_cycles, 2);
 
@@ -71,4 +76,56 @@ mod tests {
         cpu.cycle().unwrap();
         assert_eq!(cpu.program_counter, 0x5533);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_jmp_absolute_low_address() {
+        let cartridge = FlatCartridge::new(vec![
+            // JMP $0033
+            0x4C, 0x33, 0x00
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "JMP $0033");
+        assert_eq!(cpu.program_counter, 0x0033);
+    }
+
+    #[test]
+    fn test_jmp_indirect() {
+        let cartridge = FlatCartridge::new(vec![
+            // JMP ($0200)
+            0x6C, 0x00, 0x02
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x0200, 0x7E).unwrap();
+        cpu.bus.write(0x0201, 0xDB).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "JMP ($0200) = DB7E");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 5);
+
+        assert_eq!(cpu.program_counter, 0xDB7E);
+    }
+
+    #[test]
+    fn test_jmp_indirect_page_wrap() {
+        let cartridge = FlatCartridge::new(vec![
+            // JMP ($02FF)
+            0x6C, 0xFF, 0x02
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x02FF, 0x00).unwrap();
+        cpu.bus.write(0x0200, 0x03).unwrap();
+        cpu.bus.write(0x0300, 0x04).unwrap();
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "JMP ($02FF) = 0300");
+
+        // The upper byte is fetched from $0200 instead of $0300
+        assert_eq!(cpu.program_counter, 0x0300);
+    }
+}
@@ -0,0 +1,184 @@
+//! Holds the implementation of the `LDA` instruction.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::addressing::AddressingMode;
+use crate::cpu::Cpu;
+use crate::cpu::CycleError;
+
+impl<C: Cartridge> Cpu<C> {
+    /// Implements the load accumulator instruction cycles for the given addressing mode.
+    pub(super) fn load_accumulator_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        let Some(value) = self.read_operand_cycles(mode)? else {
+            return Ok(false);
+        };
+
+        self.accumulator = value;
+        self.set_signedness(value);
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::{tests::*, CpuStatusFlags};
+
+    #[test]
+    fn test_lda_immediate() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDA #$8C
+            0xA9, 0x8C
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "LDA #$8C");
+        assert_eq!(instruction_data.idle_cycles, 1);
+
+        assert_eq!(cpu.program_counter, 0x8001);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8002);
+        assert_eq!(cpu.accumulator, 0x8C);
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_lda_absolute() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDA $0180
+            0xAD, 0x80, 0x01
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x0180, 0x33).unwrap();
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "LDA $0180 = 33");
+        assert_eq!(instruction_data.idle_cycles, 3);
+
+        assert_eq!(cpu.program_counter, 0x8001);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8002);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8003);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.accumulator, 0x33);
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_lda_absolute_x_same_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDX #$10
+            0xA2, 0x10,
+
+            // LDA $0200,X
+            0xBD, 0x00, 0x02,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x0210, 0x00).unwrap();
+
+        cpu.run_full_instruction();
+        let cpu_cycles = cpu.cpu_cycles;
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDA $0200,X @ 0210 = 00");
+        assert_eq!(instruction_data.idle_cycles, 3);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.program_counter, 0x8005);
+        assert_eq!(cpu.accumulator, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_lda_absolute_x_page_cross() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDX #$10
+            0xA2, 0x10,
+
+            // LDA $02F8,X
+            0xBD, 0xF8, 0x02,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x0308, 0xBB).unwrap();
+
+        cpu.run_full_instruction();
+        let cpu_cycles = cpu.cpu_cycles;
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDA $02F8,X @ 0308 = BB");
+        assert_eq!(instruction_data.idle_cycles, 4);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 5);
+        assert_eq!(cpu.program_counter, 0x8005);
+        assert_eq!(cpu.accumulator, 0xBB);
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_lda_absolute_y_page_cross_penalty() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDA $0300,Y
+            0xB9, 0x00, 0x03,
+
+            // LDA $03FF,Y
+            0xB9, 0xFF, 0x03,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_y = 0x01;
+        cpu.bus.write(0x0301, 0x7F).unwrap();
+        cpu.bus.write(0x0400, 0x80).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        let same_page_cycles = cpu.cpu_cycles - cpu_cycles;
+
+        assert_eq!(instruction_data.assembly, "LDA $0300,Y @ 0301 = 7F");
+        assert_eq!(cpu.accumulator, 0x7F);
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        let page_cross_cycles = cpu.cpu_cycles - cpu_cycles;
+
+        assert_eq!(instruction_data.assembly, "LDA $03FF,Y @ 0400 = 80");
+        assert_eq!(cpu.accumulator, 0x80);
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+
+        assert_eq!(same_page_cycles, 4);
+        assert_eq!(page_cross_cycles, same_page_cycles + 1);
+        assert_eq!(cpu.program_counter, 0x8006);
+    }
+
+    #[test]
+    fn test_lda_batch() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDA #$01
+            0xA9, 0x01,
+
+            // LDA #$00
+            0xA9, 0x00,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+
+        cpu.batch_run_full_instruction(2);
+        assert_eq!(cpu.program_counter, 0x8004);
+        assert_eq!(cpu.accumulator, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+    }
+}
@@ -1,37 +1,24 @@
 //! Holds the implementation of the `LDX` instruction.
 
-use crate::bus::BusError;
+use crate::cartridge::Cartridge;
+use crate::cpu::addressing::AddressingMode;
 use crate::cpu::Cpu;
 use crate::cpu::CycleError;
-use crate::{build_address, cpu::impl_instruction_cycles};
-use crate::cpu::InstructionData;
 
+impl<C: Cartridge> Cpu<C> {
+    /// Implements the load X register instruction cycles for the given addressing mode.
+    pub(super) fn load_x_register_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        let Some(value) = self.read_operand_cycles(mode)? else {
+            return Ok(false);
+        };
 
-impl Cpu {
-    /// Implements the immediate load X register instruction data.
-    pub(super) fn load_x_register_immediate_instruction(&mut self) -> Result<InstructionData, BusError> {
-        let arg_1 = self.bus.read(self.program_counter + 1)?;
+        self.register_x = value;
+        self.set_signedness(value);
 
-        Ok(InstructionData {
-            arg_1: Some(arg_1),
-            arg_2: None,
-            assembly: format!("LDX #${arg_1:02X}"),
-            idle_cycles: 1,
-        })
+        Ok(true)
     }
 }
 
-impl_instruction_cycles!(
-    /// Implements the immediate load X register instruction cycles.
-    cpu, load_x_register_immediate_cycles,
-
-    2, true => {
-        cpu.register_x = cpu.read_program_counter()?;
-        cpu.program_counter += 1;
-        cpu.set_signedness(cpu.register_x);
-    },
-);
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,14 +26,14 @@ mod tests {
 
     #[test]
     fn test_ldx_immediate_positive() {
-        let cartridge = MockCartridge::new(vec![
+        let cartridge = FlatCartridge::new(vec![
             // LDX #$5C
             0xA2, 0x5C
         ]);
 
-        let mut cpu = Cpu::new(Box::new(cartridge));
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
 
-        let instruction_data = cpu.cycle().unwrap().unwrap().instruction_data;
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
         assert_eq!(instruction_data.assembly, "LDX #$5C");
         assert_eq!(instruction_data.idle_cycles, 1);
 
@@ -61,14 +48,14 @@ mod tests {
 
     #[test]
     fn test_ldx_immediate_negative() {
-        let cartridge = MockCartridge::new(vec![
+        let cartridge = FlatCartridge::new(vec![
             // LDX #$FC
             0xA2, 0xFC
         ]);
 
-        let mut cpu = Cpu::new(Box::new(cartridge));
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
 
-        let instruction_data = cpu.cycle().unwrap().unwrap().instruction_data;
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
         assert_eq!(instruction_data.assembly, "LDX #$FC");
         assert_eq!(instruction_data.idle_cycles, 1);
 
@@ -83,14 +70,14 @@ mod tests {
 
     #[test]
     fn test_ldx_immediate_zero() {
-        let cartridge = MockCartridge::new(vec![
+        let cartridge = FlatCartridge::new(vec![
             // LDX #$00
             0xA2, 0x00
         ]);
 
-        let mut cpu = Cpu::new(Box::new(cartridge));
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
 
-        let instruction_data = cpu.cycle().unwrap().unwrap().instruction_data;
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
         assert_eq!(instruction_data.assembly, "LDX #$00");
         assert_eq!(instruction_data.idle_cycles, 1);
 
@@ -102,4 +89,146 @@ mod tests {
         assert!(cpu.status.contains(CpuStatusFlags::Zero));
         assert!(!cpu.status.contains(CpuStatusFlags::Negative));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_ldx_zero_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDX $78
+            0xA6, 0x78
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x0078, 0x80).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDX $78 = 80");
+        assert_eq!(instruction_data.idle_cycles, 2);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 3);
+        assert_eq!(cpu.program_counter, 0x8002);
+        assert_eq!(cpu.register_x, 0x80);
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_ldx_zero_page_y() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDX $00,Y
+            0xB6, 0x00
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_y = 0x78;
+        cpu.bus.write(0x0078, 0x33).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDX $00,Y @ 78 = 33");
+        assert_eq!(instruction_data.idle_cycles, 3);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.program_counter, 0x8002);
+        assert_eq!(cpu.register_x, 0x33);
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_ldx_zero_page_y_wraps_inside_zero_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDX $FF,Y
+            0xB6, 0xFF
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_y = 0x01;
+        cpu.bus.write(0x0000, 0x00).unwrap();
+        cpu.bus.write(0x0100, 0x97).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDX $FF,Y @ 00 = 00");
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.register_x, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_ldx_absolute() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDX $0647
+            0xAE, 0x47, 0x06
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x0647, 0x55).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDX $0647 = 55");
+        assert_eq!(instruction_data.idle_cycles, 3);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.program_counter, 0x8003);
+        assert_eq!(cpu.register_x, 0x55);
+    }
+
+    #[test]
+    fn test_ldx_absolute_y_same_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDX $0600,Y
+            0xBE, 0x00, 0x06
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_y = 0x47;
+        cpu.bus.write(0x0647, 0xAA).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDX $0600,Y @ 0647 = AA");
+        assert_eq!(instruction_data.idle_cycles, 3);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.register_x, 0xAA);
+    }
+
+    #[test]
+    fn test_ldx_absolute_y_page_cross() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDX $80F8,Y
+            0xBE, 0xF8, 0x80
+        ]);
+
+        let accesses = cartridge.accesses();
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_y = 0x10;
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "LDX $80F8,Y @ 8108 = EA");
+        assert_eq!(instruction_data.idle_cycles, 4);
+
+        accesses.borrow_mut().clear();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        for _ in 0..instruction_data.idle_cycles {
+            cpu.cycle().unwrap();
+        }
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.register_x, 0xEA);
+        assert_eq!(
+            *accesses.borrow(),
+            vec![
+                FlatAccess::Read(0x8001),
+                FlatAccess::Read(0x8002),
+                // Dummy read with the upper byte of the address unfixed
+                FlatAccess::Read(0x8008),
+                FlatAccess::Read(0x8108),
+            ]
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Holds the implementation of the `LDY` instruction.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::addressing::AddressingMode;
+use crate::cpu::Cpu;
+use crate::cpu::CycleError;
+
+impl<C: Cartridge> Cpu<C> {
+    /// Implements the load Y register instruction cycles for the given addressing mode.
+    pub(super) fn load_y_register_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        let Some(value) = self.read_operand_cycles(mode)? else {
+            return Ok(false);
+        };
+
+        self.register_y = value;
+        self.set_signedness(value);
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::{tests::*, CpuStatusFlags};
+
+    #[test]
+    fn test_ldy_immediate_positive() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDY #$5C
+            0xA0, 0x5C
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "LDY #$5C");
+        assert_eq!(instruction_data.idle_cycles, 1);
+
+        assert_eq!(cpu.program_counter, 0x8001);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8002);
+        assert_eq!(cpu.register_y, 0x5C);
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_ldy_immediate_negative() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDY #$FC
+            0xA0, 0xFC
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+
+        cpu.run_full_instruction();
+        assert_eq!(cpu.program_counter, 0x8002);
+        assert_eq!(cpu.register_y, 0xFC);
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_ldy_immediate_zero() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDY #$00
+            0xA0, 0x00
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+
+        cpu.run_full_instruction();
+        assert_eq!(cpu.program_counter, 0x8002);
+        assert_eq!(cpu.register_y, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_ldy_zero_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDY $78
+            0xA4, 0x78
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x0078, 0x55).unwrap();
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "LDY $78 = 55");
+        assert_eq!(instruction_data.idle_cycles, 2);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8002);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.register_y, 0x55);
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_ldy_zero_page_x() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDY $33,X
+            0xB4, 0x33
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_x = 0x02;
+        cpu.bus.write(0x0035, 0xAA).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDY $33,X @ 35 = AA");
+        assert_eq!(instruction_data.idle_cycles, 3);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.program_counter, 0x8002);
+        assert_eq!(cpu.register_y, 0xAA);
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_ldy_zero_page_x_wraps_inside_zero_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDY $FF,X
+            0xB4, 0xFF
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_x = 0x8A;
+        cpu.bus.write(0x0089, 0xBB).unwrap();
+        cpu.bus.write(0x0189, 0x00).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDY $FF,X @ 89 = BB");
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.register_y, 0xBB);
+    }
+
+    #[test]
+    fn test_ldy_absolute() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDY $0633
+            0xAC, 0x33, 0x06
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x0633, 0x00).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDY $0633 = 00");
+        assert_eq!(instruction_data.idle_cycles, 3);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.program_counter, 0x8003);
+        assert_eq!(cpu.register_y, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_ldy_absolute_x_same_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDY $0633,X
+            0xBC, 0x33, 0x06
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_x = 0x01;
+        cpu.bus.write(0x0634, 0x7A).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDY $0633,X @ 0634 = 7A");
+        assert_eq!(instruction_data.idle_cycles, 3);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.program_counter, 0x8003);
+        assert_eq!(cpu.register_y, 0x7A);
+    }
+
+    #[test]
+    fn test_ldy_absolute_x_page_cross() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDY $05FF,X
+            0xBC, 0xFF, 0x05
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_x = 0x8A;
+        cpu.bus.write(0x0689, 0xBB).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "LDY $05FF,X @ 0689 = BB");
+        assert_eq!(instruction_data.idle_cycles, 4);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 5);
+        assert_eq!(cpu.program_counter, 0x8003);
+        assert_eq!(cpu.register_y, 0xBB);
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+}
@@ -0,0 +1,154 @@
+//! Holds the implementation of the `ORA` instruction.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::addressing::AddressingMode;
+use crate::cpu::Cpu;
+use crate::cpu::CycleError;
+
+impl<C: Cartridge> Cpu<C> {
+    /// Implements the logical inclusive OR instruction cycles for the given addressing mode.
+    pub(super) fn logical_inclusive_or_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        let Some(value) = self.read_operand_cycles(mode)? else {
+            return Ok(false);
+        };
+
+        self.accumulator |= value;
+        self.set_signedness(self.accumulator);
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+    use crate::cpu::CpuStatusFlags;
+
+    #[test]
+    fn test_ora_program() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDA #$00
+            0xA9, 0x00,
+
+            // ORA #$00
+            0x09, 0x00,
+
+            // ORA $10
+            0x05, 0x10,
+
+            // ORA $0F,X
+            0x15, 0x0F,
+
+            // LDA #$01
+            0xA9, 0x01,
+
+            // ORA $0180
+            0x0D, 0x80, 0x01,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_x = 0x01;
+        cpu.bus.write(0x0010, 0x40).unwrap();
+        cpu.bus.write(0x0180, 0x82).unwrap();
+
+        let snapshot = cpu.run_until_next_snapshot();
+        assert_eq!(snapshot.instruction_data.assembly, "LDA #$00");
+
+        let snapshot = cpu.run_until_next_snapshot();
+        assert_eq!(snapshot.instruction_data.assembly, "ORA #$00");
+        assert_eq!(snapshot.accumulator, 0x00);
+        assert!(snapshot.status.contains(CpuStatusFlags::Zero));
+
+        let snapshot = cpu.run_until_next_snapshot();
+        assert_eq!(snapshot.instruction_data.assembly, "ORA $10 = 40");
+        assert_eq!(snapshot.accumulator, 0x00);
+        assert!(snapshot.status.contains(CpuStatusFlags::Zero));
+
+        let snapshot = cpu.run_until_next_snapshot();
+        assert_eq!(snapshot.instruction_data.assembly, "ORA $0F,X @ 10 = 40");
+        assert_eq!(snapshot.accumulator, 0x40);
+        assert!(!snapshot.status.contains(CpuStatusFlags::Zero));
+
+        let snapshot = cpu.run_until_next_snapshot();
+        assert_eq!(snapshot.instruction_data.assembly, "LDA #$01");
+        assert_eq!(snapshot.accumulator, 0x40);
+
+        let snapshot = cpu.run_until_next_snapshot();
+        assert_eq!(snapshot.instruction_data.assembly, "ORA $0180 = 82");
+        assert_eq!(snapshot.accumulator, 0x01);
+
+        // The trailing NOP of the mock cartridge
+        let snapshot = cpu.run_until_next_snapshot();
+        assert_eq!(snapshot.accumulator, 0x83);
+
+        let status = snapshot.status;
+        assert!(status.contains(CpuStatusFlags::Negative));
+        assert!(!status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_ora_absolute_y_page_cross() {
+        let cartridge = FlatCartridge::new(vec![
+            // ORA $01FF,Y
+            0x19, 0xFF, 0x01,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x0F;
+        cpu.register_y = 0x01;
+        cpu.bus.write(0x0200, 0xF0).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "ORA $01FF,Y @ 0200 = F0");
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 5);
+        assert_eq!(cpu.accumulator, 0xFF);
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_ora_indirect_x() {
+        let cartridge = FlatCartridge::new(vec![
+            // ORA ($20,X)
+            0x01, 0x20,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x01;
+        cpu.register_x = 0x02;
+        cpu.bus.write(0x0022, 0x00).unwrap();
+        cpu.bus.write(0x0023, 0x03).unwrap();
+        cpu.bus.write(0x0300, 0x10).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "ORA ($20,X) @ 22 = 0300 = 10");
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 6);
+        assert_eq!(cpu.accumulator, 0x11);
+    }
+
+    #[test]
+    fn test_ora_indirect_y_page_cross() {
+        let cartridge = FlatCartridge::new(vec![
+            // ORA ($40),Y
+            0x11, 0x40,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_y = 0x02;
+        cpu.bus.write(0x0040, 0xFF).unwrap();
+        cpu.bus.write(0x0041, 0x02).unwrap();
+        cpu.bus.write(0x0301, 0x00).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "ORA ($40),Y = 02FF @ 0301 = 00");
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 6);
+        assert_eq!(cpu.accumulator, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+    }
+}
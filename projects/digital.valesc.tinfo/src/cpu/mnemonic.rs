@@ -0,0 +1,346 @@
+//! Holds the mnemonics of the instructions of the CPU.
+
+use std::fmt;
+
+/// The mnemonics of the instructions of the CPU, including the unofficial ones and the interrupt
+/// pseudo-instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mnemonic {
+    /// Add with carry.
+    Adc,
+
+    /// AND then logical shift right the accumulator, unofficial.
+    Alr,
+
+    /// AND then copy the negative flag to the carry, unofficial.
+    Anc,
+
+    /// Logical AND.
+    And,
+
+    /// AND then rotate right the accumulator, unofficial.
+    Arr,
+
+    /// Arithmetic shift left.
+    Asl,
+
+    /// Store the accumulator AND X minus an immediate into X, unofficial.
+    Axs,
+
+    /// Branch if carry clear.
+    Bcc,
+
+    /// Branch if carry set.
+    Bcs,
+
+    /// Branch if equal.
+    Beq,
+
+    /// Bit test.
+    Bit,
+
+    /// Branch if minus.
+    Bmi,
+
+    /// Branch if not equal.
+    Bne,
+
+    /// Branch if positive.
+    Bpl,
+
+    /// Force interrupt.
+    Brk,
+
+    /// Branch if overflow clear.
+    Bvc,
+
+    /// Branch if overflow set.
+    Bvs,
+
+    /// Clear carry flag.
+    Clc,
+
+    /// Clear decimal mode.
+    Cld,
+
+    /// Clear interrupt disable.
+    Cli,
+
+    /// Clear overflow flag.
+    Clv,
+
+    /// Compare accumulator.
+    Cmp,
+
+    /// Compare X register.
+    Cpx,
+
+    /// Compare Y register.
+    Cpy,
+
+    /// Decrement memory then compare, unofficial.
+    Dcp,
+
+    /// Decrement memory.
+    Dec,
+
+    /// Decrement X register.
+    Dex,
+
+    /// Decrement Y register.
+    Dey,
+
+    /// Exclusive OR.
+    Eor,
+
+    /// Increment memory.
+    Inc,
+
+    /// Increment X register.
+    Inx,
+
+    /// Increment Y register.
+    Iny,
+
+    /// Increment memory then subtract with carry, unofficial.
+    Isb,
+
+    /// Halt the CPU until it is reset, unofficial.
+    Jam,
+
+    /// Jump.
+    Jmp,
+
+    /// Jump to subroutine.
+    Jsr,
+
+    /// Load the accumulator and X register, unofficial.
+    Lax,
+
+    /// Load accumulator.
+    Lda,
+
+    /// Load X register.
+    Ldx,
+
+    /// Load Y register.
+    Ldy,
+
+    /// Logical shift right.
+    Lsr,
+
+    /// No operation.
+    Nop,
+
+    /// Logical inclusive OR.
+    Ora,
+
+    /// Push accumulator.
+    Pha,
+
+    /// Push processor status.
+    Php,
+
+    /// Pull accumulator.
+    Pla,
+
+    /// Pull processor status.
+    Plp,
+
+    /// Rotate left then AND, unofficial.
+    Rla,
+
+    /// Rotate left.
+    Rol,
+
+    /// Rotate right.
+    Ror,
+
+    /// Rotate right then add with carry, unofficial.
+    Rra,
+
+    /// Return from interrupt.
+    Rti,
+
+    /// Return from subroutine.
+    Rts,
+
+    /// Store the accumulator AND X, unofficial.
+    Sax,
+
+    /// Subtract with carry.
+    Sbc,
+
+    /// Set carry flag.
+    Sec,
+
+    /// Set decimal flag.
+    Sed,
+
+    /// Set interrupt disable.
+    Sei,
+
+    /// Arithmetic shift left then logical inclusive OR, unofficial.
+    Slo,
+
+    /// Logical shift right then exclusive OR, unofficial.
+    Sre,
+
+    /// Store accumulator.
+    Sta,
+
+    /// Store X register.
+    Stx,
+
+    /// Store Y register.
+    Sty,
+
+    /// Transfer accumulator to X.
+    Tax,
+
+    /// Transfer accumulator to Y.
+    Tay,
+
+    /// Transfer stack pointer to X.
+    Tsx,
+
+    /// Transfer X to accumulator.
+    Txa,
+
+    /// Transfer X to stack pointer.
+    Txs,
+
+    /// Transfer Y to accumulator.
+    Tya,
+
+    /// Service a Non Maskable Interrupt, run in place of an instruction.
+    Nmi,
+
+    /// Service an Interrupt Request, run in place of an instruction.
+    Irq,
+}
+
+impl Mnemonic {
+    /// Get the name of the mnemonic as written in assembly.
+    pub fn name(self) -> &'static str {
+        match self {
+            Mnemonic::Adc => "ADC",
+            Mnemonic::Alr => "ALR",
+            Mnemonic::Anc => "ANC",
+            Mnemonic::And => "AND",
+            Mnemonic::Arr => "ARR",
+            Mnemonic::Asl => "ASL",
+            Mnemonic::Axs => "AXS",
+            Mnemonic::Bcc => "BCC",
+            Mnemonic::Bcs => "BCS",
+            Mnemonic::Beq => "BEQ",
+            Mnemonic::Bit => "BIT",
+            Mnemonic::Bmi => "BMI",
+            Mnemonic::Bne => "BNE",
+            Mnemonic::Bpl => "BPL",
+            Mnemonic::Brk => "BRK",
+            Mnemonic::Bvc => "BVC",
+            Mnemonic::Bvs => "BVS",
+            Mnemonic::Clc => "CLC",
+            Mnemonic::Cld => "CLD",
+            Mnemonic::Cli => "CLI",
+            Mnemonic::Clv => "CLV",
+            Mnemonic::Cmp => "CMP",
+            Mnemonic::Cpx => "CPX",
+            Mnemonic::Cpy => "CPY",
+            Mnemonic::Dcp => "DCP",
+            Mnemonic::Dec => "DEC",
+            Mnemonic::Dex => "DEX",
+            Mnemonic::Dey => "DEY",
+            Mnemonic::Eor => "EOR",
+            Mnemonic::Inc => "INC",
+            Mnemonic::Inx => "INX",
+            Mnemonic::Iny => "INY",
+            Mnemonic::Isb => "ISB",
+            Mnemonic::Jam => "JAM",
+            Mnemonic::Jmp => "JMP",
+            Mnemonic::Jsr => "JSR",
+            Mnemonic::Lax => "LAX",
+            Mnemonic::Lda => "LDA",
+            Mnemonic::Ldx => "LDX",
+            Mnemonic::Ldy => "LDY",
+            Mnemonic::Lsr => "LSR",
+            Mnemonic::Nop => "NOP",
+            Mnemonic::Ora => "ORA",
+            Mnemonic::Pha => "PHA",
+            Mnemonic::Php => "PHP",
+            Mnemonic::Pla => "PLA",
+            Mnemonic::Plp => "PLP",
+            Mnemonic::Rla => "RLA",
+            Mnemonic::Rol => "ROL",
+            Mnemonic::Ror => "ROR",
+            Mnemonic::Rra => "RRA",
+            Mnemonic::Rti => "RTI",
+            Mnemonic::Rts => "RTS",
+            Mnemonic::Sax => "SAX",
+            Mnemonic::Sbc => "SBC",
+            Mnemonic::Sec => "SEC",
+            Mnemonic::Sed => "SED",
+            Mnemonic::Sei => "SEI",
+            Mnemonic::Slo => "SLO",
+            Mnemonic::Sre => "SRE",
+            Mnemonic::Sta => "STA",
+            Mnemonic::Stx => "STX",
+            Mnemonic::Sty => "STY",
+            Mnemonic::Tax => "TAX",
+            Mnemonic::Tay => "TAY",
+            Mnemonic::Tsx => "TSX",
+            Mnemonic::Txa => "TXA",
+            Mnemonic::Txs => "TXS",
+            Mnemonic::Tya => "TYA",
+            Mnemonic::Nmi => "NMI",
+            Mnemonic::Irq => "IRQ",
+        }
+    }
+
+    /// Check if the mnemonic is one of the relative branches.
+    pub fn is_branch(self) -> bool {
+        matches!(
+            self,
+            Mnemonic::Bcc | Mnemonic::Bcs | Mnemonic::Beq | Mnemonic::Bmi | Mnemonic::Bne | Mnemonic::Bpl | Mnemonic::Bvc | Mnemonic::Bvs
+        )
+    }
+
+    /// Get the mnemonic as written in assembly, prefixed by `*` when the opcode is unofficial like
+    /// the nestest logs do.
+    pub(crate) fn assembly(self, official: bool) -> String {
+        match official {
+            true => String::from(self.name()),
+            false => format!("*{}", self.name()),
+        }
+    }
+}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Mnemonic::Lda.to_string(), "LDA");
+        assert_eq!(Mnemonic::Nmi.to_string(), "NMI");
+    }
+
+    #[test]
+    fn test_assembly() {
+        assert_eq!(Mnemonic::Slo.assembly(true), "SLO");
+        assert_eq!(Mnemonic::Slo.assembly(false), "*SLO");
+    }
+
+    #[test]
+    fn test_is_branch() {
+        assert!(Mnemonic::Bne.is_branch());
+        assert!(!Mnemonic::Jmp.is_branch());
+    }
+}
@@ -1,23 +1,9 @@
 //! Holds the implementation of the `NOP` instruction.
 
-use crate::bus::BusError;
 use crate::cpu::Cpu;
 use crate::cpu::CycleError;
-use crate::{build_address, cpu::impl_instruction_cycles};
-use crate::cpu::InstructionData;
-
-
-impl Cpu {
-    /// Implements the implied no operation instruction data.
-    pub(super) fn no_operation_implied_instruction(&mut self) -> Result<InstructionData, BusError> {
-        Ok(InstructionData {
-            arg_1: None,
-            arg_2: None,
-            assembly: String::from("NOP"),
-            idle_cycles: 1,
-        })
-    }
-}
+use crate::cpu::impl_instruction_cycles;
+
 
 impl_instruction_cycles!(
     /// Implements the implied no operation instruction cycles.
@@ -36,14 +22,14 @@ mod tests {
 
     #[test]
     fn test_nop_immediate() {
-        let cartridge = MockCartridge::new(vec![
+        let cartridge = FlatCartridge::new(vec![
             // NOP
             0xEA,
         ]);
 
-        let mut cpu = Cpu::new(Box::new(cartridge));
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
 
-        let instruction_data = cpu.cycle().unwrap().unwrap().instruction_data;
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
         assert_eq!(instruction_data.assembly, "NOP");
         assert_eq!(instruction_data.idle_cycles, 1);
 
@@ -0,0 +1,474 @@
+//! Holds the metadata table describing every opcode of the CPU.
+
+use std::fmt;
+
+use crate::cartridge::Cartridge;
+use crate::cpu::interrupt::Interrupt;
+use crate::cpu::transfer::Register;
+use crate::cpu::{AddressingMode, Cpu, CycleError, InstructionData, Mnemonic};
+
+/// Runs a cycle of an instruction after its opcode has been fetched, given its addressing mode,
+/// returning if the instruction ended.
+pub(super) type OpcodeHandler<C> = fn(&mut Cpu<C>, AddressingMode) -> Result<bool, CycleError>;
+
+/// The metadata of an opcode, enough to disassemble it and run its cycles on a [Cpu] holding the
+/// given cartridge.
+pub(crate) struct OpcodeInfo<C: Cartridge = Box<dyn Cartridge>> {
+    /// The mnemonic of the instruction.
+    pub(crate) mnemonic: Mnemonic,
+
+    /// The way the instruction gets its operand.
+    pub(crate) addressing_mode: AddressingMode,
+
+    /// The number of cycles the instruction takes, including the opcode fetch but not the extra cycles
+    /// of page crosses and taken branches.
+    pub(super) cycles: u8,
+
+    /// If the opcode is part of the documented instruction set of the 6502.
+    pub(crate) official: bool,
+
+    /// Runs the cycles of the instruction after the opcode fetch.
+    pub(super) handler: OpcodeHandler<C>,
+}
+
+// Not derived, as deriving requires the cartridge to be copyable while only the handler depends on it
+impl<C: Cartridge> Clone for OpcodeInfo<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: Cartridge> Copy for OpcodeInfo<C> {}
+
+impl<C: Cartridge> OpcodeInfo<C> {
+    /// Check if the opcode halts the CPU.
+    pub(super) fn is_jam(&self) -> bool {
+        self.mnemonic == Mnemonic::Jam
+    }
+
+    /// Check if the instruction takes an extra cycle when its indexed address crosses a page.
+    ///
+    /// Only the instructions that just read their operand skip the cycle fixing the address, these
+    /// are the ones taking the fewest cycles for their addressing mode.
+    pub(super) fn has_page_cross_penalty(&self) -> bool {
+        match self.addressing_mode {
+            AddressingMode::AbsoluteX | AddressingMode::AbsoluteY => self.cycles == 4,
+            AddressingMode::IndirectY => self.cycles == 5,
+            _ => false,
+        }
+    }
+
+    /// Get the assembly mnemonic, prefixed by `*` when the opcode is unofficial like the nestest logs do.
+    pub(crate) fn assembly_mnemonic(&self) -> String {
+        self.mnemonic.assembly(self.official)
+    }
+}
+
+impl<C: Cartridge> fmt::Debug for OpcodeInfo<C> {
+    /// Format the metadata leaving out the handler, whose address tells nothing.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpcodeInfo")
+            .field("mnemonic", &self.mnemonic)
+            .field("addressing_mode", &self.addressing_mode)
+            .field("cycles", &self.cycles)
+            .field("official", &self.official)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Build the metadata of an official opcode.
+const fn official<C: Cartridge>(
+    mnemonic: Mnemonic,
+    addressing_mode: AddressingMode,
+    cycles: u8,
+    handler: OpcodeHandler<C>,
+) -> Option<OpcodeInfo<C>> {
+    Some(OpcodeInfo { mnemonic, addressing_mode, cycles, official: true, handler })
+}
+
+/// Build the metadata of an unofficial opcode.
+const fn unofficial<C: Cartridge>(
+    mnemonic: Mnemonic,
+    addressing_mode: AddressingMode,
+    cycles: u8,
+    handler: OpcodeHandler<C>,
+) -> Option<OpcodeInfo<C>> {
+    Some(OpcodeInfo { mnemonic, addressing_mode, cycles, official: false, handler })
+}
+
+/// Build the metadata of one of the opcodes that halt the CPU, which never runs any cycle.
+const fn jam<C: Cartridge>() -> Option<OpcodeInfo<C>> {
+    unofficial(Mnemonic::Jam, AddressingMode::Implied, 0, |cpu, _| {
+        Err(CycleError::Jammed {
+            opcode: cpu.current_opcode,
+            program_counter: cpu.current_instruction_address,
+        })
+    })
+}
+
+impl<C: Cartridge> Cpu<C> {
+    /// The metadata of the pseudo-instruction servicing a Non Maskable Interrupt, run in place of
+    /// the instruction at the program counter.
+    pub(super) const NMI: OpcodeInfo<C> = OpcodeInfo {
+        mnemonic: Mnemonic::Nmi,
+        addressing_mode: AddressingMode::Implied,
+        cycles: 7,
+        official: true,
+        handler: |cpu, _| cpu.interrupt_cycles(Interrupt::Nmi),
+    };
+
+    /// The metadata of the pseudo-instruction servicing an Interrupt Request, run in place of the
+    /// instruction at the program counter.
+    pub(super) const IRQ: OpcodeInfo<C> = OpcodeInfo {
+        mnemonic: Mnemonic::Irq,
+        addressing_mode: AddressingMode::Implied,
+        cycles: 7,
+        official: true,
+        handler: |cpu, _| cpu.interrupt_cycles(Interrupt::Irq),
+    };
+
+    /// The metadata of every opcode indexed by the opcode itself, [None] for the unsupported ones.
+    pub(super) const OPCODES: [Option<OpcodeInfo<C>>; 256] = opcode_table();
+}
+
+/// The metadata of every opcode for the boxed cartridges, for the users of the metadata alone like
+/// the disassembler.
+pub(crate) const OPCODES: [Option<OpcodeInfo>; 256] = opcode_table();
+
+/// Build the metadata of every opcode indexed by the opcode itself, [None] for the unsupported ones.
+const fn opcode_table<C: Cartridge>() -> [Option<OpcodeInfo<C>>; 256] {
+    let mut opcodes = [None; 256];
+
+    opcodes[0x00] = official(Mnemonic::Brk, AddressingMode::Implied, 7, |cpu, _| cpu.interrupt_cycles(Interrupt::Break));
+    opcodes[0x01] = official(Mnemonic::Ora, AddressingMode::IndirectX, 6, Cpu::logical_inclusive_or_cycles);
+    opcodes[0x02] = jam();
+    opcodes[0x03] = unofficial(Mnemonic::Slo, AddressingMode::IndirectX, 8, Cpu::shift_left_or_cycles);
+    opcodes[0x04] = unofficial(Mnemonic::Nop, AddressingMode::ZeroPage, 3, Cpu::no_operation_read_cycles);
+    opcodes[0x05] = official(Mnemonic::Ora, AddressingMode::ZeroPage, 3, Cpu::logical_inclusive_or_cycles);
+    opcodes[0x06] = official(Mnemonic::Asl, AddressingMode::ZeroPage, 5, Cpu::arithmetic_shift_left_cycles);
+    opcodes[0x07] = unofficial(Mnemonic::Slo, AddressingMode::ZeroPage, 5, Cpu::shift_left_or_cycles);
+    opcodes[0x08] = official(Mnemonic::Php, AddressingMode::Implied, 3, |cpu, _| cpu.push_processor_status_implied_cycles());
+    opcodes[0x09] = official(Mnemonic::Ora, AddressingMode::Immediate, 2, Cpu::logical_inclusive_or_cycles);
+    opcodes[0x0A] = official(Mnemonic::Asl, AddressingMode::Accumulator, 2, |cpu, _| cpu.arithmetic_shift_left_accumulator_cycles());
+    opcodes[0x0B] = unofficial(Mnemonic::Anc, AddressingMode::Immediate, 2, |cpu, _| cpu.and_copy_carry_immediate_cycles());
+    opcodes[0x0C] = unofficial(Mnemonic::Nop, AddressingMode::Absolute, 4, Cpu::no_operation_read_cycles);
+    opcodes[0x0D] = official(Mnemonic::Ora, AddressingMode::Absolute, 4, Cpu::logical_inclusive_or_cycles);
+    opcodes[0x0E] = official(Mnemonic::Asl, AddressingMode::Absolute, 6, Cpu::arithmetic_shift_left_cycles);
+    opcodes[0x0F] = unofficial(Mnemonic::Slo, AddressingMode::Absolute, 6, Cpu::shift_left_or_cycles);
+    opcodes[0x10] = official(Mnemonic::Bpl, AddressingMode::Relative, 2, |cpu, _| cpu.branch_cycles());
+    opcodes[0x11] = official(Mnemonic::Ora, AddressingMode::IndirectY, 5, Cpu::logical_inclusive_or_cycles);
+    opcodes[0x12] = jam();
+    opcodes[0x13] = unofficial(Mnemonic::Slo, AddressingMode::IndirectY, 8, Cpu::shift_left_or_cycles);
+    opcodes[0x14] = unofficial(Mnemonic::Nop, AddressingMode::ZeroPageX, 4, Cpu::no_operation_read_cycles);
+    opcodes[0x15] = official(Mnemonic::Ora, AddressingMode::ZeroPageX, 4, Cpu::logical_inclusive_or_cycles);
+    opcodes[0x16] = official(Mnemonic::Asl, AddressingMode::ZeroPageX, 6, Cpu::arithmetic_shift_left_cycles);
+    opcodes[0x17] = unofficial(Mnemonic::Slo, AddressingMode::ZeroPageX, 6, Cpu::shift_left_or_cycles);
+    opcodes[0x18] = official(Mnemonic::Clc, AddressingMode::Implied, 2, |cpu, _| cpu.clear_carry_flag_implied_cycles());
+    opcodes[0x19] = official(Mnemonic::Ora, AddressingMode::AbsoluteY, 4, Cpu::logical_inclusive_or_cycles);
+    opcodes[0x1A] = unofficial(Mnemonic::Nop, AddressingMode::Implied, 2, |cpu, _| cpu.no_operation_cycles());
+    opcodes[0x1B] = unofficial(Mnemonic::Slo, AddressingMode::AbsoluteY, 7, Cpu::shift_left_or_cycles);
+    opcodes[0x1C] = unofficial(Mnemonic::Nop, AddressingMode::AbsoluteX, 4, Cpu::no_operation_read_cycles);
+    opcodes[0x1D] = official(Mnemonic::Ora, AddressingMode::AbsoluteX, 4, Cpu::logical_inclusive_or_cycles);
+    opcodes[0x1E] = official(Mnemonic::Asl, AddressingMode::AbsoluteX, 7, Cpu::arithmetic_shift_left_cycles);
+    opcodes[0x1F] = unofficial(Mnemonic::Slo, AddressingMode::AbsoluteX, 7, Cpu::shift_left_or_cycles);
+    opcodes[0x20] = official(Mnemonic::Jsr, AddressingMode::Absolute, 6, |cpu, _| cpu.jump_to_subroutine_absolute_cycles());
+    opcodes[0x22] = jam();
+    opcodes[0x23] = unofficial(Mnemonic::Rla, AddressingMode::IndirectX, 8, Cpu::rotate_left_and_cycles);
+    opcodes[0x24] = official(Mnemonic::Bit, AddressingMode::ZeroPage, 3, Cpu::bit_test_cycles);
+    opcodes[0x26] = official(Mnemonic::Rol, AddressingMode::ZeroPage, 5, Cpu::rotate_left_cycles);
+    opcodes[0x27] = unofficial(Mnemonic::Rla, AddressingMode::ZeroPage, 5, Cpu::rotate_left_and_cycles);
+    opcodes[0x28] = official(Mnemonic::Plp, AddressingMode::Implied, 4, |cpu, _| cpu.pull_processor_status_implied_cycles());
+    opcodes[0x2A] = official(Mnemonic::Rol, AddressingMode::Accumulator, 2, |cpu, _| cpu.rotate_left_accumulator_cycles());
+    opcodes[0x2B] = unofficial(Mnemonic::Anc, AddressingMode::Immediate, 2, |cpu, _| cpu.and_copy_carry_immediate_cycles());
+    opcodes[0x2C] = official(Mnemonic::Bit, AddressingMode::Absolute, 4, Cpu::bit_test_cycles);
+    opcodes[0x2E] = official(Mnemonic::Rol, AddressingMode::Absolute, 6, Cpu::rotate_left_cycles);
+    opcodes[0x2F] = unofficial(Mnemonic::Rla, AddressingMode::Absolute, 6, Cpu::rotate_left_and_cycles);
+    opcodes[0x30] = official(Mnemonic::Bmi, AddressingMode::Relative, 2, |cpu, _| cpu.branch_cycles());
+    opcodes[0x32] = jam();
+    opcodes[0x33] = unofficial(Mnemonic::Rla, AddressingMode::IndirectY, 8, Cpu::rotate_left_and_cycles);
+    opcodes[0x34] = unofficial(Mnemonic::Nop, AddressingMode::ZeroPageX, 4, Cpu::no_operation_read_cycles);
+    opcodes[0x36] = official(Mnemonic::Rol, AddressingMode::ZeroPageX, 6, Cpu::rotate_left_cycles);
+    opcodes[0x37] = unofficial(Mnemonic::Rla, AddressingMode::ZeroPageX, 6, Cpu::rotate_left_and_cycles);
+    opcodes[0x38] = official(Mnemonic::Sec, AddressingMode::Implied, 2, |cpu, _| cpu.set_carry_flag_implied_cycles());
+    opcodes[0x3A] = unofficial(Mnemonic::Nop, AddressingMode::Implied, 2, |cpu, _| cpu.no_operation_cycles());
+    opcodes[0x3B] = unofficial(Mnemonic::Rla, AddressingMode::AbsoluteY, 7, Cpu::rotate_left_and_cycles);
+    opcodes[0x3C] = unofficial(Mnemonic::Nop, AddressingMode::AbsoluteX, 4, Cpu::no_operation_read_cycles);
+    opcodes[0x3E] = official(Mnemonic::Rol, AddressingMode::AbsoluteX, 7, Cpu::rotate_left_cycles);
+    opcodes[0x3F] = unofficial(Mnemonic::Rla, AddressingMode::AbsoluteX, 7, Cpu::rotate_left_and_cycles);
+    opcodes[0x40] = official(Mnemonic::Rti, AddressingMode::Implied, 6, |cpu, _| cpu.return_from_interrupt_cycles());
+    opcodes[0x42] = jam();
+    opcodes[0x43] = unofficial(Mnemonic::Sre, AddressingMode::IndirectX, 8, Cpu::shift_right_exclusive_or_cycles);
+    opcodes[0x44] = unofficial(Mnemonic::Nop, AddressingMode::ZeroPage, 3, Cpu::no_operation_read_cycles);
+    opcodes[0x47] = unofficial(Mnemonic::Sre, AddressingMode::ZeroPage, 5, Cpu::shift_right_exclusive_or_cycles);
+    opcodes[0x48] = official(Mnemonic::Pha, AddressingMode::Implied, 3, |cpu, _| cpu.push_accumulator_implied_cycles());
+    opcodes[0x4B] = unofficial(Mnemonic::Alr, AddressingMode::Immediate, 2, |cpu, _| cpu.and_shift_right_immediate_cycles());
+    opcodes[0x4C] = official(Mnemonic::Jmp, AddressingMode::Absolute, 3, |cpu, _| cpu.jump_absolute_cycles());
+    opcodes[0x4F] = unofficial(Mnemonic::Sre, AddressingMode::Absolute, 6, Cpu::shift_right_exclusive_or_cycles);
+    opcodes[0x50] = official(Mnemonic::Bvc, AddressingMode::Relative, 2, |cpu, _| cpu.branch_cycles());
+    opcodes[0x52] = jam();
+    opcodes[0x53] = unofficial(Mnemonic::Sre, AddressingMode::IndirectY, 8, Cpu::shift_right_exclusive_or_cycles);
+    opcodes[0x54] = unofficial(Mnemonic::Nop, AddressingMode::ZeroPageX, 4, Cpu::no_operation_read_cycles);
+    opcodes[0x57] = unofficial(Mnemonic::Sre, AddressingMode::ZeroPageX, 6, Cpu::shift_right_exclusive_or_cycles);
+    opcodes[0x58] = official(Mnemonic::Cli, AddressingMode::Implied, 2, |cpu, _| cpu.clear_interrupt_disable_flag_implied_cycles());
+    opcodes[0x5A] = unofficial(Mnemonic::Nop, AddressingMode::Implied, 2, |cpu, _| cpu.no_operation_cycles());
+    opcodes[0x5B] = unofficial(Mnemonic::Sre, AddressingMode::AbsoluteY, 7, Cpu::shift_right_exclusive_or_cycles);
+    opcodes[0x5C] = unofficial(Mnemonic::Nop, AddressingMode::AbsoluteX, 4, Cpu::no_operation_read_cycles);
+    opcodes[0x5F] = unofficial(Mnemonic::Sre, AddressingMode::AbsoluteX, 7, Cpu::shift_right_exclusive_or_cycles);
+    opcodes[0x60] = official(Mnemonic::Rts, AddressingMode::Implied, 6, |cpu, _| cpu.return_from_subroutine_cycles());
+    opcodes[0x61] = official(Mnemonic::Adc, AddressingMode::IndirectX, 6, Cpu::add_with_carry_cycles);
+    opcodes[0x62] = jam();
+    opcodes[0x63] = unofficial(Mnemonic::Rra, AddressingMode::IndirectX, 8, Cpu::rotate_right_add_cycles);
+    opcodes[0x64] = unofficial(Mnemonic::Nop, AddressingMode::ZeroPage, 3, Cpu::no_operation_read_cycles);
+    opcodes[0x65] = official(Mnemonic::Adc, AddressingMode::ZeroPage, 3, Cpu::add_with_carry_cycles);
+    opcodes[0x66] = official(Mnemonic::Ror, AddressingMode::ZeroPage, 5, Cpu::rotate_right_cycles);
+    opcodes[0x67] = unofficial(Mnemonic::Rra, AddressingMode::ZeroPage, 5, Cpu::rotate_right_add_cycles);
+    opcodes[0x68] = official(Mnemonic::Pla, AddressingMode::Implied, 4, |cpu, _| cpu.pull_accumulator_implied_cycles());
+    opcodes[0x69] = official(Mnemonic::Adc, AddressingMode::Immediate, 2, Cpu::add_with_carry_cycles);
+    opcodes[0x6A] = official(Mnemonic::Ror, AddressingMode::Accumulator, 2, |cpu, _| cpu.rotate_right_accumulator_cycles());
+    opcodes[0x6B] = unofficial(Mnemonic::Arr, AddressingMode::Immediate, 2, |cpu, _| cpu.and_rotate_right_immediate_cycles());
+    opcodes[0x6C] = official(Mnemonic::Jmp, AddressingMode::Indirect, 5, |cpu, _| cpu.jump_indirect_cycles());
+    opcodes[0x6D] = official(Mnemonic::Adc, AddressingMode::Absolute, 4, Cpu::add_with_carry_cycles);
+    opcodes[0x6E] = official(Mnemonic::Ror, AddressingMode::Absolute, 6, Cpu::rotate_right_cycles);
+    opcodes[0x6F] = unofficial(Mnemonic::Rra, AddressingMode::Absolute, 6, Cpu::rotate_right_add_cycles);
+    opcodes[0x70] = official(Mnemonic::Bvs, AddressingMode::Relative, 2, |cpu, _| cpu.branch_cycles());
+    opcodes[0x71] = official(Mnemonic::Adc, AddressingMode::IndirectY, 5, Cpu::add_with_carry_cycles);
+    opcodes[0x72] = jam();
+    opcodes[0x73] = unofficial(Mnemonic::Rra, AddressingMode::IndirectY, 8, Cpu::rotate_right_add_cycles);
+    opcodes[0x74] = unofficial(Mnemonic::Nop, AddressingMode::ZeroPageX, 4, Cpu::no_operation_read_cycles);
+    opcodes[0x75] = official(Mnemonic::Adc, AddressingMode::ZeroPageX, 4, Cpu::add_with_carry_cycles);
+    opcodes[0x76] = official(Mnemonic::Ror, AddressingMode::ZeroPageX, 6, Cpu::rotate_right_cycles);
+    opcodes[0x77] = unofficial(Mnemonic::Rra, AddressingMode::ZeroPageX, 6, Cpu::rotate_right_add_cycles);
+    opcodes[0x78] = official(Mnemonic::Sei, AddressingMode::Implied, 2, |cpu, _| cpu.set_interrupt_disable_flag_implied_cycles());
+    opcodes[0x79] = official(Mnemonic::Adc, AddressingMode::AbsoluteY, 4, Cpu::add_with_carry_cycles);
+    opcodes[0x7A] = unofficial(Mnemonic::Nop, AddressingMode::Implied, 2, |cpu, _| cpu.no_operation_cycles());
+    opcodes[0x7B] = unofficial(Mnemonic::Rra, AddressingMode::AbsoluteY, 7, Cpu::rotate_right_add_cycles);
+    opcodes[0x7C] = unofficial(Mnemonic::Nop, AddressingMode::AbsoluteX, 4, Cpu::no_operation_read_cycles);
+    opcodes[0x7D] = official(Mnemonic::Adc, AddressingMode::AbsoluteX, 4, Cpu::add_with_carry_cycles);
+    opcodes[0x7E] = official(Mnemonic::Ror, AddressingMode::AbsoluteX, 7, Cpu::rotate_right_cycles);
+    opcodes[0x7F] = unofficial(Mnemonic::Rra, AddressingMode::AbsoluteX, 7, Cpu::rotate_right_add_cycles);
+    opcodes[0x80] = unofficial(Mnemonic::Nop, AddressingMode::Immediate, 2, Cpu::no_operation_read_cycles);
+    opcodes[0x82] = unofficial(Mnemonic::Nop, AddressingMode::Immediate, 2, Cpu::no_operation_read_cycles);
+    opcodes[0x83] = unofficial(Mnemonic::Sax, AddressingMode::IndirectX, 6, Cpu::store_accumulator_and_x_register_cycles);
+    opcodes[0x84] = official(Mnemonic::Sty, AddressingMode::ZeroPage, 3, Cpu::store_y_register_cycles);
+    opcodes[0x85] = official(Mnemonic::Sta, AddressingMode::ZeroPage, 3, Cpu::store_accumulator_cycles);
+    opcodes[0x86] = official(Mnemonic::Stx, AddressingMode::ZeroPage, 3, Cpu::store_x_register_cycles);
+    opcodes[0x87] = unofficial(Mnemonic::Sax, AddressingMode::ZeroPage, 3, Cpu::store_accumulator_and_x_register_cycles);
+    opcodes[0x89] = unofficial(Mnemonic::Nop, AddressingMode::Immediate, 2, Cpu::no_operation_read_cycles);
+    opcodes[0x8A] = official(Mnemonic::Txa, AddressingMode::Implied, 2, |cpu, _| cpu.transfer_cycles(Register::X, Register::Accumulator));
+    opcodes[0x8C] = official(Mnemonic::Sty, AddressingMode::Absolute, 4, Cpu::store_y_register_cycles);
+    opcodes[0x8D] = official(Mnemonic::Sta, AddressingMode::Absolute, 4, Cpu::store_accumulator_cycles);
+    opcodes[0x8F] = unofficial(Mnemonic::Sax, AddressingMode::Absolute, 4, Cpu::store_accumulator_and_x_register_cycles);
+    opcodes[0x90] = official(Mnemonic::Bcc, AddressingMode::Relative, 2, |cpu, _| cpu.branch_cycles());
+    opcodes[0x91] = official(Mnemonic::Sta, AddressingMode::IndirectY, 6, Cpu::store_accumulator_cycles);
+    opcodes[0x92] = jam();
+    opcodes[0x94] = official(Mnemonic::Sty, AddressingMode::ZeroPageX, 4, Cpu::store_y_register_cycles);
+    opcodes[0x97] = unofficial(Mnemonic::Sax, AddressingMode::ZeroPageY, 4, Cpu::store_accumulator_and_x_register_cycles);
+    opcodes[0x98] = official(Mnemonic::Tya, AddressingMode::Implied, 2, |cpu, _| cpu.transfer_cycles(Register::Y, Register::Accumulator));
+    opcodes[0x99] = official(Mnemonic::Sta, AddressingMode::AbsoluteY, 5, Cpu::store_accumulator_cycles);
+    opcodes[0x9A] = official(Mnemonic::Txs, AddressingMode::Implied, 2, |cpu, _| cpu.transfer_cycles(Register::X, Register::StackPointer));
+    opcodes[0x9D] = official(Mnemonic::Sta, AddressingMode::AbsoluteX, 5, Cpu::store_accumulator_cycles);
+    opcodes[0xA0] = official(Mnemonic::Ldy, AddressingMode::Immediate, 2, Cpu::load_y_register_cycles);
+    opcodes[0xA2] = official(Mnemonic::Ldx, AddressingMode::Immediate, 2, Cpu::load_x_register_cycles);
+    opcodes[0xA3] = unofficial(Mnemonic::Lax, AddressingMode::IndirectX, 6, Cpu::load_accumulator_x_register_cycles);
+    opcodes[0xA4] = official(Mnemonic::Ldy, AddressingMode::ZeroPage, 3, Cpu::load_y_register_cycles);
+    opcodes[0xA6] = official(Mnemonic::Ldx, AddressingMode::ZeroPage, 3, Cpu::load_x_register_cycles);
+    opcodes[0xA7] = unofficial(Mnemonic::Lax, AddressingMode::ZeroPage, 3, Cpu::load_accumulator_x_register_cycles);
+    opcodes[0xA8] = official(Mnemonic::Tay, AddressingMode::Implied, 2, |cpu, _| cpu.transfer_cycles(Register::Accumulator, Register::Y));
+    opcodes[0xA9] = official(Mnemonic::Lda, AddressingMode::Immediate, 2, Cpu::load_accumulator_cycles);
+    opcodes[0xAA] = official(Mnemonic::Tax, AddressingMode::Implied, 2, |cpu, _| cpu.transfer_cycles(Register::Accumulator, Register::X));
+    opcodes[0xAC] = official(Mnemonic::Ldy, AddressingMode::Absolute, 4, Cpu::load_y_register_cycles);
+    opcodes[0xAD] = official(Mnemonic::Lda, AddressingMode::Absolute, 4, Cpu::load_accumulator_cycles);
+    opcodes[0xAE] = official(Mnemonic::Ldx, AddressingMode::Absolute, 4, Cpu::load_x_register_cycles);
+    opcodes[0xAF] = unofficial(Mnemonic::Lax, AddressingMode::Absolute, 4, Cpu::load_accumulator_x_register_cycles);
+    opcodes[0xB0] = official(Mnemonic::Bcs, AddressingMode::Relative, 2, |cpu, _| cpu.branch_cycles());
+    opcodes[0xB2] = jam();
+    opcodes[0xB3] = unofficial(Mnemonic::Lax, AddressingMode::IndirectY, 5, Cpu::load_accumulator_x_register_cycles);
+    opcodes[0xB4] = official(Mnemonic::Ldy, AddressingMode::ZeroPageX, 4, Cpu::load_y_register_cycles);
+    opcodes[0xB6] = official(Mnemonic::Ldx, AddressingMode::ZeroPageY, 4, Cpu::load_x_register_cycles);
+    opcodes[0xB7] = unofficial(Mnemonic::Lax, AddressingMode::ZeroPageY, 4, Cpu::load_accumulator_x_register_cycles);
+    opcodes[0xB9] = official(Mnemonic::Lda, AddressingMode::AbsoluteY, 4, Cpu::load_accumulator_cycles);
+    opcodes[0xBA] = official(Mnemonic::Tsx, AddressingMode::Implied, 2, |cpu, _| cpu.transfer_cycles(Register::StackPointer, Register::X));
+    opcodes[0xBC] = official(Mnemonic::Ldy, AddressingMode::AbsoluteX, 4, Cpu::load_y_register_cycles);
+    opcodes[0xBD] = official(Mnemonic::Lda, AddressingMode::AbsoluteX, 4, Cpu::load_accumulator_cycles);
+    opcodes[0xBE] = official(Mnemonic::Ldx, AddressingMode::AbsoluteY, 4, Cpu::load_x_register_cycles);
+    opcodes[0xBF] = unofficial(Mnemonic::Lax, AddressingMode::AbsoluteY, 4, Cpu::load_accumulator_x_register_cycles);
+    opcodes[0xC0] = official(Mnemonic::Cpy, AddressingMode::Immediate, 2, Cpu::compare_y_register_cycles);
+    opcodes[0xC2] = unofficial(Mnemonic::Nop, AddressingMode::Immediate, 2, Cpu::no_operation_read_cycles);
+    opcodes[0xC3] = unofficial(Mnemonic::Dcp, AddressingMode::IndirectX, 8, Cpu::decrement_compare_cycles);
+    opcodes[0xC4] = official(Mnemonic::Cpy, AddressingMode::ZeroPage, 3, Cpu::compare_y_register_cycles);
+    opcodes[0xC6] = official(Mnemonic::Dec, AddressingMode::ZeroPage, 5, Cpu::decrement_memory_cycles);
+    opcodes[0xC7] = unofficial(Mnemonic::Dcp, AddressingMode::ZeroPage, 5, Cpu::decrement_compare_cycles);
+    opcodes[0xCB] = unofficial(Mnemonic::Axs, AddressingMode::Immediate, 2, |cpu, _| cpu.and_x_register_subtract_immediate_cycles());
+    opcodes[0xCC] = official(Mnemonic::Cpy, AddressingMode::Absolute, 4, Cpu::compare_y_register_cycles);
+    opcodes[0xCE] = official(Mnemonic::Dec, AddressingMode::Absolute, 6, Cpu::decrement_memory_cycles);
+    opcodes[0xCF] = unofficial(Mnemonic::Dcp, AddressingMode::Absolute, 6, Cpu::decrement_compare_cycles);
+    opcodes[0xD0] = official(Mnemonic::Bne, AddressingMode::Relative, 2, |cpu, _| cpu.branch_cycles());
+    opcodes[0xD2] = jam();
+    opcodes[0xD3] = unofficial(Mnemonic::Dcp, AddressingMode::IndirectY, 8, Cpu::decrement_compare_cycles);
+    opcodes[0xD4] = unofficial(Mnemonic::Nop, AddressingMode::ZeroPageX, 4, Cpu::no_operation_read_cycles);
+    opcodes[0xD6] = official(Mnemonic::Dec, AddressingMode::ZeroPageX, 6, Cpu::decrement_memory_cycles);
+    opcodes[0xD7] = unofficial(Mnemonic::Dcp, AddressingMode::ZeroPageX, 6, Cpu::decrement_compare_cycles);
+    opcodes[0xD8] = official(Mnemonic::Cld, AddressingMode::Implied, 2, |cpu, _| cpu.clear_decimal_flag_implied_cycles());
+    opcodes[0xDA] = unofficial(Mnemonic::Nop, AddressingMode::Implied, 2, |cpu, _| cpu.no_operation_cycles());
+    opcodes[0xDB] = unofficial(Mnemonic::Dcp, AddressingMode::AbsoluteY, 7, Cpu::decrement_compare_cycles);
+    opcodes[0xDC] = unofficial(Mnemonic::Nop, AddressingMode::AbsoluteX, 4, Cpu::no_operation_read_cycles);
+    opcodes[0xDE] = official(Mnemonic::Dec, AddressingMode::AbsoluteX, 7, Cpu::decrement_memory_cycles);
+    opcodes[0xDF] = unofficial(Mnemonic::Dcp, AddressingMode::AbsoluteX, 7, Cpu::decrement_compare_cycles);
+    opcodes[0xE0] = official(Mnemonic::Cpx, AddressingMode::Immediate, 2, Cpu::compare_x_register_cycles);
+    opcodes[0xE1] = official(Mnemonic::Sbc, AddressingMode::IndirectX, 6, Cpu::subtract_with_carry_cycles);
+    opcodes[0xE2] = unofficial(Mnemonic::Nop, AddressingMode::Immediate, 2, Cpu::no_operation_read_cycles);
+    opcodes[0xE3] = unofficial(Mnemonic::Isb, AddressingMode::IndirectX, 8, Cpu::increment_subtract_cycles);
+    opcodes[0xE4] = official(Mnemonic::Cpx, AddressingMode::ZeroPage, 3, Cpu::compare_x_register_cycles);
+    opcodes[0xE5] = official(Mnemonic::Sbc, AddressingMode::ZeroPage, 3, Cpu::subtract_with_carry_cycles);
+    opcodes[0xE6] = official(Mnemonic::Inc, AddressingMode::ZeroPage, 5, Cpu::increment_memory_cycles);
+    opcodes[0xE7] = unofficial(Mnemonic::Isb, AddressingMode::ZeroPage, 5, Cpu::increment_subtract_cycles);
+    opcodes[0xE9] = official(Mnemonic::Sbc, AddressingMode::Immediate, 2, Cpu::subtract_with_carry_cycles);
+    opcodes[0xEA] = official(Mnemonic::Nop, AddressingMode::Implied, 2, |cpu, _| cpu.no_operation_cycles());
+    opcodes[0xEB] = unofficial(Mnemonic::Sbc, AddressingMode::Immediate, 2, Cpu::subtract_with_carry_cycles);
+    opcodes[0xEC] = official(Mnemonic::Cpx, AddressingMode::Absolute, 4, Cpu::compare_x_register_cycles);
+    opcodes[0xED] = official(Mnemonic::Sbc, AddressingMode::Absolute, 4, Cpu::subtract_with_carry_cycles);
+    opcodes[0xEE] = official(Mnemonic::Inc, AddressingMode::Absolute, 6, Cpu::increment_memory_cycles);
+    opcodes[0xEF] = unofficial(Mnemonic::Isb, AddressingMode::Absolute, 6, Cpu::increment_subtract_cycles);
+    opcodes[0xF0] = official(Mnemonic::Beq, AddressingMode::Relative, 2, |cpu, _| cpu.branch_cycles());
+    opcodes[0xF1] = official(Mnemonic::Sbc, AddressingMode::IndirectY, 5, Cpu::subtract_with_carry_cycles);
+    opcodes[0xF2] = jam();
+    opcodes[0xF3] = unofficial(Mnemonic::Isb, AddressingMode::IndirectY, 8, Cpu::increment_subtract_cycles);
+    opcodes[0xF4] = unofficial(Mnemonic::Nop, AddressingMode::ZeroPageX, 4, Cpu::no_operation_read_cycles);
+    opcodes[0xF5] = official(Mnemonic::Sbc, AddressingMode::ZeroPageX, 4, Cpu::subtract_with_carry_cycles);
+    opcodes[0xF6] = official(Mnemonic::Inc, AddressingMode::ZeroPageX, 6, Cpu::increment_memory_cycles);
+    opcodes[0xF7] = unofficial(Mnemonic::Isb, AddressingMode::ZeroPageX, 6, Cpu::increment_subtract_cycles);
+    opcodes[0xF8] = official(Mnemonic::Sed, AddressingMode::Implied, 2, |cpu, _| cpu.set_decimal_flag_implied_cycles());
+    opcodes[0xF9] = official(Mnemonic::Sbc, AddressingMode::AbsoluteY, 4, Cpu::subtract_with_carry_cycles);
+    opcodes[0xFA] = unofficial(Mnemonic::Nop, AddressingMode::Implied, 2, |cpu, _| cpu.no_operation_cycles());
+    opcodes[0xFB] = unofficial(Mnemonic::Isb, AddressingMode::AbsoluteY, 7, Cpu::increment_subtract_cycles);
+    opcodes[0xFC] = unofficial(Mnemonic::Nop, AddressingMode::AbsoluteX, 4, Cpu::no_operation_read_cycles);
+    opcodes[0xFD] = official(Mnemonic::Sbc, AddressingMode::AbsoluteX, 4, Cpu::subtract_with_carry_cycles);
+    opcodes[0xFE] = official(Mnemonic::Inc, AddressingMode::AbsoluteX, 7, Cpu::increment_memory_cycles);
+    opcodes[0xFF] = unofficial(Mnemonic::Isb, AddressingMode::AbsoluteX, 7, Cpu::increment_subtract_cycles);
+
+    opcodes
+}
+
+impl<C: Cartridge> Cpu<C> {
+    /// Build the instruction data of the given opcode about to run at the program counter.
+    pub(super) fn instruction_data(&self, info: &OpcodeInfo<C>) -> InstructionData {
+        let mode = info.addressing_mode;
+
+        let mut bytes = [0x00; 3];
+        for (offset, byte) in bytes.iter_mut().enumerate().take(mode.length() as usize) {
+            *byte = self.bus.peek(self.program_counter.wrapping_add(offset as u16));
+        }
+
+        let arg_1 = (mode.length() > 1).then_some(bytes[1]);
+        let arg_2 = (mode.length() > 2).then_some(bytes[2]);
+
+        let mut idle_cycles = info.cycles - 1;
+
+        if mode == AddressingMode::Relative {
+            if self.branch_taken() {
+                idle_cycles += 1;
+
+                if self.operand_crosses_page(mode) {
+                    idle_cycles += 1;
+                }
+            }
+        } else if info.has_page_cross_penalty() && self.operand_crosses_page(mode) {
+            idle_cycles += 1;
+        }
+
+        let mut instruction_data = InstructionData {
+            assembly: String::new(),
+            mnemonic: info.mnemonic,
+            is_official: info.official,
+            idle_cycles,
+            arg_1,
+            arg_2,
+            addressing_mode: mode,
+            length: mode.length(),
+            bytes,
+        };
+
+        // The assembly is built from the structured fields, so both always agree
+        if self.trace {
+            instruction_data.assembly = self.operand_assembly(
+                instruction_data.mnemonic,
+                instruction_data.is_official,
+                instruction_data.addressing_mode,
+            );
+        }
+
+        instruction_data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+    use crate::cpu::CpuStatusFlags;
+
+    #[test]
+    fn test_declared_cycles_match_implementation() {
+        for (opcode, info) in OPCODES.iter().enumerate() {
+            let Some(info) = info.filter(|info| !info.is_jam()) else {
+                continue;
+            };
+
+            // The operand is $0400, in the RAM, and the index registers are zero so no page is crossed
+            let cartridge = FlatCartridge::new(vec![opcode as u8, 0x00, 0x04]);
+
+            let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+
+            // Never take the branches
+            if info.addressing_mode == AddressingMode::Relative {
+                cpu.status = match opcode & 0x20 {
+                    0 => CpuStatusFlags::all(),
+                    _ => CpuStatusFlags::empty(),
+                };
+            }
+
+            let instruction_data = cpu.run_until_next_snapshot().instruction_data;
+
+            let mut cycles = 1;
+            while cpu.cycle_with_snapshot().unwrap().is_none() {
+                cycles += 1;
+            }
+
+            assert_eq!(cycles, info.cycles, "the opcode {opcode:02X} takes a different number of cycles");
+            assert_eq!(instruction_data.idle_cycles + 1, info.cycles, "the opcode {opcode:02X} reports different cycles");
+            assert_eq!(instruction_data.length, info.addressing_mode.length());
+        }
+    }
+
+    #[test]
+    fn test_jam_cycles_fail() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(vec![0x02]))).unwrap();
+        cpu.current_opcode = 0x02;
+
+        let info = OPCODES[0x02].unwrap();
+        assert!(matches!(
+            (info.handler)(&mut cpu, info.addressing_mode),
+            Err(CycleError::Jammed { opcode: 0x02, .. })
+        ));
+    }
+
+    #[test]
+    fn test_instruction_data_structured_fields() {
+        for (bytes, mnemonic, is_official, addressing_mode, assembly) in [
+            (vec![0xA9, 0x42], Mnemonic::Lda, true, AddressingMode::Immediate, "LDA #$42"),
+            (vec![0x0A], Mnemonic::Asl, true, AddressingMode::Accumulator, "ASL A"),
+            (vec![0xD0, 0x10], Mnemonic::Bne, true, AddressingMode::Relative, "BNE $8012"),
+            (vec![0x6C, 0x00, 0x02], Mnemonic::Jmp, true, AddressingMode::Indirect, "JMP ($0200) = 0000"),
+            (vec![0x07, 0x10], Mnemonic::Slo, false, AddressingMode::ZeroPage, "*SLO $10 = 00"),
+            (vec![0xEB, 0x42], Mnemonic::Sbc, false, AddressingMode::Immediate, "*SBC #$42"),
+        ] {
+            let mut cpu = Cpu::new(Box::new(FlatCartridge::new(bytes))).unwrap();
+
+            let instruction_data = cpu.run_full_instruction();
+            assert_eq!(instruction_data.mnemonic, mnemonic);
+            assert_eq!(instruction_data.is_official, is_official);
+            assert_eq!(instruction_data.addressing_mode, addressing_mode);
+            assert_eq!(instruction_data.assembly, assembly);
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Holds the shortcut to run raw snippets of 6502 code, for the experiments and the examples of the
+//! documentation.
+
+use crate::cartridge::testing::{FlatCartridge, DEFAULT_BASE_ADDRESS};
+use crate::cpu::{Cpu, CpuBuilder};
+
+impl Cpu<FlatCartridge> {
+    /// Create a new [Cpu] ready to run the given program, loaded from `$8000` by a
+    /// [FlatCartridge].
+    ///
+    /// ```
+    /// use tinfo::cpu::Cpu;
+    ///
+    /// // LDX #$10, TXA
+    /// let mut cpu = Cpu::with_program(&[0xA2, 0x10, 0x8A]);
+    /// assert_eq!(cpu.program_counter(), 0x8000);
+    ///
+    /// cpu.step_n(2).unwrap();
+    /// assert_eq!(cpu.accumulator(), 0x10);
+    /// ```
+    pub fn with_program(program: &[u8]) -> Cpu<FlatCartridge> {
+        Cpu::with_program_at(program, DEFAULT_BASE_ADDRESS)
+    }
+
+    /// Create a new [Cpu] ready to run the given program, loaded from the given address with the
+    /// reset vector pointing to it.
+    ///
+    /// The program is written through the bus, so it can be loaded to the internal RAM, like the
+    /// zero page, as well as to the [FlatCartridge] from `$4020`.
+    ///
+    /// # Panics
+    /// If the bus refuses to write the program, as it does for some of the registers of the
+    /// console.
+    pub fn with_program_at(program: &[u8], address: u16) -> Cpu<FlatCartridge> {
+        CpuBuilder::new()
+            .cartridge(FlatCartridge::new(vec![]).with_reset_vector(address))
+            .ram(address, program)
+            .build()
+            .expect("The program can't be written to the given address")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::RESET_VECTOR_ADDRESS;
+
+    #[test]
+    fn test_with_program() {
+        // LDA #$05, TAY, ADC #$03
+        let mut cpu = Cpu::with_program(&[0xA9, 0x05, 0xA8, 0x69, 0x03]);
+
+        let snapshots = cpu.step_n(3).unwrap();
+        let addresses: Vec<u16> = snapshots.iter().map(|snapshot| snapshot.program_counter).collect();
+        assert_eq!(addresses, [0x8000, 0x8002, 0x8003]);
+
+        assert_eq!(cpu.accumulator, 0x08);
+        assert_eq!(cpu.register_y, 0x05);
+        assert_eq!(cpu.program_counter, 0x8005);
+    }
+
+    #[test]
+    fn test_with_program_reset_vector() {
+        let cpu = Cpu::with_program(&[0xEA]);
+
+        assert_eq!(cpu.bus.peek(RESET_VECTOR_ADDRESS), 0x00);
+        assert_eq!(cpu.bus.peek(RESET_VECTOR_ADDRESS + 1), 0x80);
+    }
+
+    #[test]
+    fn test_with_program_in_zero_page() {
+        // LDA $0003, with the operand read from the program itself
+        let mut cpu = Cpu::with_program_at(&[0xAD, 0x03, 0x00, 0x42], 0x0000);
+        assert_eq!(cpu.program_counter, 0x0000);
+
+        cpu.step().unwrap();
+        assert_eq!(cpu.accumulator, 0x42);
+    }
+}
@@ -0,0 +1,183 @@
+//! Holds the implementation of the `ROL` and `ROR` instructions.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::addressing::AddressingMode;
+use crate::cpu::Cpu;
+use crate::cpu::CpuStatusFlags;
+use crate::cpu::CycleError;
+
+impl<C: Cartridge> Cpu<C> {
+    /// Rotate the given value one bit to the left through the carry flag, setting the zero and
+    /// negative flags from the result.
+    pub(super) fn rotate_left(&mut self, value: u8) -> u8 {
+        let result = (value << 1) | self.status.contains(CpuStatusFlags::Carry) as u8;
+
+        self.status.set(CpuStatusFlags::Carry, value & 0x80 != 0);
+        self.set_signedness(result);
+
+        result
+    }
+
+    /// Rotate the given value one bit to the right through the carry flag, setting the zero and
+    /// negative flags from the result.
+    pub(super) fn rotate_right(&mut self, value: u8) -> u8 {
+        let result = (value >> 1) | ((self.status.contains(CpuStatusFlags::Carry) as u8) << 7);
+
+        self.status.set(CpuStatusFlags::Carry, value & 0x01 != 0);
+        self.set_signedness(result);
+
+        result
+    }
+
+    /// Implements the accumulator rotate left instruction cycles.
+    pub(super) fn rotate_left_accumulator_cycles(&mut self) -> Result<bool, CycleError> {
+        self.accumulator_modify_cycles(Cpu::rotate_left)
+    }
+
+    /// Implements the rotate left instruction cycles for the given addressing mode.
+    pub(super) fn rotate_left_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        self.read_modify_write_cycles(mode, Cpu::rotate_left)
+    }
+
+    /// Implements the accumulator rotate right instruction cycles.
+    pub(super) fn rotate_right_accumulator_cycles(&mut self) -> Result<bool, CycleError> {
+        self.accumulator_modify_cycles(Cpu::rotate_right)
+    }
+
+    /// Implements the rotate right instruction cycles for the given addressing mode.
+    pub(super) fn rotate_right_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        self.read_modify_write_cycles(mode, Cpu::rotate_right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+
+    fn rotate_accumulator(opcode: u8, accumulator: u8, status: CpuStatusFlags) -> Cpu {
+        let cartridge = FlatCartridge::new(vec![
+            opcode,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = accumulator;
+        cpu.status = status;
+
+        let mnemonic = if opcode == 0x2A { "ROL" } else { "ROR" };
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, format!("{mnemonic} A"));
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 2);
+        assert_eq!(cpu.program_counter, 0x8001);
+
+        cpu
+    }
+
+    #[test]
+    fn test_rol_accumulator_carry_clear() {
+        let cpu = rotate_accumulator(0x2A, 0x80, CpuStatusFlags::empty());
+
+        assert_eq!(cpu.accumulator, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_rol_accumulator_carry_set() {
+        let cpu = rotate_accumulator(0x2A, 0x40, CpuStatusFlags::Carry);
+
+        // The carry flows into bit 0
+        assert_eq!(cpu.accumulator, 0x81);
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_ror_accumulator_carry_clear() {
+        let cpu = rotate_accumulator(0x6A, 0x01, CpuStatusFlags::empty());
+
+        assert_eq!(cpu.accumulator, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_ror_accumulator_carry_set() {
+        let cpu = rotate_accumulator(0x6A, 0x02, CpuStatusFlags::Carry);
+
+        // The carry flows into bit 7
+        assert_eq!(cpu.accumulator, 0x81);
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_rol_zero_page_x() {
+        let cartridge = FlatCartridge::new(vec![
+            // ROL $10,X
+            0x36, 0x10,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.status = CpuStatusFlags::Carry;
+        cpu.register_x = 0x01;
+        cpu.bus.write(0x0011, 0xC0).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "ROL $10,X @ 11 = C0");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 6);
+
+        assert_eq!(cpu.bus.read(0x0011).unwrap(), 0x81);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_ror_absolute() {
+        let cartridge = FlatCartridge::new(vec![
+            // ROR $0180
+            0x6E, 0x80, 0x01,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.status = CpuStatusFlags::Carry;
+        cpu.bus.write(0x0180, 0x03).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "ROR $0180 = 03");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 6);
+        assert_eq!(cpu.program_counter, 0x8003);
+
+        assert_eq!(cpu.bus.read(0x0180).unwrap(), 0x81);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_ror_absolute_x() {
+        let cartridge = FlatCartridge::new(vec![
+            // ROR $0100,X
+            0x7E, 0x00, 0x01,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_x = 0x80;
+        cpu.bus.write(0x0180, 0x80).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "ROR $0100,X @ 0180 = 80");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 7);
+
+        assert_eq!(cpu.bus.read(0x0180).unwrap(), 0x40);
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+    }
+}
@@ -0,0 +1,306 @@
+//! Holds the save states, snapshots of the whole machine that can be restored later.
+
+use crate::bus::BusState;
+use crate::cartridge::Cartridge;
+use crate::cpu::cache::InstructionCache;
+use crate::cpu::dma::OamDma;
+use crate::cpu::opcode::OpcodeInfo;
+use crate::cpu::{Cpu, CpuError, CpuStatusFlags, CycleError, Mnemonic};
+
+/// The instruction the CPU is running, as the metadata of the opcodes can't be saved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+enum SavedInstruction {
+    /// An instruction fetched from the bus.
+    Opcode(u8),
+
+    /// The sequence servicing a Non Maskable Interrupt.
+    Nmi,
+
+    /// The sequence servicing an Interrupt Request.
+    Irq,
+}
+
+/// The state of the whole machine at some point, given by [Cpu::save_state] to be restored with
+/// [Cpu::load_state].
+///
+/// The instruction in flight is part of the state, so the machine can be saved at any cycle. The
+/// ROM of the cartridge and the debugging tools, like the tracing or the watchpoints, are not.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SaveState {
+    /// The accumulator register.
+    accumulator: u8,
+
+    /// The X index register.
+    register_x: u8,
+
+    /// The Y index register.
+    register_y: u8,
+
+    /// The status register.
+    status: CpuStatusFlags,
+
+    /// The stack pointer register.
+    stack_pointer: u8,
+
+    /// The program counter register.
+    program_counter: u16,
+
+    /// The opcode of the instruction that is being run.
+    current_opcode: u8,
+
+    /// The instruction that is being run.
+    current_instruction: SavedInstruction,
+
+    /// The address the instruction that is being run was fetched from.
+    current_instruction_address: u16,
+
+    /// The cycle of the current instruction that is going to be run next.
+    current_instruction_cycle: u8,
+
+    /// The bytes cached by the previous cycles of the current instruction.
+    cache: InstructionCache,
+
+    /// The number of cycles the CPU has already executed.
+    cpu_cycles: u64,
+
+    /// The opcode and its address if the CPU has been jammed.
+    jammed: Option<(u8, u16)>,
+
+    /// If an NMI is waiting to be serviced.
+    nmi_pending: bool,
+
+    /// If the IRQ line is asserted.
+    irq_line: bool,
+
+    /// The OAM DMA stalling the CPU, if any.
+    oam_dma: Option<OamDma>,
+
+    /// The state of the bus, including the RAM and the cartridge.
+    bus: BusState,
+}
+
+impl SaveState {
+    /// Get the number of cycles the CPU had run when the state was saved.
+    pub fn cycles(&self) -> u64 {
+        self.cpu_cycles
+    }
+}
+
+impl<C: Cartridge> Cpu<C> {
+    /// Save the state of the whole machine, to be restored later with [Cpu::load_state].
+    pub fn save_state(&self) -> SaveState {
+        let current_instruction = match self.current_instruction.mnemonic {
+            Mnemonic::Nmi => SavedInstruction::Nmi,
+            Mnemonic::Irq => SavedInstruction::Irq,
+            _ => SavedInstruction::Opcode(self.current_opcode),
+        };
+
+        SaveState {
+            accumulator: self.accumulator,
+            register_x: self.register_x,
+            register_y: self.register_y,
+            status: self.status,
+            stack_pointer: self.stack_pointer,
+            program_counter: self.program_counter,
+            current_opcode: self.current_opcode,
+            current_instruction,
+            current_instruction_address: self.current_instruction_address,
+            current_instruction_cycle: self.current_instruction_cycle,
+            cache: self.cache,
+            cpu_cycles: self.cpu_cycles,
+            jammed: self.jammed,
+            nmi_pending: self.nmi_pending,
+            irq_line: self.irq_line,
+            oam_dma: self.oam_dma,
+            bus: self.bus.save_state(),
+        }
+    }
+
+    /// Restore a state given by [Cpu::save_state], which must come from a machine with the same
+    /// cartridge.
+    ///
+    /// The machine is left untouched if the state can't be restored. The debugging tools keep
+    /// their settings and the events already raised, but the shadow call stack is emptied as its
+    /// calls belong to another timeline.
+    pub fn load_state(&mut self, state: &SaveState) -> Result<(), CpuError> {
+        let current_instruction: OpcodeInfo<C> = match state.current_instruction {
+            SavedInstruction::Nmi => Self::NMI,
+            SavedInstruction::Irq => Self::IRQ,
+            SavedInstruction::Opcode(opcode) => Self::OPCODES[opcode as usize].ok_or(CycleError::UnsupportedOpcode {
+                opcode,
+                program_counter: state.current_instruction_address,
+            })?,
+        };
+
+        // Only a jammed CPU can be halted on a JAM opcode, which has no cycle to run
+        if current_instruction.is_jam() && state.jammed.is_none() {
+            return Err(CpuError::InstructionError(CycleError::Jammed {
+                opcode: state.current_opcode,
+                program_counter: state.current_instruction_address,
+            }));
+        }
+
+        self.bus.load_state(&state.bus)?;
+
+        self.accumulator = state.accumulator;
+        self.register_x = state.register_x;
+        self.register_y = state.register_y;
+        self.status = state.status;
+        self.stack_pointer = state.stack_pointer;
+        self.program_counter = state.program_counter;
+        self.current_opcode = state.current_opcode;
+        self.current_instruction = current_instruction;
+        self.current_instruction_address = state.current_instruction_address;
+        self.current_instruction_cycle = state.current_instruction_cycle;
+        self.cache = state.cache;
+        self.cpu_cycles = state.cpu_cycles;
+        self.jammed = state.jammed;
+        self.nmi_pending = state.nmi_pending;
+        self.irq_line = state.irq_line;
+        self.oam_dma = state.oam_dma;
+
+        if let Some(call_stack) = &mut self.call_stack {
+            call_stack.clear();
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<C: Cartridge> serde::Serialize for Cpu<C> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.save_state().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for CpuStatusFlags {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.bits())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CpuStatusFlags {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u8::deserialize(deserializer).map(CpuStatusFlags::from_bits_retain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+
+    #[test]
+    fn test_save_state_round_trip() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+
+        // Save in the middle of an instruction
+        for _ in 0..1001 {
+            cpu.cycle().unwrap();
+        }
+
+        let state = cpu.save_state();
+        assert_eq!(state.cycles(), cpu.cycles());
+
+        for _ in 0..500 {
+            cpu.cycle().unwrap();
+        }
+
+        let original_timeline = machine_state(&cpu);
+
+        cpu.load_state(&state).unwrap();
+
+        for _ in 0..500 {
+            cpu.cycle().unwrap();
+        }
+
+        assert_eq!(machine_state(&cpu), original_timeline);
+    }
+
+    #[test]
+    fn test_save_state_interrupt_in_flight() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+        cpu.batch_run_full_instruction(3);
+
+        cpu.raise_nmi();
+        cpu.cycle().unwrap();
+        cpu.cycle().unwrap();
+
+        let state = cpu.save_state();
+        let mut restored_cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+        restored_cpu.load_state(&state).unwrap();
+
+        for _ in 0..100 {
+            cpu.cycle().unwrap();
+            restored_cpu.cycle().unwrap();
+        }
+
+        assert_eq!(machine_state(&restored_cpu), machine_state(&cpu));
+    }
+
+    #[test]
+    fn test_load_state_unsupported_opcode() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+
+        let mut state = cpu.save_state();
+        state.accumulator = 0x42;
+        state.current_instruction = SavedInstruction::Opcode(0xBB);
+
+        assert!(matches!(
+            cpu.load_state(&state),
+            Err(CpuError::InstructionError(CycleError::UnsupportedOpcode { opcode: 0xBB, .. }))
+        ));
+
+        // The machine is left untouched
+        assert_eq!(cpu.accumulator, 0x00);
+    }
+
+    #[test]
+    fn test_load_state_jam_opcode() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+
+        let mut state = cpu.save_state();
+        state.current_opcode = 0x02;
+        state.current_instruction = SavedInstruction::Opcode(0x02);
+        state.current_instruction_cycle = 2;
+
+        assert!(matches!(
+            cpu.load_state(&state),
+            Err(CpuError::InstructionError(CycleError::Jammed { opcode: 0x02, .. }))
+        ));
+
+        // The state of a jammed CPU is fine, which keeps failing without running the opcode
+        state.jammed = Some((0x02, state.current_instruction_address));
+        cpu.load_state(&state).unwrap();
+        assert!(matches!(cpu.cycle(), Err(CpuError::Jammed { opcode: 0x02, .. })));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_save_state_serialization() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+
+        for _ in 0..1001 {
+            cpu.cycle().unwrap();
+        }
+
+        let serialized_cpu = bincode::serialize(&cpu).unwrap();
+        let state: SaveState = bincode::deserialize(&serialized_cpu).unwrap();
+        assert_eq!(state, cpu.save_state());
+
+        let mut restored_cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+        restored_cpu.load_state(&state).unwrap();
+
+        for _ in 0..500 {
+            cpu.cycle().unwrap();
+            restored_cpu.cycle().unwrap();
+        }
+
+        assert_eq!(machine_state(&restored_cpu), machine_state(&cpu));
+    }
+}
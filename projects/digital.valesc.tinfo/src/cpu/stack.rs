@@ -0,0 +1,255 @@
+//! Holds the implementation of the `PHA`, `PLA`, `PHP` and `PLP` instructions.
+
+use crate::cpu::Cpu;
+use crate::cpu::CycleError;
+use crate::cpu::impl_instruction_cycles;
+use crate::cpu::STACK_ADDRESS;
+
+impl_instruction_cycles!(
+    /// Implements the implied push accumulator instruction cycles.
+    cpu, push_accumulator_implied_cycles,
+
+    2, false => {
+        // Dummy read
+        let _ = cpu.read_program_counter()?;
+    },
+
+    3, true => {
+        cpu.stack_push(cpu.accumulator)?;
+    },
+);
+
+impl_instruction_cycles!(
+    /// Implements the implied pull accumulator instruction cycles.
+    cpu, pull_accumulator_implied_cycles,
+
+    2, false => {
+        // Dummy read
+        let _ = cpu.read_program_counter()?;
+    },
+
+    3, false => {
+        // Dummy read of the stack while the stack pointer is incremented
+        let _ = cpu.bus.read(STACK_ADDRESS + cpu.stack_pointer as u16)?;
+    },
+
+    4, true => {
+        cpu.accumulator = cpu.stack_pull()?;
+        cpu.set_signedness(cpu.accumulator);
+    },
+);
+
+impl_instruction_cycles!(
+    /// Implements the implied push processor status instruction cycles.
+    cpu, push_processor_status_implied_cycles,
+
+    2, false => {
+        // Dummy read
+        let _ = cpu.read_program_counter()?;
+    },
+
+    3, true => {
+        cpu.push_status(true)?;
+    },
+);
+
+impl_instruction_cycles!(
+    /// Implements the implied pull processor status instruction cycles.
+    cpu, pull_processor_status_implied_cycles,
+
+    2, false => {
+        // Dummy read
+        let _ = cpu.read_program_counter()?;
+    },
+
+    3, false => {
+        // Dummy read of the stack while the stack pointer is incremented
+        let _ = cpu.bus.read(STACK_ADDRESS + cpu.stack_pointer as u16)?;
+    },
+
+    4, true => {
+        cpu.pull_status()?;
+    },
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+    use crate::cpu::CpuBuilder;
+    use crate::cpu::CpuStatusFlags;
+
+    #[test]
+    fn test_pha_pla_round_trip() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDA #$80
+            0xA9, 0x80,
+
+            // PHA
+            0x48,
+
+            // LDA #$00
+            0xA9, 0x00,
+
+            // PHA
+            0x48,
+
+            // LDA #$33
+            0xA9, 0x33,
+
+            // PLA
+            0x68,
+
+            // PLA
+            0x68,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        let stack_pointer = cpu.stack_pointer;
+
+        cpu.run_full_instruction();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "PHA");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 3);
+        assert_eq!(cpu.bus.read(0x01FD).unwrap(), 0x80);
+
+        cpu.batch_run_full_instruction(3);
+        assert_eq!(cpu.bus.read(0x01FC).unwrap(), 0x00);
+        assert_eq!(cpu.stack_pointer, stack_pointer - 2);
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "PLA");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.accumulator, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+
+        cpu.run_full_instruction();
+        assert_eq!(cpu.accumulator, 0x80);
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+
+        assert_eq!(cpu.stack_pointer, stack_pointer);
+    }
+
+    #[test]
+    fn test_pla_stack_pointer_wraparound() {
+        let cartridge = FlatCartridge::new(vec![
+            // PLA
+            0x68,
+        ]);
+
+        let mut cpu = CpuBuilder::new()
+            .cartridge(cartridge)
+            .stack_pointer(0xFF)
+            .ram(0x0100, &[0x7F])
+            .build()
+            .unwrap();
+
+        cpu.run_full_instruction();
+
+        assert_eq!(cpu.stack_pointer(), 0x00);
+        assert_eq!(cpu.accumulator, 0x7F);
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_pha_stack_pointer_wraparound() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDA #$7F
+            0xA9, 0x7F,
+
+            // PHA
+            0x48,
+        ]);
+
+        let mut cpu = CpuBuilder::new().cartridge(cartridge).stack_pointer(0x00).build().unwrap();
+
+        cpu.batch_run_full_instruction(2);
+
+        assert_eq!(cpu.stack_pointer(), 0xFF);
+        assert_eq!(cpu.bus.read(0x0100).unwrap(), 0x7F);
+        assert_eq!(cpu.bus.read(0x0200).unwrap(), 0x00);
+    }
+
+    #[test]
+    fn test_php_plp_round_trip() {
+        let cartridge = FlatCartridge::new(vec![
+            // PHP
+            0x08,
+
+            // PLP
+            0x28,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.status = CpuStatusFlags::all() - CpuStatusFlags::B - CpuStatusFlags::Stub;
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "PHP");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 3);
+
+        // The B and stub flags are set on the pushed copy
+        assert_eq!(cpu.bus.read(0x01FD).unwrap(), 0xFF);
+
+        cpu.status = CpuStatusFlags::empty();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "PLP");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+
+        // The pulled B flag is ignored
+        assert_eq!(cpu.status, CpuStatusFlags::all() - CpuStatusFlags::B);
+        assert_eq!(cpu.stack_pointer, 0xFD);
+    }
+
+    #[test]
+    fn test_php_plp_preserve_decimal_flag() {
+        let cartridge = FlatCartridge::new(vec![
+            // SED
+            0xF8,
+
+            // PHP
+            0x08,
+
+            // CLD
+            0xD8,
+
+            // PLP
+            0x28,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.batch_run_full_instruction(3);
+
+        assert!(!cpu.status.contains(CpuStatusFlags::Decimal));
+        assert_ne!(cpu.bus.read(0x01FD).unwrap() & CpuStatusFlags::Decimal.bits(), 0);
+
+        cpu.run_full_instruction();
+        assert!(cpu.status.contains(CpuStatusFlags::Decimal));
+    }
+
+    #[test]
+    fn test_plp_ignores_b_and_forces_stub() {
+        let cartridge = FlatCartridge::new(vec![
+            // PLP
+            0x28,
+        ]);
+
+        let mut cpu = CpuBuilder::new()
+            .cartridge(cartridge)
+            .stack_pointer(0xFC)
+            .ram(0x01FD, &[(CpuStatusFlags::Carry | CpuStatusFlags::B).bits()])
+            .build()
+            .unwrap();
+
+        cpu.run_full_instruction();
+
+        assert_eq!(cpu.status, CpuStatusFlags::Carry | CpuStatusFlags::Stub);
+    }
+}
@@ -0,0 +1,266 @@
+//! Holds the implementation of the `STA` instruction.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::AddressingMode;
+use crate::cpu::Cpu;
+use crate::cpu::CycleError;
+
+impl<C: Cartridge> Cpu<C> {
+    /// Implements the store accumulator instruction cycles for the given addressing mode.
+    pub(super) fn store_accumulator_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        self.write_operand_cycles(mode, self.accumulator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bus::BusError;
+    use crate::cartridge::nrom::Nrom;
+    use crate::cartridge::Mirroring;
+    use crate::cpu::tests::*;
+    use crate::cpu::CpuError;
+    use crate::rom::ines::InesFile;
+
+    #[test]
+    fn test_sta_zero_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDA #$5C
+            0xA9, 0x5C,
+
+            // STA $EE
+            0x85, 0xEE,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x00EE, 0xAB).unwrap();
+
+        cpu.run_full_instruction();
+        let cpu_cycles = cpu.cpu_cycles;
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "STA $EE = AB");
+        assert_eq!(instruction_data.idle_cycles, 2);
+
+        assert_eq!(cpu.program_counter, 0x8003);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8004);
+        assert_eq!(cpu.bus.read(0x00EE).unwrap(), 0xAB);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8004);
+        assert_eq!(cpu.bus.read(0x00EE).unwrap(), 0x5C);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 3);
+    }
+
+    #[test]
+    fn test_sta_absolute() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDA #$7F
+            0xA9, 0x7F,
+
+            // STA $0647
+            0x8D, 0x47, 0x06,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+
+        cpu.run_full_instruction();
+        let cpu_cycles = cpu.cpu_cycles;
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "STA $0647 = 00");
+        assert_eq!(instruction_data.idle_cycles, 3);
+
+        assert_eq!(cpu.program_counter, 0x8003);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8004);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8005);
+        assert_eq!(cpu.bus.read(0x0647).unwrap(), 0x00);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8005);
+        assert_eq!(cpu.bus.read(0x0647).unwrap(), 0x7F);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+    }
+
+    #[test]
+    fn test_sta_absolute_on_read_only_cartridge_region() {
+        let mut prg_rom = vec![0xEA; 16 * 1024];
+        // STA $8000
+        prg_rom[..3].copy_from_slice(&[0x8D, 0x00, 0x80]);
+        // The reset vector points to $8000
+        prg_rom[0x3FFC..0x3FFE].copy_from_slice(&[0x00, 0x80]);
+
+        let rom = InesFile {
+            prg_rom,
+            prg_rom_size: 16 * 1024,
+            chr_rom: vec![],
+        };
+
+        let mut cpu = Cpu::new(Box::new(Nrom::new(false, Mirroring::Horizontal, rom))).unwrap();
+
+        cpu.cycle().unwrap();
+        cpu.cycle().unwrap();
+        cpu.cycle().unwrap();
+
+        assert!(matches!(
+            cpu.cycle(),
+            Err(CpuError::CycleFailed {
+                program_counter: 0x8000,
+                opcode: 0x8D,
+                cycle: 4,
+                source: CycleError::BusError(BusError::CartridgeError(_)),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_sta_absolute_x_same_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // STA $0633,X
+            0x9D, 0x33, 0x06,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x44;
+        cpu.register_x = 0x01;
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "STA $0633,X @ 0634 = 00");
+        assert_eq!(instruction_data.idle_cycles, 4);
+
+        // Indexed stores never take the page cross shortcut
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 5);
+        assert_eq!(cpu.program_counter, 0x8003);
+        assert_eq!(cpu.bus.read(0x0634).unwrap(), 0x44);
+    }
+
+    #[test]
+    fn test_sta_absolute_x_page_cross() {
+        let cartridge = FlatCartridge::new(vec![
+            // STA $80F8,X
+            0x9D, 0xF8, 0x80,
+        ]);
+
+        let accesses = cartridge.accesses();
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x44;
+        cpu.register_x = 0x10;
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.idle_cycles, 4);
+
+        accesses.borrow_mut().clear();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        for _ in 0..instruction_data.idle_cycles {
+            cpu.cycle().unwrap();
+        }
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(
+            *accesses.borrow(),
+            vec![
+                FlatAccess::Read(0x8001),
+                FlatAccess::Read(0x8002),
+                // Dummy read with the upper byte of the address unfixed
+                FlatAccess::Read(0x8008),
+                FlatAccess::Write(0x8108, 0x44),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sta_absolute_y_page_cross() {
+        let cartridge = FlatCartridge::new(vec![
+            // STA $01FF,Y
+            0x99, 0xFF, 0x01,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x87;
+        cpu.register_y = 0x02;
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "STA $01FF,Y @ 0201 = 00");
+        assert_eq!(instruction_data.idle_cycles, 4);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 5);
+        assert_eq!(cpu.program_counter, 0x8003);
+        assert_eq!(cpu.bus.read(0x0201).unwrap(), 0x87);
+        assert_eq!(cpu.bus.read(0x0101).unwrap(), 0x00);
+    }
+
+    #[test]
+    fn test_sta_indirect_y_same_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // STA ($33),Y
+            0x91, 0x33,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x7F;
+        cpu.register_y = 0x00;
+        cpu.bus.write(0x0033, 0x00).unwrap();
+        cpu.bus.write(0x0034, 0x04).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "STA ($33),Y = 0400 @ 0400 = 00");
+        assert_eq!(instruction_data.idle_cycles, 5);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 6);
+        assert_eq!(cpu.program_counter, 0x8002);
+        assert_eq!(cpu.bus.read(0x0400).unwrap(), 0x7F);
+    }
+
+    #[test]
+    fn test_sta_indirect_y_page_cross() {
+        let cartridge = FlatCartridge::new(vec![
+            // STA ($FF),Y
+            0x91, 0xFF,
+        ]);
+
+        let accesses = cartridge.accesses();
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x7F;
+        cpu.register_y = 0x20;
+        // The pointer wraps around inside the zero page
+        cpu.bus.write(0x00FF, 0xF0).unwrap();
+        cpu.bus.write(0x0000, 0x80).unwrap();
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "STA ($FF),Y = 80F0 @ 8110 = EA");
+        assert_eq!(instruction_data.idle_cycles, 5);
+
+        accesses.borrow_mut().clear();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        for _ in 0..instruction_data.idle_cycles {
+            cpu.cycle().unwrap();
+        }
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 5);
+        assert_eq!(
+            *accesses.borrow(),
+            vec![
+                FlatAccess::Read(0x8001),
+                // Dummy read with the upper byte of the address unfixed
+                FlatAccess::Read(0x8010),
+                FlatAccess::Write(0x8110, 0x7F),
+            ]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Holds the implementation of the `STY` instruction.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::AddressingMode;
+use crate::cpu::Cpu;
+use crate::cpu::CycleError;
+
+impl<C: Cartridge> Cpu<C> {
+    /// Implements the store Y register instruction cycles for the given addressing mode.
+    pub(super) fn store_y_register_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        self.write_operand_cycles(mode, self.register_y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+
+    #[test]
+    fn test_sty_zero_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDY #$5C
+            0xA0, 0x5C,
+
+            // STY $EE
+            0x84, 0xEE,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x00EE, 0xAB).unwrap();
+
+        cpu.run_full_instruction();
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        assert_eq!(instruction_data.assembly, "STY $EE = AB");
+        assert_eq!(instruction_data.idle_cycles, 2);
+
+        assert_eq!(cpu.program_counter, 0x8003);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8004);
+
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.program_counter, 0x8004);
+        assert_eq!(cpu.bus.read(0x00EE).unwrap(), 0x5C);
+    }
+
+    #[test]
+    fn test_sty_zero_page_x() {
+        let cartridge = FlatCartridge::new(vec![
+            // STY $33,X
+            0x94, 0x33,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_y = 0x66;
+        cpu.register_x = 0x02;
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "STY $33,X @ 35 = 00");
+        assert_eq!(instruction_data.idle_cycles, 3);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.program_counter, 0x8002);
+        assert_eq!(cpu.bus.read(0x0035).unwrap(), 0x66);
+    }
+
+    #[test]
+    fn test_sty_zero_page_x_wraps_inside_zero_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // STY $85,X
+            0x94, 0x85,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_y = 0x99;
+        cpu.register_x = 0x80;
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "STY $85,X @ 05 = 00");
+
+        assert_eq!(cpu.bus.read(0x0005).unwrap(), 0x99);
+        assert_eq!(cpu.bus.read(0x0105).unwrap(), 0x00);
+    }
+
+    #[test]
+    fn test_sty_absolute() {
+        let cartridge = FlatCartridge::new(vec![
+            // STY $0647
+            0x8C, 0x47, 0x06,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_y = 0xAB;
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "STY $0647 = 00");
+        assert_eq!(instruction_data.idle_cycles, 3);
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+        assert_eq!(cpu.program_counter, 0x8003);
+        assert_eq!(cpu.bus.read(0x0647).unwrap(), 0xAB);
+    }
+}
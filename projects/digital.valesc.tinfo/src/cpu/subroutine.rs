@@ -1,46 +1,25 @@
-//! Holds the implementation of the `JSR` instruction.
+//! Holds the implementation of the `JSR` and `RTS` instructions.
 
-use crate::bus::BusError;
+use crate::cpu::CallKind;
 use crate::cpu::Cpu;
 use crate::cpu::CycleError;
 use crate::U16Ex;
 use crate::{build_address, cpu::impl_instruction_cycles};
-use crate::cpu::InstructionData;
-
-use super::STACK_ADDRESS;
-
-impl Cpu {
-    /// Implements the absolute jump instruction data.
-    pub(super) fn jump_to_subroutine_absolute_instruction(&mut self) -> Result<InstructionData, BusError> {
-        let arg_1 = self.bus.read(self.program_counter + 1)?;
-        let arg_2 = self.bus.read(self.program_counter + 2)?;
-        
-        let address = build_address(
-            arg_1,
-            arg_2
-        );
-
-        Ok(InstructionData {
-            arg_1: Some(arg_1),
-            arg_2: Some(arg_2),
-            assembly: format!("JSR ${address:02X}"),
-            idle_cycles: 5,
-        })
-    }
-}
+use crate::cpu::STACK_ADDRESS;
 
 impl_instruction_cycles!(
     /// Implements the absolute jump to subroutine instruction cycles.
     cpu, jump_to_subroutine_absolute_cycles,
 
     2, false => {
-        cpu.cache.push(cpu.read_program_counter()?);
-        cpu.program_counter += 1;
+        let program_counter_low = cpu.read_program_counter()?;
+        cpu.cache.push(program_counter_low);
+        cpu.advance_program_counter(1);
     },
 
     3, false => {
-        // Internal operation
-        let _ = cpu.bus.read(0x100)?;
+        // Internal operation, with a dummy read of the stack
+        let _ = cpu.bus.read(STACK_ADDRESS + cpu.stack_pointer as u16)?;
     },
 
     4, false => {
@@ -53,26 +32,65 @@ impl_instruction_cycles!(
 
     6, true => {
         let program_counter_high = cpu.read_program_counter()?;
+        let return_address = cpu.program_counter.wrapping_add(1);
 
         cpu.program_counter = build_address(cpu.cache[0], program_counter_high);
+        cpu.push_call_frame(CallKind::Subroutine, return_address, cpu.program_counter);
+    },
+);
+
+impl_instruction_cycles!(
+    /// Implements the implied return from subroutine instruction cycles.
+    cpu, return_from_subroutine_cycles,
+
+    2, false => {
+        // Dummy read
+        let _ = cpu.read_program_counter()?;
+    },
+
+    3, false => {
+        // Dummy read of the stack while the stack pointer is incremented
+        let _ = cpu.bus.read(STACK_ADDRESS + cpu.stack_pointer as u16)?;
+    },
+
+    4, false => {
+        let program_counter_low = cpu.stack_pull()?;
+        cpu.cache.push(program_counter_low);
+    },
+
+    5, false => {
+        let program_counter_high = cpu.stack_pull()?;
+
+        cpu.program_counter = build_address(cpu.cache[0], program_counter_high);
+    },
+
+    6, true => {
+        // JSR pushes the address of its last byte
+        let _ = cpu.read_program_counter()?;
+        cpu.advance_program_counter(1);
+        cpu.pop_call_frame(cpu.program_counter);
     },
 );
 
 #[cfg(test)]
 mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
     use super::*;
+    use crate::bus::{BusAccess, BusObserver};
     use crate::cpu::tests::*;
 
     #[test]
     fn test_jsr_immediate() {
-        let cartridge = MockCartridge::new(vec![
+        let cartridge = FlatCartridge::new(vec![
             // JSR $77EE
             0x20, 0xEE, 0x77
         ]);
 
-        let mut cpu = Cpu::new(Box::new(cartridge));
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
 
-        let instruction_data = cpu.cycle().unwrap().unwrap().instruction_data;
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
         assert_eq!(instruction_data.assembly, "JSR $77EE");
         assert_eq!(instruction_data.idle_cycles, 5);
 
@@ -95,4 +113,110 @@ mod tests {
         cpu.cycle().unwrap();
         assert_eq!(cpu.program_counter, 0x77EE);
     }
-}
\ No newline at end of file
+
+    /// Records every access to the bus.
+    struct RecordingObserver(Rc<RefCell<Vec<(BusAccess, u16, u8)>>>);
+
+    impl BusObserver for RecordingObserver {
+        fn on_read(&mut self, address: u16, value: u8) {
+            self.0.borrow_mut().push((BusAccess::Read, address, value));
+        }
+
+        fn on_write(&mut self, address: u16, value: u8) {
+            self.0.borrow_mut().push((BusAccess::Write, address, value));
+        }
+    }
+
+    #[test]
+    fn test_jsr_bus_accesses() {
+        let cartridge = FlatCartridge::new(vec![
+            // JSR $1234
+            0x20, 0x34, 0x12,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x01FD, 0x55).unwrap();
+
+        let accesses = Rc::new(RefCell::new(vec![]));
+        cpu.bus_mut().set_observer(Some(Box::new(RecordingObserver(accesses.clone()))));
+
+        let mut cycle_accesses = vec![];
+        for _ in 0..6 {
+            cpu.cycle().unwrap();
+            cycle_accesses.push(accesses.borrow_mut().drain(..).collect::<Vec<_>>());
+        }
+
+        // The snapshot and disassembly peeks are not reported
+        assert_eq!(cycle_accesses, vec![
+            // Fetch opcode, increment PC
+            vec![(BusAccess::Read, 0x8000, 0x20)],
+
+            // Fetch low address byte, increment PC
+            vec![(BusAccess::Read, 0x8001, 0x34)],
+
+            // Internal operation
+            vec![(BusAccess::Read, 0x01FD, 0x55)],
+
+            // Push PCH on stack, decrement S
+            vec![(BusAccess::Write, 0x01FD, 0x80)],
+
+            // Push PCL on stack, decrement S
+            vec![(BusAccess::Write, 0x01FC, 0x02)],
+
+            // Copy low address byte to PCL, fetch high address byte to PCH
+            vec![(BusAccess::Read, 0x8002, 0x12)],
+        ]);
+
+        assert_eq!(cpu.program_counter, 0x1234);
+
+        cpu.bus_mut().set_observer(None);
+        cpu.bus.write(0x0000, 0x00).unwrap();
+        assert!(accesses.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_jsr_low_address() {
+        let cartridge = FlatCartridge::new(vec![
+            // JSR $00EE
+            0x20, 0xEE, 0x00
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "JSR $00EE");
+        assert_eq!(cpu.program_counter, 0x00EE);
+    }
+
+    #[test]
+    fn test_jsr_rts() {
+        let cartridge = FlatCartridge::new(vec![
+            // JSR $8004
+            0x20, 0x04, 0x80,
+
+            // NOP
+            0xEA,
+
+            // NOP
+            0xEA,
+
+            // RTS
+            0x60,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        let stack_pointer = cpu.stack_pointer;
+
+        cpu.batch_run_full_instruction(2);
+        assert_eq!(cpu.program_counter, 0x8005);
+        assert_eq!(cpu.stack_pointer, stack_pointer - 2);
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "RTS");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 6);
+
+        assert_eq!(cpu.program_counter, 0x8003);
+        assert_eq!(cpu.stack_pointer, stack_pointer);
+    }
+}
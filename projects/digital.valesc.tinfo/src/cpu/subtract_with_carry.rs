@@ -0,0 +1,136 @@
+//! Holds the implementation of the `SBC` instruction.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::addressing::AddressingMode;
+use crate::cpu::Cpu;
+use crate::cpu::CycleError;
+
+impl<C: Cartridge> Cpu<C> {
+    /// Implements the subtract with carry instruction cycles for the given addressing mode.
+    ///
+    /// Subtracting is the same as adding the one's complement of the operand, with the carry flag
+    /// acting as an inverted borrow.
+    pub(super) fn subtract_with_carry_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        let Some(value) = self.read_operand_cycles(mode)? else {
+            return Ok(false);
+        };
+
+        self.add_with_carry(!value);
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+    use crate::cpu::CpuBuilder;
+    use crate::cpu::CpuStatusFlags;
+
+    fn subtract_with_carry_immediate(opcode: u8, accumulator: u8, value: u8, status: CpuStatusFlags) -> Cpu<FlatCartridge> {
+        let cartridge = FlatCartridge::new(vec![
+            // SBC #value
+            opcode, value,
+        ]);
+
+        let mut cpu = CpuBuilder::new()
+            .cartridge(cartridge)
+            .accumulator(accumulator)
+            .status(status)
+            .build()
+            .unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+
+        let mnemonic = if opcode == 0xEB { "*SBC" } else { "SBC" };
+        assert_eq!(instruction_data.assembly, format!("{mnemonic} #${value:02X}"));
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 2);
+        assert_eq!(cpu.program_counter, 0x8002);
+
+        cpu
+    }
+
+    #[test]
+    fn test_sbc_immediate_no_borrow() {
+        let cpu = subtract_with_carry_immediate(0xE9, 0x40, 0x3F, CpuStatusFlags::Carry);
+
+        assert_eq!(cpu.accumulator, 0x01);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Overflow));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_sbc_immediate_borrow() {
+        let cpu = subtract_with_carry_immediate(0xE9, 0x40, 0x40, CpuStatusFlags::empty());
+
+        // The clear carry flag borrows one from the result
+        assert_eq!(cpu.accumulator, 0xFF);
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Overflow));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_sbc_immediate_signed_overflow() {
+        let cpu = subtract_with_carry_immediate(0xE9, 0x80, 0x01, CpuStatusFlags::Carry);
+
+        assert_eq!(cpu.accumulator, 0x7F);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Overflow));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_sbc_immediate_ignores_decimal_flag() {
+        let cpu = subtract_with_carry_immediate(0xE9, 0x10, 0x01, CpuStatusFlags::Decimal | CpuStatusFlags::Carry);
+
+        // A binary-coded decimal subtraction would have given 0x09
+        assert_eq!(cpu.accumulator, 0x0F);
+        assert!(cpu.status.contains(CpuStatusFlags::Decimal));
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+    }
+
+    #[test]
+    fn test_sbc_immediate_unofficial_alias() {
+        for (accumulator, value, status) in [
+            (0x40, 0x40, CpuStatusFlags::Carry),
+            (0x40, 0x41, CpuStatusFlags::Carry),
+            (0x80, 0x7F, CpuStatusFlags::empty()),
+        ] {
+            let official = subtract_with_carry_immediate(0xE9, accumulator, value, status);
+            let unofficial = subtract_with_carry_immediate(0xEB, accumulator, value, status);
+
+            assert_eq!(official.accumulator, unofficial.accumulator);
+            assert_eq!(official.status, unofficial.status);
+        }
+    }
+
+    #[test]
+    fn test_sbc_absolute_x_page_cross() {
+        let cartridge = FlatCartridge::new(vec![
+            // SBC $01FF,X
+            0xFD, 0xFF, 0x01,
+        ]);
+
+        let mut cpu = CpuBuilder::new()
+            .cartridge(cartridge)
+            .accumulator(0x50)
+            .x(0x01)
+            .status(CpuStatusFlags::Carry)
+            .ram(0x0200, &[0x10])
+            .build()
+            .unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "SBC $01FF,X @ 0200 = 10");
+
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 5);
+        assert_eq!(cpu.program_counter, 0x8003);
+        assert_eq!(cpu.accumulator, 0x40);
+    }
+}
@@ -0,0 +1,215 @@
+//! Holds the implementation of the `TAX`, `TXA`, `TAY`, `TYA`, `TSX` and `TXS` instructions.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::Cpu;
+use crate::cpu::CycleError;
+
+/// The registers of the CPU values can be transferred between.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Register {
+    /// The accumulator register.
+    Accumulator,
+
+    /// The X index register.
+    X,
+
+    /// The Y index register.
+    Y,
+
+    /// The stack pointer register.
+    StackPointer,
+}
+
+impl<C: Cartridge> Cpu<C> {
+    /// Get the value of the given register.
+    fn register(&self, register: Register) -> u8 {
+        match register {
+            Register::Accumulator => self.accumulator,
+            Register::X => self.register_x,
+            Register::Y => self.register_y,
+            Register::StackPointer => self.stack_pointer,
+        }
+    }
+
+    /// Get a mutable reference to the given register.
+    fn register_mut(&mut self, register: Register) -> &mut u8 {
+        match register {
+            Register::Accumulator => &mut self.accumulator,
+            Register::X => &mut self.register_x,
+            Register::Y => &mut self.register_y,
+            Register::StackPointer => &mut self.stack_pointer,
+        }
+    }
+
+    /// Implements the implied transfer instruction cycles, copying the source register into the
+    /// destination register and setting the zero and negative flags from the transferred value.
+    ///
+    /// Transferring into the stack pointer leaves the flags untouched.
+    pub(super) fn transfer_cycles(&mut self, source: Register, destination: Register) -> Result<bool, CycleError> {
+        match self.current_instruction_cycle {
+            2 => {
+                // Dummy read
+                let _ = self.read_program_counter()?;
+
+                let value = self.register(source);
+                *self.register_mut(destination) = value;
+
+                if destination != Register::StackPointer {
+                    self.set_signedness(value);
+                }
+
+                Ok(true)
+            }
+
+            _ => Err(CycleError::InstructionCycleOutOfBounds),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+    use crate::cpu::CpuStatusFlags;
+
+    fn transfer_implied(opcode: u8, mnemonic: &str, setup: impl FnOnce(&mut Cpu)) -> Cpu {
+        let cartridge = FlatCartridge::new(vec![
+            opcode,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        setup(&mut cpu);
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, mnemonic);
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 2);
+        assert_eq!(cpu.program_counter, 0x8001);
+
+        cpu
+    }
+
+    #[test]
+    fn test_tax_negative() {
+        let cpu = transfer_implied(0xAA, "TAX", |cpu| cpu.accumulator = 0x80);
+
+        assert_eq!(cpu.register_x, 0x80);
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_txa_zero() {
+        let cpu = transfer_implied(0x8A, "TXA", |cpu| {
+            cpu.accumulator = 0x33;
+            cpu.register_x = 0x00;
+        });
+
+        assert_eq!(cpu.accumulator, 0x00);
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_tay_zero() {
+        let cpu = transfer_implied(0xA8, "TAY", |cpu| {
+            cpu.accumulator = 0x00;
+            cpu.register_y = 0x10;
+        });
+
+        assert_eq!(cpu.register_y, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_tya_negative() {
+        let cpu = transfer_implied(0x98, "TYA", |cpu| cpu.register_y = 0xF0);
+
+        assert_eq!(cpu.accumulator, 0xF0);
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_lda_tax_txa_chain() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDA #$42
+            0xA9, 0x42,
+
+            // TAX
+            0xAA,
+
+            // LDA #$00
+            0xA9, 0x00,
+
+            // TXA
+            0x8A,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.batch_run_full_instruction(3);
+
+        assert_eq!(cpu.register_x, 0x42);
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+
+        cpu.run_full_instruction();
+        assert_eq!(cpu.accumulator, 0x42);
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_tsx_flags() {
+        let cpu = transfer_implied(0xBA, "TSX", |cpu| cpu.stack_pointer = 0x00);
+
+        assert_eq!(cpu.register_x, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+
+        let cpu = transfer_implied(0xBA, "TSX", |cpu| cpu.stack_pointer = 0xFD);
+
+        assert_eq!(cpu.register_x, 0xFD);
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_txs_no_flags() {
+        let cpu = transfer_implied(0x9A, "TXS", |cpu| {
+            cpu.status = CpuStatusFlags::empty();
+            cpu.register_x = 0x00;
+        });
+
+        assert_eq!(cpu.stack_pointer, 0x00);
+        assert_eq!(cpu.status, CpuStatusFlags::empty());
+
+        let cpu = transfer_implied(0x9A, "TXS", |cpu| {
+            cpu.status = CpuStatusFlags::Zero;
+            cpu.register_x = 0x80;
+        });
+
+        assert_eq!(cpu.stack_pointer, 0x80);
+        assert_eq!(cpu.status, CpuStatusFlags::Zero);
+    }
+
+    #[test]
+    fn test_txs_jsr() {
+        let cartridge = FlatCartridge::new(vec![
+            // TXS
+            0x9A,
+
+            // JSR $9000
+            0x20, 0x00, 0x90,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.register_x = 0x40;
+
+        cpu.batch_run_full_instruction(2);
+
+        assert_eq!(cpu.program_counter, 0x9000);
+        assert_eq!(cpu.stack_pointer, 0x3E);
+        assert_eq!(cpu.bus.read(0x0140).unwrap(), 0x80);
+        assert_eq!(cpu.bus.read(0x013F).unwrap(), 0x03);
+    }
+}
@@ -0,0 +1,826 @@
+//! Holds the implementation of the unofficial instructions, also known as illegal opcodes.
+//!
+//! Their mnemonics are prefixed with `*`, like nestest does.
+
+use crate::cartridge::Cartridge;
+use crate::cpu::addressing::AddressingMode;
+use crate::cpu::Cpu;
+use crate::cpu::CpuStatusFlags;
+use crate::cpu::CycleError;
+
+impl<C: Cartridge> Cpu<C> {
+    /// Implements the load accumulator and X register instruction cycles for the given addressing mode.
+    pub(super) fn load_accumulator_x_register_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        let Some(value) = self.read_operand_cycles(mode)? else {
+            return Ok(false);
+        };
+
+        self.accumulator = value;
+        self.register_x = value;
+        self.set_signedness(value);
+
+        Ok(true)
+    }
+
+    /// Implements the store accumulator AND X register instruction cycles for the given addressing mode.
+    ///
+    /// No flags are modified.
+    pub(super) fn store_accumulator_and_x_register_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        self.write_operand_cycles(mode, self.accumulator & self.register_x)
+    }
+
+    /// Decrement the given value and compare the accumulator against the result.
+    fn decrement_compare(&mut self, value: u8) -> u8 {
+        let result = value.wrapping_sub(1);
+        self.compare(self.accumulator, result);
+
+        result
+    }
+
+    /// Implements the decrement and compare instruction cycles for the given addressing mode.
+    pub(super) fn decrement_compare_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        self.read_modify_write_cycles(mode, Cpu::decrement_compare)
+    }
+
+    /// Increment the given value and subtract the result from the accumulator.
+    fn increment_subtract(&mut self, value: u8) -> u8 {
+        let result = value.wrapping_add(1);
+        self.add_with_carry(!result);
+
+        result
+    }
+
+    /// Implements the increment and subtract with carry instruction cycles for the given addressing mode.
+    pub(super) fn increment_subtract_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        self.read_modify_write_cycles(mode, Cpu::increment_subtract)
+    }
+
+    /// Shift the given value one bit to the right, moving the old bit 0 into the carry flag and
+    /// setting the zero and negative flags from the result.
+    fn logical_shift_right(&mut self, value: u8) -> u8 {
+        let result = value >> 1;
+
+        self.status.set(CpuStatusFlags::Carry, value & 0x01 != 0);
+        self.set_signedness(result);
+
+        result
+    }
+
+    /// Shift the given value to the left and OR the result into the accumulator.
+    fn shift_left_or(&mut self, value: u8) -> u8 {
+        let result = self.arithmetic_shift_left(value);
+
+        self.accumulator |= result;
+        self.set_signedness(self.accumulator);
+
+        result
+    }
+
+    /// Rotate the given value to the left and AND the result into the accumulator.
+    fn rotate_left_and(&mut self, value: u8) -> u8 {
+        let result = self.rotate_left(value);
+
+        self.accumulator &= result;
+        self.set_signedness(self.accumulator);
+
+        result
+    }
+
+    /// Shift the given value to the right and exclusive OR the result into the accumulator.
+    fn shift_right_exclusive_or(&mut self, value: u8) -> u8 {
+        let result = self.logical_shift_right(value);
+
+        self.accumulator ^= result;
+        self.set_signedness(self.accumulator);
+
+        result
+    }
+
+    /// Rotate the given value to the right and add the result to the accumulator, using the
+    /// rotated out bit as the carry.
+    fn rotate_right_add(&mut self, value: u8) -> u8 {
+        let result = self.rotate_right(value);
+        self.add_with_carry(result);
+
+        result
+    }
+
+    /// Implements the shift left and OR instruction cycles for the given addressing mode.
+    pub(super) fn shift_left_or_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        self.read_modify_write_cycles(mode, Cpu::shift_left_or)
+    }
+
+    /// Implements the rotate left and AND instruction cycles for the given addressing mode.
+    pub(super) fn rotate_left_and_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        self.read_modify_write_cycles(mode, Cpu::rotate_left_and)
+    }
+
+    /// Implements the shift right and exclusive OR instruction cycles for the given addressing mode.
+    pub(super) fn shift_right_exclusive_or_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        self.read_modify_write_cycles(mode, Cpu::shift_right_exclusive_or)
+    }
+
+    /// Implements the rotate right and add with carry instruction cycles for the given addressing mode.
+    pub(super) fn rotate_right_add_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        self.read_modify_write_cycles(mode, Cpu::rotate_right_add)
+    }
+
+    /// AND the given value into the accumulator, copying the bit 7 of the result into the carry flag.
+    fn and_copy_carry(&mut self, value: u8) {
+        self.accumulator &= value;
+        self.set_signedness(self.accumulator);
+
+        self.status.set(CpuStatusFlags::Carry, self.accumulator & 0x80 != 0);
+    }
+
+    /// AND the given value into the accumulator and shift the result to the right.
+    fn and_shift_right(&mut self, value: u8) {
+        self.accumulator = self.logical_shift_right(self.accumulator & value);
+    }
+
+    /// AND the given value into the accumulator and rotate the result to the right through the carry flag.
+    ///
+    /// The carry flag is set from the bit 6 of the result and the overflow flag from the bit 6
+    /// exclusive OR the bit 5 of the result.
+    fn and_rotate_right(&mut self, value: u8) {
+        let carry = self.status.contains(CpuStatusFlags::Carry) as u8;
+        let result = ((self.accumulator & value) >> 1) | (carry << 7);
+
+        self.accumulator = result;
+        self.set_signedness(result);
+
+        self.status.set(CpuStatusFlags::Carry, result & 0x40 != 0);
+        self.status.set(CpuStatusFlags::Overflow, ((result >> 6) ^ (result >> 5)) & 0x01 != 0);
+    }
+
+    /// Set the X register to the accumulator AND the X register minus the given value, setting the
+    /// flags like a compare.
+    fn and_x_register_subtract(&mut self, value: u8) {
+        let and = self.accumulator & self.register_x;
+
+        self.compare(and, value);
+        self.register_x = and.wrapping_sub(value);
+    }
+
+    /// Run a cycle of an immediate instruction applying the given operation to the operand, returning
+    /// if the instruction ended.
+    fn immediate_operation_cycles(&mut self, operation: fn(&mut Self, u8)) -> Result<bool, CycleError> {
+        let Some(value) = self.read_operand_cycles(AddressingMode::Immediate)? else {
+            return Ok(false);
+        };
+
+        operation(self, value);
+
+        Ok(true)
+    }
+
+    /// Implements the immediate AND with carry instruction cycles.
+    pub(super) fn and_copy_carry_immediate_cycles(&mut self) -> Result<bool, CycleError> {
+        self.immediate_operation_cycles(Cpu::and_copy_carry)
+    }
+
+    /// Implements the immediate AND and shift right instruction cycles.
+    pub(super) fn and_shift_right_immediate_cycles(&mut self) -> Result<bool, CycleError> {
+        self.immediate_operation_cycles(Cpu::and_shift_right)
+    }
+
+    /// Implements the immediate AND and rotate right instruction cycles.
+    pub(super) fn and_rotate_right_immediate_cycles(&mut self) -> Result<bool, CycleError> {
+        self.immediate_operation_cycles(Cpu::and_rotate_right)
+    }
+
+    /// Implements the immediate AND X register and subtract instruction cycles.
+    pub(super) fn and_x_register_subtract_immediate_cycles(&mut self) -> Result<bool, CycleError> {
+        self.immediate_operation_cycles(Cpu::and_x_register_subtract)
+    }
+
+    /// Implements the unofficial no operation instruction cycles for the given addressing mode.
+    ///
+    /// The operand is still read through the bus, triggering the side effects of the read.
+    pub(super) fn no_operation_read_cycles(&mut self, mode: AddressingMode) -> Result<bool, CycleError> {
+        Ok(self.read_operand_cycles(mode)?.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+
+    #[test]
+    fn test_lax_zero_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // LAX $10
+            0xA7, 0x10,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x0010, 0x80).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*LAX $10 = 80");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 3);
+
+        assert_eq!(cpu.accumulator, 0x80);
+        assert_eq!(cpu.register_x, 0x80);
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_lax_zero_page_y_zero() {
+        let cartridge = FlatCartridge::new(vec![
+            // LAX $FF,Y
+            0xB7, 0xFF,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x11;
+        cpu.register_x = 0x22;
+        cpu.register_y = 0x02;
+        cpu.bus.write(0x0001, 0x00).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*LAX $FF,Y @ 01 = 00");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 4);
+
+        assert_eq!(cpu.accumulator, 0x00);
+        assert_eq!(cpu.register_x, 0x00);
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_lax_indirect_y_page_cross_penalty() {
+        let cartridge = FlatCartridge::new(vec![
+            // LAX ($33),Y
+            0xB3, 0x33,
+
+            // LAX ($33),Y
+            0xB3, 0x33,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.bus.write(0x0033, 0xFF).unwrap();
+        cpu.bus.write(0x0034, 0x03).unwrap();
+        cpu.bus.write(0x03FF, 0x12).unwrap();
+        cpu.bus.write(0x0400, 0x34).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*LAX ($33),Y = 03FF @ 03FF = 12");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 5);
+        assert_eq!(cpu.register_x, 0x12);
+
+        cpu.register_y = 0x01;
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*LAX ($33),Y = 03FF @ 0400 = 34");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 6);
+        assert_eq!(cpu.accumulator, 0x34);
+        assert_eq!(cpu.register_x, 0x34);
+    }
+
+    fn store_accumulator_and_x_register(cartridge: FlatCartridge, assembly: &str, cycles: u64, address: u16) {
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0b1100_1010;
+        cpu.register_x = 0b1010_0110;
+        cpu.register_y = 0x01;
+        cpu.status = CpuStatusFlags::Zero | CpuStatusFlags::Overflow;
+
+        // Pointer for the indexed indirect form
+        cpu.bus.write(0x0026, 0x80).unwrap();
+        cpu.bus.write(0x0027, 0x01).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, assembly);
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, cycles);
+
+        assert_eq!(cpu.bus.read(address).unwrap(), 0b1000_0010);
+        assert_eq!(cpu.status, CpuStatusFlags::Zero | CpuStatusFlags::Overflow);
+    }
+
+    #[test]
+    fn test_sax_zero_page() {
+        store_accumulator_and_x_register(
+            FlatCartridge::new(vec![
+                // SAX $10
+                0x87, 0x10,
+            ]),
+            "*SAX $10 = 00",
+            3,
+            0x0010,
+        );
+    }
+
+    #[test]
+    fn test_sax_zero_page_y() {
+        store_accumulator_and_x_register(
+            FlatCartridge::new(vec![
+                // SAX $10,Y
+                0x97, 0x10,
+            ]),
+            "*SAX $10,Y @ 11 = 00",
+            4,
+            0x0011,
+        );
+    }
+
+    #[test]
+    fn test_sax_absolute() {
+        store_accumulator_and_x_register(
+            FlatCartridge::new(vec![
+                // SAX $0180
+                0x8F, 0x80, 0x01,
+            ]),
+            "*SAX $0180 = 00",
+            4,
+            0x0180,
+        );
+    }
+
+    #[test]
+    fn test_sax_indirect_x() {
+        store_accumulator_and_x_register(
+            FlatCartridge::new(vec![
+                // SAX ($80,X)
+                0x83, 0x80,
+            ]),
+            "*SAX ($80,X) @ 26 = 0180 = 00",
+            6,
+            0x0180,
+        );
+    }
+
+    #[test]
+    fn test_dcp_zero_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // DCP $10
+            0xC7, 0x10,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x40;
+        cpu.bus.write(0x0010, 0x41).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*DCP $10 = 41");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 5);
+
+        assert_eq!(cpu.bus.read(0x0010).unwrap(), 0x40);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_dcp_absolute_y_wraparound() {
+        let cartridge = FlatCartridge::new(vec![
+            // DCP $0180,Y
+            0xDB, 0x80, 0x01,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x40;
+        cpu.register_y = 0x01;
+        cpu.bus.write(0x0181, 0x00).unwrap();
+
+        // The indexed forms never skip the cycle fixing the upper byte of the address
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*DCP $0180,Y @ 0181 = 00");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 7);
+
+        assert_eq!(cpu.bus.read(0x0181).unwrap(), 0xFF);
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_dcp_indirect_x() {
+        let cartridge = FlatCartridge::new(vec![
+            // DCP ($45,X)
+            0xC3, 0x45,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x40;
+        cpu.register_x = 0x02;
+        cpu.bus.write(0x0047, 0x47).unwrap();
+        cpu.bus.write(0x0048, 0x06).unwrap();
+        cpu.bus.write(0x0647, 0xEB).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*DCP ($45,X) @ 47 = 0647 = EB");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 8);
+
+        assert_eq!(cpu.bus.read(0x0647).unwrap(), 0xEA);
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_dcp_indirect_y_no_page_cross() {
+        let cartridge = FlatCartridge::new(vec![
+            // DCP ($33),Y
+            0xD3, 0x33,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x00;
+        cpu.register_y = 0x01;
+        cpu.bus.write(0x0033, 0x00).unwrap();
+        cpu.bus.write(0x0034, 0x04).unwrap();
+        cpu.bus.write(0x0401, 0x02).unwrap();
+
+        // Even without crossing a page the instruction takes the longer cycle count
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*DCP ($33),Y = 0400 @ 0401 = 02");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 8);
+
+        assert_eq!(cpu.bus.read(0x0401).unwrap(), 0x01);
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_isb_zero_page_x_wraparound() {
+        let cartridge = FlatCartridge::new(vec![
+            // ISB $10,X
+            0xF7, 0x10,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x40;
+        cpu.register_x = 0x01;
+        cpu.status = CpuStatusFlags::Carry;
+        cpu.bus.write(0x0011, 0xFF).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*ISB $10,X @ 11 = FF");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 6);
+
+        // Subtracting the wrapped zero leaves the accumulator untouched without borrowing
+        assert_eq!(cpu.bus.read(0x0011).unwrap(), 0x00);
+        assert_eq!(cpu.accumulator, 0x40);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Overflow));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_isb_absolute_overflow() {
+        let cartridge = FlatCartridge::new(vec![
+            // ISB $0180
+            0xEF, 0x80, 0x01,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x80;
+        cpu.status = CpuStatusFlags::Carry;
+        cpu.bus.write(0x0180, 0x00).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*ISB $0180 = 00");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 6);
+
+        assert_eq!(cpu.bus.read(0x0180).unwrap(), 0x01);
+        assert_eq!(cpu.accumulator, 0x7F);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Overflow));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_isb_indirect_y_borrow() {
+        let cartridge = FlatCartridge::new(vec![
+            // ISB ($33),Y
+            0xF3, 0x33,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x10;
+        cpu.register_y = 0x10;
+        cpu.status = CpuStatusFlags::Carry;
+        cpu.bus.write(0x0033, 0xF8).unwrap();
+        cpu.bus.write(0x0034, 0x02).unwrap();
+        cpu.bus.write(0x0308, 0x10).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*ISB ($33),Y = 02F8 @ 0308 = 10");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 8);
+
+        assert_eq!(cpu.bus.read(0x0308).unwrap(), 0x11);
+        assert_eq!(cpu.accumulator, 0xFF);
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_slo_zero_page() {
+        let cartridge = FlatCartridge::new(vec![
+            // SLO $10
+            0x07, 0x10,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x01;
+        cpu.bus.write(0x0010, 0xC0).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*SLO $10 = C0");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 5);
+
+        assert_eq!(cpu.bus.read(0x0010).unwrap(), 0x80);
+        assert_eq!(cpu.accumulator, 0x81);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_rla_absolute_x() {
+        let cartridge = FlatCartridge::new(vec![
+            // RLA $0180,X
+            0x3F, 0x80, 0x01,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x0F;
+        cpu.register_x = 0x01;
+        cpu.status = CpuStatusFlags::Carry;
+        cpu.bus.write(0x0181, 0x84).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*RLA $0180,X @ 0181 = 84");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 7);
+
+        assert_eq!(cpu.bus.read(0x0181).unwrap(), 0x09);
+        assert_eq!(cpu.accumulator, 0x09);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_sre_indirect_x() {
+        let cartridge = FlatCartridge::new(vec![
+            // SRE ($40,X)
+            0x43, 0x40,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x21;
+        cpu.register_x = 0x02;
+        cpu.bus.write(0x0042, 0x00).unwrap();
+        cpu.bus.write(0x0043, 0x03).unwrap();
+        cpu.bus.write(0x0300, 0x43).unwrap();
+
+        let cpu_cycles = cpu.cpu_cycles;
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*SRE ($40,X) @ 42 = 0300 = 43");
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 8);
+
+        assert_eq!(cpu.bus.read(0x0300).unwrap(), 0x21);
+        assert_eq!(cpu.accumulator, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    #[test]
+    fn test_rra_carry_through() {
+        let cartridge = FlatCartridge::new(vec![
+            // RRA $10
+            0x67, 0x10,
+
+            // RRA $11
+            0x67, 0x11,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.accumulator = 0x10;
+        cpu.status = CpuStatusFlags::empty();
+        cpu.bus.write(0x0010, 0x03).unwrap();
+        cpu.bus.write(0x0011, 0x02).unwrap();
+
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "*RRA $10 = 03");
+
+        // The rotated out bit 0 is added as the carry
+        assert_eq!(cpu.bus.read(0x0010).unwrap(), 0x01);
+        assert_eq!(cpu.accumulator, 0x12);
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+
+        cpu.status = CpuStatusFlags::Carry;
+        cpu.accumulator = 0x7F;
+
+        cpu.run_full_instruction();
+
+        // The carry flows into bit 7 of the memory value before adding it
+        assert_eq!(cpu.bus.read(0x0011).unwrap(), 0x81);
+        assert_eq!(cpu.accumulator, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Overflow));
+    }
+
+    fn unofficial_immediate(opcode: u8, value: u8, setup: impl FnOnce(&mut Cpu)) -> Cpu {
+        let cartridge = FlatCartridge::new(vec![
+            opcode, value,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.status = CpuStatusFlags::empty();
+        setup(&mut cpu);
+
+        let cpu_cycles = cpu.cpu_cycles;
+        cpu.run_full_instruction();
+        assert_eq!(cpu.cpu_cycles - cpu_cycles, 2);
+        assert_eq!(cpu.program_counter, 0x8002);
+
+        cpu
+    }
+
+    #[test]
+    fn test_anc_immediate() {
+        for opcode in [0x0B, 0x2B] {
+            let cpu = unofficial_immediate(opcode, 0xF0, |cpu| cpu.accumulator = 0x8F);
+
+            assert_eq!(cpu.accumulator, 0x80);
+            assert!(cpu.status.contains(CpuStatusFlags::Carry));
+            assert!(cpu.status.contains(CpuStatusFlags::Negative));
+
+            let cpu = unofficial_immediate(opcode, 0x0F, |cpu| {
+                cpu.accumulator = 0x70;
+                cpu.status = CpuStatusFlags::Carry;
+            });
+
+            assert_eq!(cpu.accumulator, 0x00);
+            assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+            assert!(cpu.status.contains(CpuStatusFlags::Zero));
+        }
+    }
+
+    #[test]
+    fn test_alr_immediate() {
+        let cpu = unofficial_immediate(0x4B, 0x03, |cpu| cpu.accumulator = 0xFF);
+
+        assert_eq!(cpu.accumulator, 0x01);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+        assert!(!cpu.status.contains(CpuStatusFlags::Negative));
+
+        let cpu = unofficial_immediate(0x4B, 0x01, |cpu| cpu.accumulator = 0xFF);
+
+        assert_eq!(cpu.accumulator, 0x00);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Zero));
+    }
+
+    #[test]
+    fn test_arr_immediate() {
+        // (accumulator, value, carry in, result, carry out, overflow)
+        let vectors = [
+            (0xFF, 0xFF, false, 0x7F, true, false),
+            (0xFF, 0xFF, true, 0xFF, true, false),
+            (0xFF, 0x40, false, 0x20, false, true),
+            (0xFF, 0x80, false, 0x40, true, true),
+            (0xFF, 0xC0, false, 0x60, true, false),
+            (0xFF, 0x01, false, 0x00, false, false),
+            (0x00, 0xFF, true, 0x80, false, false),
+        ];
+
+        for (accumulator, value, carry, result, carry_out, overflow) in vectors {
+            let cpu = unofficial_immediate(0x6B, value, |cpu| {
+                cpu.accumulator = accumulator;
+                cpu.status.set(CpuStatusFlags::Carry, carry);
+            });
+
+            assert_eq!(cpu.accumulator, result, "{accumulator:02X} ARR {value:02X}");
+            assert_eq!(cpu.status.contains(CpuStatusFlags::Carry), carry_out, "{accumulator:02X} ARR {value:02X}");
+            assert_eq!(cpu.status.contains(CpuStatusFlags::Overflow), overflow, "{accumulator:02X} ARR {value:02X}");
+            assert_eq!(cpu.status.contains(CpuStatusFlags::Zero), result == 0);
+            assert_eq!(cpu.status.contains(CpuStatusFlags::Negative), result & 0x80 != 0);
+        }
+    }
+
+    #[test]
+    fn test_axs_immediate() {
+        let cpu = unofficial_immediate(0xCB, 0x02, |cpu| {
+            cpu.accumulator = 0x0F;
+            cpu.register_x = 0xFC;
+        });
+
+        assert_eq!(cpu.register_x, 0x0A);
+        assert_eq!(cpu.accumulator, 0x0F);
+        assert!(cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(!cpu.status.contains(CpuStatusFlags::Zero));
+
+        let cpu = unofficial_immediate(0xCB, 0x01, |cpu| {
+            cpu.accumulator = 0xF0;
+            cpu.register_x = 0x0F;
+        });
+
+        assert_eq!(cpu.register_x, 0xFF);
+        assert!(!cpu.status.contains(CpuStatusFlags::Carry));
+        assert!(cpu.status.contains(CpuStatusFlags::Negative));
+    }
+
+    fn unofficial_no_operation(opcodes: &[u8], arg_1: u8, assembly: &str, length: u16, cycles: u64) {
+        for &opcode in opcodes {
+            let cartridge = FlatCartridge::new(vec![
+                opcode, arg_1, 0x01,
+            ]);
+
+            let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+            cpu.register_x = 0x01;
+            let status = cpu.status;
+
+            let cpu_cycles = cpu.cpu_cycles;
+            let instruction_data = cpu.run_full_instruction();
+            assert_eq!(instruction_data.assembly, assembly, "Opcode {opcode:02X}");
+            assert_eq!(cpu.cpu_cycles - cpu_cycles, cycles, "Opcode {opcode:02X}");
+            assert_eq!(cpu.program_counter, 0x8000 + length, "Opcode {opcode:02X}");
+
+            assert_eq!(cpu.accumulator, 0x00);
+            assert_eq!(cpu.status, status);
+        }
+    }
+
+    #[test]
+    fn test_nop_unofficial_implied() {
+        unofficial_no_operation(&[0x1A, 0x3A, 0x5A, 0x7A, 0xDA, 0xFA], 0x10, "*NOP", 1, 2);
+    }
+
+    #[test]
+    fn test_nop_unofficial_immediate() {
+        unofficial_no_operation(&[0x80, 0x82, 0x89, 0xC2, 0xE2], 0x10, "*NOP #$10", 2, 2);
+    }
+
+    #[test]
+    fn test_nop_unofficial_zero_page() {
+        unofficial_no_operation(&[0x04, 0x44, 0x64], 0x10, "*NOP $10 = 00", 2, 3);
+    }
+
+    #[test]
+    fn test_nop_unofficial_zero_page_x() {
+        unofficial_no_operation(&[0x14, 0x34, 0x54, 0x74, 0xD4, 0xF4], 0x10, "*NOP $10,X @ 11 = 00", 2, 4);
+    }
+
+    #[test]
+    fn test_nop_unofficial_absolute() {
+        unofficial_no_operation(&[0x0C], 0x10, "*NOP $0110 = 00", 3, 4);
+    }
+
+    #[test]
+    fn test_nop_unofficial_absolute_x() {
+        unofficial_no_operation(&[0x1C, 0x3C, 0x5C, 0x7C, 0xDC, 0xFC], 0x10, "*NOP $0110,X @ 0111 = 00", 3, 4);
+
+        // Crossing a page takes an extra cycle
+        unofficial_no_operation(&[0x1C, 0x3C, 0x5C, 0x7C, 0xDC, 0xFC], 0xFF, "*NOP $01FF,X @ 0200 = 00", 3, 5);
+    }
+
+    #[test]
+    fn test_nop_unofficial_reads_operand() {
+        let cartridge = FlatCartridge::new(vec![
+            // NOP $8010
+            0x0C, 0x10, 0x80,
+        ]);
+
+        let accesses = cartridge.accesses();
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+
+        let instruction_data = cpu.cycle_with_snapshot().unwrap().unwrap().instruction_data;
+        accesses.borrow_mut().clear();
+
+        for _ in 0..instruction_data.idle_cycles {
+            cpu.cycle().unwrap();
+        }
+
+        assert_eq!(
+            *accesses.borrow(),
+            vec![
+                FlatAccess::Read(0x8001),
+                FlatAccess::Read(0x8002),
+                FlatAccess::Read(0x8010),
+            ]
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! Holds the watchpoints, which raise an event when the CPU accesses some addresses.
+
+use std::ops::RangeInclusive;
+
+use crate::bus::BusAccess;
+use crate::cartridge::Cartridge;
+use crate::cpu::{Cpu, CpuEvent};
+
+impl<C: Cartridge> Cpu<C> {
+    /// Raise a [CpuEvent::Watchpoint] every time the CPU reads from the given address.
+    ///
+    /// The address is matched as the CPU accesses it, so the mirrors of the address are not watched.
+    pub fn watch_read(&mut self, address: u16) {
+        self.watch_read_range(address..=address);
+    }
+
+    /// Raise a [CpuEvent::Watchpoint] every time the CPU writes to the given address.
+    ///
+    /// The address is matched as the CPU accesses it, so the mirrors of the address are not watched.
+    pub fn watch_write(&mut self, address: u16) {
+        self.watch_write_range(address..=address);
+    }
+
+    /// Raise a [CpuEvent::Watchpoint] every time the CPU reads from one of the given addresses.
+    pub fn watch_read_range(&mut self, addresses: RangeInclusive<u16>) {
+        self.bus.watch(addresses, BusAccess::Read);
+    }
+
+    /// Raise a [CpuEvent::Watchpoint] every time the CPU writes to one of the given addresses.
+    pub fn watch_write_range(&mut self, addresses: RangeInclusive<u16>) {
+        self.bus.watch(addresses, BusAccess::Write);
+    }
+
+    /// Remove all the watchpoints, the events already raised can still be taken.
+    pub fn clear_watchpoints(&mut self) {
+        self.bus.clear_watchpoints();
+    }
+
+    /// Take the events raised since the last call, in the order they happened.
+    pub fn take_events(&mut self) -> Vec<CpuEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Turn the accesses that hit a watchpoint during the last cycle into events, given the number
+    /// of cycles run before it.
+    pub(super) fn collect_watchpoint_hits(&mut self, cycles: u64) {
+        for hit in self.bus.take_watchpoint_hits() {
+            self.events.push(CpuEvent::Watchpoint {
+                address: hit.address,
+                value: hit.value,
+                access: hit.access,
+                program_counter: self.current_instruction_address,
+                cycles,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::*;
+
+    #[test]
+    fn test_watch_write() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDX #$42
+            0xA2, 0x42,
+
+            // STX $F0
+            0x86, 0xF0,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.watch_write(0x00F0);
+
+        cpu.run_full_instruction();
+
+        // The opcode and the operand fetches
+        cpu.cycle().unwrap();
+        cpu.cycle().unwrap();
+        assert_eq!(cpu.take_events(), vec![]);
+
+        let cycles = cpu.cycles();
+        cpu.cycle().unwrap();
+        assert_eq!(
+            cpu.take_events(),
+            vec![CpuEvent::Watchpoint {
+                address: 0x00F0,
+                value: 0x42,
+                access: BusAccess::Write,
+                program_counter: 0x8002,
+                cycles,
+            }]
+        );
+
+        // The events are only taken once
+        assert_eq!(cpu.take_events(), vec![]);
+    }
+
+    #[test]
+    fn test_watch_read_ignores_peeks() {
+        let cartridge = FlatCartridge::new(vec![
+            // STX $F0
+            0x86, 0xF0,
+
+            // LDY $F0
+            0xA4, 0xF0,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.watch_read(0x00F0);
+
+        // The disassembly peeks the operand, which is not a real access
+        let instruction_data = cpu.run_full_instruction();
+        assert_eq!(instruction_data.assembly, "STX $F0 = 00");
+        assert_eq!(cpu.take_events(), vec![]);
+
+        cpu.run_full_instruction();
+
+        assert!(matches!(
+            cpu.take_events()[..],
+            [CpuEvent::Watchpoint { address: 0x00F0, value: 0x00, access: BusAccess::Read, program_counter: 0x8002, .. }]
+        ));
+    }
+
+    #[test]
+    fn test_watch_range() {
+        let cartridge = FlatCartridge::new(vec![
+            // LDX #$42
+            0xA2, 0x42,
+
+            // STX $10
+            0x86, 0x10,
+
+            // STX $20
+            0x86, 0x20,
+
+            // STX $30
+            0x86, 0x30,
+        ]);
+
+        let mut cpu = Cpu::new(Box::new(cartridge)).unwrap();
+        cpu.watch_write_range(0x0010..=0x0020);
+
+        cpu.batch_run_full_instruction(4);
+
+        let addresses: Vec<u16> = cpu
+            .take_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                CpuEvent::Watchpoint { address, .. } => Some(address),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(addresses, vec![0x0010, 0x0020]);
+
+        cpu.clear_watchpoints();
+        cpu.set_program_counter(0x8002);
+        cpu.run_full_instruction();
+
+        assert_eq!(cpu.take_events(), vec![]);
+    }
+}
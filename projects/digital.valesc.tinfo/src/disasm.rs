@@ -0,0 +1,249 @@
+//! Holds a static disassembler, turning the bytes of a program into instructions without running them.
+
+use std::fmt;
+
+use crate::build_address;
+use crate::bus::Bus;
+use crate::cartridge::Cartridge;
+use crate::cpu::opcode::OPCODES;
+use crate::cpu::{AddressingMode, Mnemonic};
+use crate::relative_address;
+
+/// An instruction decoded by the disassembler.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisassembledInstruction {
+    /// The address of the opcode of the instruction.
+    pub address: u16,
+
+    /// The bytes of the instruction, starting with the opcode.
+    pub bytes: Vec<u8>,
+
+    /// The mnemonic of the instruction, [None] for the opcodes that are not supported.
+    pub mnemonic: Option<Mnemonic>,
+
+    /// If the opcode is part of the documented instruction set of the 6502.
+    pub is_official: bool,
+
+    /// The way the instruction gets its operand, [None] for the opcodes that are not supported.
+    pub addressing_mode: Option<AddressingMode>,
+
+    /// The operand formatted like an assembler takes it, empty if the instruction has none.
+    pub operand: String,
+}
+
+impl fmt::Display for DisassembledInstruction {
+    /// Format the instruction as assembly, prefixing the mnemonic with `*` when the opcode is
+    /// unofficial like the nestest logs do, and writing the unsupported opcodes as `.byte`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.mnemonic {
+            Some(mnemonic) => write!(f, "{}", mnemonic.assembly(self.is_official))?,
+            None => write!(f, ".byte")?,
+        }
+
+        if !self.operand.is_empty() {
+            write!(f, " {}", self.operand)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Disassemble the given number of instructions starting at the given address of the bus.
+///
+/// The bus is only peeked with [Bus::peek], so disassembling has no side effects.
+pub fn disassemble<C: Cartridge>(bus: &Bus<C>, start: u16, count: usize) -> Vec<DisassembledInstruction> {
+    disassemble_with(start, count, |address| Some(bus.peek(address)))
+}
+
+/// Disassemble up to the given number of instructions from the start of the given bytes, which are
+/// mapped starting at the given address, like the ones of a PRG ROM.
+///
+/// The disassembly stops early at the end of the bytes, leaving the opcode of a truncated
+/// instruction as `.byte`.
+pub fn disassemble_slice(bytes: &[u8], base_address: u16, count: usize) -> Vec<DisassembledInstruction> {
+    disassemble_with(base_address, count, |address| bytes.get(address.wrapping_sub(base_address) as usize).copied())
+}
+
+/// Disassemble the given number of instructions starting at the given address, reading the bytes
+/// with the given function, which answers [None] past the end of the program.
+fn disassemble_with(start: u16, count: usize, read: impl Fn(u16) -> Option<u8>) -> Vec<DisassembledInstruction> {
+    let mut instructions = Vec::with_capacity(count);
+    let mut address = start;
+
+    while instructions.len() < count {
+        let Some(opcode) = read(address) else {
+            break;
+        };
+
+        let instruction = match OPCODES[opcode as usize] {
+            Some(info) => {
+                let operand_bytes = (1..info.addressing_mode.length() as u16)
+                    .map(|offset| read(address.wrapping_add(offset)))
+                    .collect::<Option<Vec<u8>>>();
+
+                operand_bytes.map(|operand_bytes| DisassembledInstruction {
+                    address,
+                    bytes: [&[opcode], &operand_bytes[..]].concat(),
+                    mnemonic: Some(info.mnemonic),
+                    is_official: info.official,
+                    addressing_mode: Some(info.addressing_mode),
+                    operand: format_operand(info.addressing_mode, &operand_bytes, address),
+                })
+            }
+
+            None => None,
+        };
+
+        let instruction = instruction.unwrap_or_else(|| DisassembledInstruction {
+            address,
+            bytes: vec![opcode],
+            mnemonic: None,
+            is_official: false,
+            addressing_mode: None,
+            operand: format!("${opcode:02X}"),
+        });
+
+        address = address.wrapping_add(instruction.bytes.len() as u16);
+        instructions.push(instruction);
+    }
+
+    instructions
+}
+
+/// Format the operand of an instruction at the given address using the given addressing mode,
+/// given the bytes following its opcode.
+fn format_operand(mode: AddressingMode, operand_bytes: &[u8], address: u16) -> String {
+    let arg_1 = operand_bytes.first().copied().unwrap_or_default();
+    let word = build_address(arg_1, operand_bytes.get(1).copied().unwrap_or_default());
+
+    match mode {
+        AddressingMode::Implied => String::new(),
+        AddressingMode::Accumulator => String::from("A"),
+        AddressingMode::Immediate => format!("#${arg_1:02X}"),
+        AddressingMode::ZeroPage => format!("${arg_1:02X}"),
+        AddressingMode::ZeroPageX => format!("${arg_1:02X},X"),
+        AddressingMode::ZeroPageY => format!("${arg_1:02X},Y"),
+        AddressingMode::Absolute => format!("${word:04X}"),
+        AddressingMode::AbsoluteX => format!("${word:04X},X"),
+        AddressingMode::AbsoluteY => format!("${word:04X},Y"),
+        AddressingMode::Indirect => format!("(${word:04X})"),
+        AddressingMode::IndirectX => format!("(${arg_1:02X},X)"),
+        AddressingMode::IndirectY => format!("(${arg_1:02X}),Y"),
+        AddressingMode::Relative => {
+            let (_, target) = relative_address(address.wrapping_add(2), arg_1);
+
+            format!("${target:04X}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::FlatCartridge;
+
+    /// A program using every addressing mode, followed by an unofficial opcode and an unsupported one.
+    const PROGRAM: [u8; 31] = [
+        // NOP
+        0xEA,
+
+        // ASL A
+        0x0A,
+
+        // LDA #$42
+        0xA9, 0x42,
+
+        // STA $10
+        0x85, 0x10,
+
+        // LDY $10,X
+        0xB4, 0x10,
+
+        // LDX $10,Y
+        0xB6, 0x10,
+
+        // JSR $1234
+        0x20, 0x34, 0x12,
+
+        // LDA $1234,X
+        0xBD, 0x34, 0x12,
+
+        // STA $1234,Y
+        0x99, 0x34, 0x12,
+
+        // JMP ($0200)
+        0x6C, 0x00, 0x02,
+
+        // ORA ($10,X)
+        0x01, 0x10,
+
+        // ADC ($10),Y
+        0x71, 0x10,
+
+        // BNE $8018
+        0xD0, 0xFC,
+
+        // *SLO $10
+        0x07, 0x10,
+
+        // An unsupported opcode
+        0xBB,
+    ];
+
+    /// The disassembly of [PROGRAM], one line per instruction.
+    const LISTING: [&str; 15] = [
+        "NOP",
+        "ASL A",
+        "LDA #$42",
+        "STA $10",
+        "LDY $10,X",
+        "LDX $10,Y",
+        "JSR $1234",
+        "LDA $1234,X",
+        "STA $1234,Y",
+        "JMP ($0200)",
+        "ORA ($10,X)",
+        "ADC ($10),Y",
+        "BNE $8018",
+        "*SLO $10",
+        ".byte $BB",
+    ];
+
+    #[test]
+    fn test_disassemble_slice() {
+        let instructions = disassemble_slice(&PROGRAM, 0x8000, LISTING.len());
+        let listing: Vec<String> = instructions.iter().map(ToString::to_string).collect();
+
+        assert_eq!(listing, LISTING);
+
+        assert_eq!(instructions[7].address, 0x800D);
+        assert_eq!(instructions[7].bytes, vec![0xBD, 0x34, 0x12]);
+        assert_eq!(instructions[7].mnemonic, Some(Mnemonic::Lda));
+        assert_eq!(instructions[7].addressing_mode, Some(AddressingMode::AbsoluteX));
+        assert_eq!(instructions[7].operand, "$1234,X");
+
+        assert!(!instructions[13].is_official);
+        assert_eq!(instructions[14].addressing_mode, None);
+    }
+
+    #[test]
+    fn test_disassemble_bus() {
+        let bus = Bus::new(Box::new(FlatCartridge::new(PROGRAM.to_vec())));
+
+        let instructions = disassemble(&bus, 0x8006, 3);
+        let listing: Vec<String> = instructions.iter().map(ToString::to_string).collect();
+
+        assert_eq!(listing, LISTING[4..7]);
+        assert_eq!(instructions[2].address, 0x800A);
+    }
+
+    #[test]
+    fn test_disassemble_slice_truncated() {
+        let instructions = disassemble_slice(&[0xEA, 0xAD, 0x34], 0xC000, 10);
+        let listing: Vec<String> = instructions.iter().map(ToString::to_string).collect();
+
+        // The operand of the LDA is missing its upper byte
+        assert_eq!(listing, vec!["NOP", ".byte $AD", ".byte $34"]);
+        assert_eq!(instructions[2].address, 0xC002);
+    }
+}
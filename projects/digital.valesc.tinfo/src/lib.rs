@@ -1,8 +1,14 @@
 //! Headless NES
 
+pub mod apu;
 pub mod bus;
 pub mod cartridge;
+pub mod cheat;
+pub mod coverage;
 pub mod cpu;
+pub mod disasm;
+pub mod ppu;
+pub mod rewind;
 pub mod rom;
 
 /// The number of bytes in a kibibyte (1 KiB).
@@ -14,6 +20,39 @@ pub(crate) fn build_address(lower_byte: u8, upper_byte: u8) -> u16 {
     (lower_byte as u16) | ((upper_byte as u16) << 8)
 }
 
+/// Add an offset to a base address the way the CPU does, returning the address with the upper byte
+/// still unfixed, the one accessed by the dummy reads, along with the fixed address.
+pub(crate) fn offset_address(base_address: u16, offset: u8) -> (u16, u16) {
+    let address = base_address.wrapping_add(offset as u16);
+
+    (build_address(address.lower_byte(), base_address.upper_byte()), address)
+}
+
+/// Add a signed offset, as taken by the relative branches, to a base address, returning the address
+/// with the upper byte still unfixed along with the fixed address.
+///
+/// Negative offsets borrow from the upper byte instead of carrying into it, so the fixed address may
+/// land on the previous page.
+pub(crate) fn relative_address(base_address: u16, offset: u8) -> (u16, u16) {
+    let address = base_address.wrapping_add(offset as i8 as u16);
+
+    (build_address(address.lower_byte(), base_address.upper_byte()), address)
+}
+
+/// Get the address after the given one without carrying into the upper byte, wrapping from `$xxFF`
+/// to `$xx00` like the pointers of the CPU do.
+pub(crate) fn next_address_in_page(address: u16) -> u16 {
+    offset_address(address, 1).0
+}
+
+/// Check if adding an offset to a base address crosses a page boundary, costing the CPU an extra
+/// cycle to fix the upper byte of the address.
+pub(crate) fn crosses_page(base_address: u16, offset: u8) -> bool {
+    let (unfixed_address, address) = offset_address(base_address, offset);
+
+    unfixed_address != address
+}
+
 /// Extension methods for the [u16] type.
 trait U16Ex {
     /// Get the least significant byte of the [u16].
@@ -37,7 +76,7 @@ impl U16Ex for u16 {
 
 #[cfg(test)]
 mod tests {
-    use crate::{build_address, U16Ex};
+    use crate::{build_address, crosses_page, offset_address, relative_address, U16Ex};
 
     #[test]
     fn test_build_address() {
@@ -53,4 +92,53 @@ mod tests {
     fn test_u16_get_upper_byte() {
         assert_eq!(0xFF00_u16.upper_byte(), 0xFF);
     }
+
+    #[test]
+    fn test_offset_address_page_boundary() {
+        assert_eq!(offset_address(0x00FF, 0x01), (0x0000, 0x0100));
+        assert_eq!(offset_address(0x0100, 0xFF), (0x01FF, 0x01FF));
+        assert!(crosses_page(0x00FF, 0x01));
+        assert!(!crosses_page(0x0100, 0xFF));
+    }
+
+    #[test]
+    fn test_offset_address_wraps_around_memory() {
+        assert_eq!(offset_address(0xFFFF, 0x01), (0xFF00, 0x0000));
+        assert_eq!(offset_address(0xFF80, 0xFF), (0xFF7F, 0x007F));
+        assert!(crosses_page(0xFFFF, 0x01));
+    }
+
+    #[test]
+    fn test_offset_address_properties() {
+        let bases = (0x00F0..=0x0110).chain(0xFFE0..=0xFFFF).chain([0x0000, 0x8000]);
+
+        for base_address in bases {
+            for offset in 0..=0xFF {
+                let (unfixed_address, address) = offset_address(base_address, offset);
+
+                assert_eq!(address, base_address.wrapping_add(offset as u16));
+                assert_eq!(unfixed_address.lower_byte(), address.lower_byte());
+                assert_eq!(unfixed_address.upper_byte(), base_address.upper_byte());
+
+                // Crossing a page only ever carries one into the upper byte
+                assert_eq!(crosses_page(base_address, offset), base_address.upper_byte() != address.upper_byte());
+                if crosses_page(base_address, offset) {
+                    assert_eq!(address.upper_byte(), base_address.upper_byte().wrapping_add(1));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_relative_address_forward() {
+        assert_eq!(relative_address(0x8002, 0x20), (0x8022, 0x8022));
+        assert_eq!(relative_address(0x80F2, 0x7F), (0x8071, 0x8171));
+    }
+
+    #[test]
+    fn test_relative_address_backward() {
+        assert_eq!(relative_address(0x8012, 0xF6), (0x8008, 0x8008));
+        assert_eq!(relative_address(0x8102, 0xFC), (0x81FE, 0x80FE));
+        assert_eq!(relative_address(0x0001, 0x80), (0x0081, 0xFF81));
+    }
 }
@@ -0,0 +1,649 @@
+//! Holds the emulation of the PPU, the 2C02 chip drawing the picture, starting with the eight
+//! registers it exposes to the CPU.
+
+use bitflags::bitflags;
+
+use crate::cartridge::Cartridge;
+use crate::ppu::render::Background;
+use crate::ppu::sprites::Sprites;
+
+mod frame;
+mod memory;
+mod render;
+mod sprites;
+mod timing;
+
+pub use crate::ppu::frame::{Frame, FRAME_HEIGHT, FRAME_WIDTH, NTSC_PALETTE};
+pub use crate::ppu::memory::{PALETTE_SIZE, VRAM_SIZE};
+pub use crate::ppu::sprites::SECONDARY_OAM_SIZE;
+pub use crate::ppu::timing::{DOTS_PER_SCANLINE, SCANLINES_PER_FRAME};
+
+/// The number of registers of the PPU, mirrored every eight bytes from `$2000` to `$3FFF`.
+pub const PPU_REGISTERS: usize = 8;
+
+/// The number of bytes of the OAM of the PPU, which holds the attributes of the sprites.
+pub const OAM_SIZE: usize = 256;
+
+/// The mask giving the number of the PPU register accessed by an address of its mirrored range.
+const PPU_REGISTER_MASK: u16 = 0x0007;
+
+/// The number of the PPUCTRL register, write-only.
+const PPUCTRL: u8 = 0;
+
+/// The number of the PPUMASK register, write-only.
+const PPUMASK: u8 = 1;
+
+/// The number of the PPUSTATUS register, read-only.
+const PPUSTATUS: u8 = 2;
+
+/// The number of the OAMADDR register, write-only.
+const OAMADDR: u8 = 3;
+
+/// The number of the OAMDATA register.
+const OAMDATA: u8 = 4;
+
+/// The number of the PPUSCROLL register, write-only and sharing the write latch with PPUADDR.
+const PPUSCROLL: u8 = 5;
+
+/// The number of the PPUADDR register, write-only and sharing the write latch with PPUSCROLL.
+const PPUADDR: u8 = 6;
+
+/// The number of the PPUDATA register.
+const PPUDATA: u8 = 7;
+
+/// The bits missing from the attribute bytes of the OAM, which read back as zeros.
+const OAM_ATTRIBUTE_MISSING_BITS: u8 = 0b0001_1100;
+
+/// The bits of PPUSTATUS driven by the PPU, the other ones come from the PPU data bus.
+const PPUSTATUS_BITS: u8 = 0b1110_0000;
+
+/// The bits of the loopy registers selecting the nametable.
+const NAMETABLE_BITS: u16 = 0b000_1100_0000_0000;
+
+/// The bits of the loopy registers holding the coarse X scroll.
+const COARSE_X_BITS: u16 = 0b000_0000_0001_1111;
+
+/// The bits of the loopy registers holding the coarse and fine Y scroll.
+const Y_SCROLL_BITS: u16 = 0b111_0011_1110_0000;
+
+/// The bits of the loopy registers making a 15-bit address.
+const LOOPY_BITS: u16 = 0x7FFF;
+
+bitflags! {
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    /// The flags of PPUCTRL, controlling the PPU.
+    pub struct PpuCtrl: u8 {
+        /// The bit 0 of the base nametable, adding 256 to the X scroll.
+        const NametableX = 1 << 0;
+
+        /// The bit 1 of the base nametable, adding 240 to the Y scroll.
+        const NametableY = 1 << 1;
+
+        /// If the VRAM address is incremented by 32 after an access to PPUDATA, going down a row of
+        /// the nametable, instead of 1.
+        const VramIncrement32 = 1 << 2;
+
+        /// If the patterns of the 8x8 sprites are taken from `$1000` instead of `$0000`.
+        const SpritePatternTable = 1 << 3;
+
+        /// If the patterns of the background are taken from `$1000` instead of `$0000`.
+        const BackgroundPatternTable = 1 << 4;
+
+        /// If the sprites are 8x16 instead of 8x8.
+        const TallSprites = 1 << 5;
+
+        /// If the PPU drives its EXT pins, which is never done on the consoles.
+        const MasterSlave = 1 << 6;
+
+        /// If a NMI is raised when the vertical blank starts.
+        const Nmi = 1 << 7;
+    }
+}
+
+bitflags! {
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    /// The flags of PPUMASK, controlling the rendering.
+    pub struct PpuMask: u8 {
+        /// If the colors are turned into greys.
+        const Greyscale = 1 << 0;
+
+        /// If the background is shown on the leftmost 8 pixels of the screen.
+        const ShowBackgroundLeft = 1 << 1;
+
+        /// If the sprites are shown on the leftmost 8 pixels of the screen.
+        const ShowSpritesLeft = 1 << 2;
+
+        /// If the background is rendered.
+        const ShowBackground = 1 << 3;
+
+        /// If the sprites are rendered.
+        const ShowSprites = 1 << 4;
+
+        /// If the red is emphasized.
+        const EmphasizeRed = 1 << 5;
+
+        /// If the green is emphasized.
+        const EmphasizeGreen = 1 << 6;
+
+        /// If the blue is emphasized.
+        const EmphasizeBlue = 1 << 7;
+    }
+}
+
+bitflags! {
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    /// The flags of PPUSTATUS, telling the state of the rendering.
+    pub struct PpuStatus: u8 {
+        /// If more than eight sprites were found on a scanline, with the hardware bug of the
+        /// evaluation.
+        const SpriteOverflow = 1 << 5;
+
+        /// If an opaque pixel of the sprite 0 overlapped an opaque pixel of the background.
+        const Sprite0Hit = 1 << 6;
+
+        /// If the vertical blank has started.
+        const VBlank = 1 << 7;
+    }
+}
+
+/// Implement the serde traits of the given flags as their bits.
+macro_rules! impl_serde_bits {
+    ($($flags:ty),*) => {
+        $(
+            #[cfg(feature = "serde")]
+            impl serde::Serialize for $flags {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.serialize_u8(self.bits())
+                }
+            }
+
+            #[cfg(feature = "serde")]
+            impl<'de> serde::Deserialize<'de> for $flags {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    u8::deserialize(deserializer).map(<$flags>::from_bits_retain)
+                }
+            }
+        )*
+    };
+}
+
+impl_serde_bits!(PpuCtrl, PpuMask, PpuStatus);
+
+/// Emulation of the PPU of the NES, the 2C02.
+///
+/// The CPU talks to the PPU through eight registers, mirrored every eight bytes from `$2000` to
+/// `$3FFF`. The scroll and the VRAM address are kept in the internal registers described by loopy
+/// on the NESDev wiki: the current VRAM address `v`, the temporary one `t`, the fine X scroll `x`
+/// and the write latch `w` shared by PPUSCROLL and PPUADDR.
+///
+/// The write-only registers read the value left on the data bus between the CPU and the PPU,
+/// which is also the source of the lower bits of PPUSTATUS.
+///
+/// The PPU memory is accessed through PPUDATA, at the VRAM address: the pattern tables are on the
+/// cartridge, while the nametables and the palettes are on the PPU. The reads but the ones of the
+/// palettes return the byte buffered by the previous read.
+///
+/// The PPU runs three dots for every CPU cycle, clocked by the bus, drawing the background into
+/// its [Frame] while rendering, then setting the VBlank flag of PPUSTATUS when the vertical blank
+/// starts and raising a NMI if PPUCTRL asks for it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ppu {
+    /// The flags of PPUCTRL.
+    ctrl: PpuCtrl,
+
+    /// The flags of PPUMASK.
+    mask: PpuMask,
+
+    /// The flags of PPUSTATUS.
+    status: PpuStatus,
+
+    /// The address of the OAM accessed through OAMDATA.
+    oam_address: u8,
+
+    /// The OAM, which holds the attributes of the sprites.
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    oam: [u8; OAM_SIZE],
+
+    /// The current VRAM address, `v`, also the scroll position while rendering.
+    vram_address: u16,
+
+    /// The temporary VRAM address, `t`, the scroll position of the top left of the screen.
+    temporary_address: u16,
+
+    /// The fine X scroll, `x`, the pixel of the tile where the rendering starts.
+    fine_x: u8,
+
+    /// The write latch, `w`, telling if the next write to PPUSCROLL or PPUADDR is the second one.
+    write_latch: bool,
+
+    /// The last value on the data bus between the CPU and the PPU, read back from the write-only
+    /// registers.
+    io_bus: u8,
+
+    /// The byte of the PPU memory buffered by the last read of PPUDATA.
+    read_buffer: u8,
+
+    /// The VRAM, which holds the nametables.
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    vram: [u8; VRAM_SIZE],
+
+    /// The palette RAM, which holds the colors of the background and the sprites.
+    palette: [u8; PALETTE_SIZE],
+
+    /// The scanline of the next dot, from 0 to 261, the pre-render one.
+    scanline: u16,
+
+    /// The next dot of the scanline, from 0 to 340.
+    dot: u16,
+
+    /// The number of frames fully run since the power-on.
+    frame_count: u64,
+
+    /// If a NMI was raised and not taken by the CPU yet.
+    nmi_request: bool,
+
+    /// The pipeline fetching the tiles of the background.
+    background: Background,
+
+    /// The sprites evaluated for the next scanline and the ones drawn on the current one.
+    sprites: Sprites,
+
+    /// The picture drawn.
+    frame: Frame,
+}
+
+impl Ppu {
+    /// Create a new [Ppu] as it is on power-on.
+    pub fn new() -> Ppu {
+        Ppu {
+            ctrl: PpuCtrl::empty(),
+            mask: PpuMask::empty(),
+            status: PpuStatus::empty(),
+            oam_address: 0x00,
+            oam: [0; OAM_SIZE],
+            vram_address: 0x0000,
+            temporary_address: 0x0000,
+            fine_x: 0,
+            write_latch: false,
+            io_bus: 0x00,
+            read_buffer: 0x00,
+            vram: [0; VRAM_SIZE],
+            palette: [0; PALETTE_SIZE],
+            scanline: 0,
+            dot: 0,
+            frame_count: 0,
+            nmi_request: false,
+            background: Background::default(),
+            sprites: Sprites::default(),
+            frame: Frame::new(),
+        }
+    }
+
+    /// Get the number of the register accessed by the given address of the range mirroring the
+    /// PPU registers.
+    pub fn register_number(address: u16) -> u8 {
+        (address & PPU_REGISTER_MASK) as u8
+    }
+
+    /// Get the flags of PPUCTRL.
+    pub fn ctrl(&self) -> PpuCtrl {
+        self.ctrl
+    }
+
+    /// Get the flags of PPUMASK.
+    pub fn mask(&self) -> PpuMask {
+        self.mask
+    }
+
+    /// Get the flags of PPUSTATUS.
+    pub fn status(&self) -> PpuStatus {
+        self.status
+    }
+
+    /// Get the address of the OAM accessed through OAMDATA.
+    pub fn oam_address(&self) -> u8 {
+        self.oam_address
+    }
+
+    /// Get the current VRAM address, the loopy `v` register.
+    pub fn vram_address(&self) -> u16 {
+        self.vram_address
+    }
+
+    /// Get the temporary VRAM address, the loopy `t` register.
+    pub fn temporary_address(&self) -> u16 {
+        self.temporary_address
+    }
+
+    /// Get the fine X scroll, the loopy `x` register.
+    pub fn fine_x(&self) -> u8 {
+        self.fine_x
+    }
+
+    /// Tell if the next write to PPUSCROLL or PPUADDR is the second one, the loopy `w` register.
+    pub fn write_latch(&self) -> bool {
+        self.write_latch
+    }
+
+    /// Get the 32 bytes of the palettes as the PPU sees them, the four background palettes first,
+    /// then the four sprite palettes, with the aliased first colors of the sprite palettes resolved
+    /// to the background ones.
+    pub fn palette(&self) -> [u8; PALETTE_SIZE] {
+        std::array::from_fn(|index| self.palette[Ppu::palette_index(index as u16)])
+    }
+
+    /// Get the picture drawn by the PPU, the last frame above the scanline being drawn and the one
+    /// before below it.
+    pub fn frame(&self) -> &Frame {
+        &self.frame
+    }
+
+    /// Get the scanline of the next dot the PPU runs, from 0 to 261, the pre-render one.
+    pub fn scanline(&self) -> u16 {
+        self.scanline
+    }
+
+    /// Get the next dot the PPU runs in its scanline, from 0 to 340.
+    pub fn dot(&self) -> u16 {
+        self.dot
+    }
+
+    /// Get the number of frames fully run since the power-on.
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    /// Read the register accessed by the given address, given the cartridge mapping the pattern
+    /// tables.
+    ///
+    /// Reading PPUSTATUS clears the VBlank flag and resets the write latch, while reading PPUDATA
+    /// moves on to the next byte of the PPU memory.
+    pub(crate) fn read<C: Cartridge>(&mut self, address: u16, cartridge: &C) -> u8 {
+        let value = match Ppu::register_number(address) {
+            PPUSTATUS => {
+                let value = self.peek(address);
+                self.status.remove(PpuStatus::VBlank);
+                self.write_latch = false;
+
+                value
+            }
+
+            PPUDATA => self.read_data(cartridge),
+            _ => self.peek(address),
+        };
+
+        self.io_bus = value;
+
+        value
+    }
+
+    /// Read the register accessed by the given address like [Ppu::read] does, without side
+    /// effects.
+    ///
+    /// OAMDATA is always readable, even while rendering, where the consoles return the byte the
+    /// sprite evaluation is at.
+    pub(crate) fn peek(&self, address: u16) -> u8 {
+        match Ppu::register_number(address) {
+            PPUSTATUS => (self.status.bits() & PPUSTATUS_BITS) | (self.io_bus & !PPUSTATUS_BITS),
+            OAMDATA => self.oam[self.oam_address as usize],
+            PPUDATA => self.peek_data(),
+
+            // The write-only registers
+            _ => self.io_bus,
+        }
+    }
+
+    /// Write a value to the register accessed by the given address, given the cartridge mapping
+    /// the pattern tables.
+    ///
+    /// Enabling the NMI in PPUCTRL during the vertical blank raises one right away.
+    pub(crate) fn write<C: Cartridge>(&mut self, address: u16, value: u8, cartridge: &mut C) {
+        self.io_bus = value;
+
+        match Ppu::register_number(address) {
+            PPUCTRL => {
+                let ctrl = PpuCtrl::from_bits_retain(value);
+
+                let nmi_enabled = ctrl.contains(PpuCtrl::Nmi) && !self.ctrl.contains(PpuCtrl::Nmi);
+
+                if nmi_enabled && self.status.contains(PpuStatus::VBlank) {
+                    self.nmi_request = true;
+                }
+
+                self.ctrl = ctrl;
+                self.temporary_address = (self.temporary_address & !NAMETABLE_BITS) | ((value as u16 & 0x03) << 10);
+            }
+
+            PPUMASK => self.mask = PpuMask::from_bits_retain(value),
+
+            OAMADDR => self.oam_address = value,
+
+            OAMDATA => self.write_oam_data(value),
+
+            PPUSCROLL => self.write_scroll(value),
+            PPUADDR => self.write_address(value),
+            PPUDATA => self.write_data(value, cartridge),
+
+            // PPUSTATUS is read-only
+            _ => {}
+        }
+    }
+
+    /// Get the OAM, which holds the attributes of the sprites, four bytes each, for the sprite
+    /// viewers.
+    pub fn oam(&self) -> &[u8; OAM_SIZE] {
+        &self.oam
+    }
+
+    /// Write OAMDATA, storing the value in the OAM at OAMADDR, which is then incremented.
+    ///
+    /// The third byte of every sprite, its attributes, lacks the bits 2 to 4.
+    fn write_oam_data(&mut self, value: u8) {
+        let value = match self.oam_address % 4 {
+            2 => value & !OAM_ATTRIBUTE_MISSING_BITS,
+            _ => value,
+        };
+
+        self.oam[self.oam_address as usize] = value;
+        self.oam_address = self.oam_address.wrapping_add(1);
+    }
+
+    /// Write to PPUSCROLL, the X scroll first and the Y scroll second.
+    fn write_scroll(&mut self, value: u8) {
+        let value = value as u16;
+
+        match self.write_latch {
+            false => {
+                self.temporary_address = (self.temporary_address & !COARSE_X_BITS) | (value >> 3);
+                self.fine_x = value as u8 & 0x07;
+            }
+
+            // The fine Y scroll lands on the bits 12 to 14, and the coarse one on the bits 5 to 9
+            true => {
+                self.temporary_address =
+                    (self.temporary_address & !Y_SCROLL_BITS) | ((value & 0x07) << 12) | ((value & 0xF8) << 2);
+            }
+        }
+
+        self.write_latch = !self.write_latch;
+    }
+
+    /// Write to PPUADDR, the upper byte of the address first and the lower one second, which
+    /// copies the temporary address to the current one.
+    fn write_address(&mut self, value: u8) {
+        let value = value as u16;
+
+        match self.write_latch {
+            // Only 6 bits are written, and the bit 14 is cleared
+            false => self.temporary_address = (self.temporary_address & 0x00FF) | ((value & 0x3F) << 8),
+            true => {
+                self.temporary_address = (self.temporary_address & 0xFF00) | value;
+                self.vram_address = self.temporary_address & LOOPY_BITS;
+            }
+        }
+
+        self.write_latch = !self.write_latch;
+    }
+}
+
+impl Default for Ppu {
+    fn default() -> Self {
+        Ppu::new()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) use crate::cartridge::testing::FlatCartridge;
+
+    /// The color of the backdrop of the tests, a black.
+    pub(crate) const BACKDROP: u8 = 0x0F;
+
+    /// Run the PPU for two whole frames, the first one setting up the scroll on its pre-render
+    /// scanline.
+    pub(crate) fn render_two_frames(ppu: &mut Ppu, cartridge: &FlatCartridge) {
+        for _ in 0..2 * DOTS_PER_SCANLINE as usize * SCANLINES_PER_FRAME as usize {
+            ppu.step(cartridge);
+        }
+    }
+
+    /// Run the PPU until the given dot of the given scanline is the next one.
+    pub(crate) fn run_until(ppu: &mut Ppu, cartridge: &FlatCartridge, scanline: u16, dot: u16) {
+        while (ppu.scanline(), ppu.dot()) != (scanline, dot) {
+            ppu.step(cartridge);
+        }
+    }
+
+    #[test]
+    fn test_register_number() {
+        assert_eq!(Ppu::register_number(0x2000), 0);
+        assert_eq!(Ppu::register_number(0x2007), 7);
+        assert_eq!(Ppu::register_number(0x2008), 0);
+        assert_eq!(Ppu::register_number(0x3FFA), 2);
+        assert_eq!(Ppu::register_number(0x3FFF), 7);
+    }
+
+    #[test]
+    fn test_ppuctrl_nametable_in_t() {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+        ppu.write(0x2000, 0b1000_0011, &mut cartridge);
+
+        assert_eq!(ppu.temporary_address(), 0x0C00);
+        assert!(ppu.ctrl().contains(PpuCtrl::Nmi));
+
+        ppu.write(0x2000, 0b0000_0001, &mut cartridge);
+        assert_eq!(ppu.temporary_address(), 0x0400);
+
+        // The current address is left as it is
+        assert_eq!(ppu.vram_address(), 0x0000);
+    }
+
+    #[test]
+    fn test_ppuscroll_writes() {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+
+        // X = 125, Y = 94
+        ppu.write(0x2005, 0x7D, &mut cartridge);
+        assert_eq!(ppu.temporary_address(), 0x000F);
+        assert_eq!(ppu.fine_x(), 0x05);
+        assert!(ppu.write_latch());
+
+        ppu.write(0x2005, 0x5E, &mut cartridge);
+        assert_eq!(ppu.temporary_address(), 0x616F);
+        assert!(!ppu.write_latch());
+    }
+
+    #[test]
+    fn test_ppuaddr_writes() {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+
+        // The bits 6 and 7 of the upper byte are dropped
+        ppu.write(0x2006, 0xFD, &mut cartridge);
+        assert_eq!(ppu.temporary_address(), 0x3D00);
+        assert_eq!(ppu.vram_address(), 0x0000);
+
+        ppu.write(0x2006, 0x42, &mut cartridge);
+        assert_eq!(ppu.temporary_address(), 0x3D42);
+        assert_eq!(ppu.vram_address(), 0x3D42);
+    }
+
+    #[test]
+    fn test_shared_write_latch() {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+
+        // The first PPUADDR write leaves the latch set for PPUSCROLL, which takes the Y scroll
+        ppu.write(0x2006, 0x04, &mut cartridge);
+        ppu.write(0x2005, 0xFF, &mut cartridge);
+        assert!(!ppu.write_latch());
+        assert_eq!(ppu.temporary_address(), 0x77E0);
+
+        // Reading PPUSTATUS resets the latch
+        ppu.write(0x2006, 0x21, &mut cartridge);
+        ppu.read(0x2002, &cartridge);
+        ppu.write(0x2006, 0x23, &mut cartridge);
+        ppu.write(0x2006, 0xC0, &mut cartridge);
+        assert_eq!(ppu.vram_address(), 0x23C0);
+    }
+
+    #[test]
+    fn test_register_readability() {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+
+        // The write-only registers read the last value on the data bus
+        ppu.write(0x2001, 0x1E, &mut cartridge);
+        assert_eq!(ppu.mask().bits(), 0x1E);
+        assert_eq!(ppu.read(0x2000, &cartridge), 0x1E);
+        assert_eq!(ppu.read(0x2006, &cartridge), 0x1E);
+
+        // PPUSTATUS is read-only, with its lower bits from the data bus
+        ppu.status = PpuStatus::VBlank;
+        ppu.write(0x2002, 0x5F, &mut cartridge);
+        assert_eq!(ppu.peek(0x2002), 0x9F);
+        assert_eq!(ppu.status(), PpuStatus::VBlank);
+    }
+
+    #[test]
+    fn test_oamdata() {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+        ppu.write(0x2003, 0xFE, &mut cartridge);
+        ppu.write(0x2004, 0x11, &mut cartridge);
+        ppu.write(0x2004, 0x22, &mut cartridge);
+
+        // The address wraps around the OAM, and $FE is an attribute byte
+        assert_eq!(ppu.oam_address(), 0x00);
+        assert_eq!(ppu.oam()[0xFE..], [0x01, 0x22]);
+
+        ppu.write(0x2003, 0xFF, &mut cartridge);
+        assert_eq!(ppu.read(0x2004, &cartridge), 0x22);
+
+        // Reading doesn't increment the address
+        assert_eq!(ppu.oam_address(), 0xFF);
+    }
+
+    #[test]
+    fn test_oam_attribute_bits() {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+
+        ppu.write(0x2003, 0x80, &mut cartridge);
+
+        for _ in 0..8 {
+            ppu.write(0x2004, 0xFF, &mut cartridge);
+        }
+
+        // The attribute byte of every sprite lacks the bits 2 to 4
+        assert_eq!(ppu.oam()[0x80..0x88], [0xFF, 0xFF, 0xE3, 0xFF, 0xFF, 0xFF, 0xE3, 0xFF]);
+
+        ppu.write(0x2003, 0x86, &mut cartridge);
+        assert_eq!(ppu.read(0x2004, &cartridge), 0xE3);
+    }
+}
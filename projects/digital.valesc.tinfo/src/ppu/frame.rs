@@ -0,0 +1,122 @@
+//! Holds the picture drawn by the PPU, along with the palette of the NTSC consoles turning its
+//! colors into RGB.
+
+/// The number of pixels of every line of the picture.
+pub const FRAME_WIDTH: usize = 256;
+
+/// The number of lines of the picture, one for every visible scanline.
+pub const FRAME_HEIGHT: usize = 240;
+
+/// The color the frames are filled with before the PPU draws them, a black.
+const BLANK_COLOR: u8 = 0x0F;
+
+/// The RGB values of the 64 colors the 2C02 outputs, by their index in the NES palette.
+///
+/// The colors emphasized by PPUMASK are not covered.
+pub const NTSC_PALETTE: [[u8; 3]; 64] = [
+    [84, 84, 84], [0, 30, 116], [8, 16, 144], [48, 0, 136],
+    [68, 0, 100], [92, 0, 48], [84, 4, 0], [60, 24, 0],
+    [32, 42, 0], [8, 58, 0], [0, 64, 0], [0, 60, 0],
+    [0, 50, 60], [0, 0, 0], [0, 0, 0], [0, 0, 0],
+
+    [152, 150, 152], [8, 76, 196], [48, 50, 236], [92, 30, 228],
+    [136, 20, 176], [160, 20, 100], [152, 34, 32], [120, 60, 0],
+    [84, 90, 0], [40, 114, 0], [8, 124, 0], [0, 118, 40],
+    [0, 102, 120], [0, 0, 0], [0, 0, 0], [0, 0, 0],
+
+    [236, 238, 236], [76, 154, 236], [120, 124, 236], [176, 98, 236],
+    [228, 84, 236], [236, 88, 180], [236, 106, 100], [212, 136, 32],
+    [160, 170, 0], [116, 196, 0], [76, 208, 32], [56, 204, 108],
+    [56, 180, 204], [60, 60, 60], [0, 0, 0], [0, 0, 0],
+
+    [236, 238, 236], [168, 204, 236], [188, 188, 236], [212, 178, 236],
+    [236, 174, 236], [236, 174, 212], [236, 180, 176], [228, 196, 144],
+    [204, 210, 120], [180, 222, 120], [168, 226, 144], [152, 226, 180],
+    [160, 214, 228], [160, 162, 160], [0, 0, 0], [0, 0, 0],
+];
+
+/// A picture drawn by the PPU, [FRAME_WIDTH] by [FRAME_HEIGHT] pixels, holding the index of the
+/// color of every pixel in the NES palette.
+///
+/// The colors are turned into RGB with [NTSC_PALETTE].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frame {
+    /// The index of the color of every pixel, line by line from the top left.
+    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    pixels: Vec<u8>,
+}
+
+impl Frame {
+    /// Create a new [Frame] filled with black.
+    pub fn new() -> Frame {
+        Frame {
+            pixels: vec![BLANK_COLOR; FRAME_WIDTH * FRAME_HEIGHT],
+        }
+    }
+
+    /// Get the index of the color of the pixel at the given position in the NES palette.
+    ///
+    /// # Panics
+    /// If the position is outside of the frame.
+    pub fn palette_index(&self, x: usize, y: usize) -> u8 {
+        self.pixels[Frame::offset(x, y)]
+    }
+
+    /// Get the indexes of the colors of all the pixels in the NES palette, line by line from the
+    /// top left.
+    pub fn palette_indices(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Get the RGB value of the pixel at the given position.
+    ///
+    /// # Panics
+    /// If the position is outside of the frame.
+    pub fn rgb(&self, x: usize, y: usize) -> [u8; 3] {
+        NTSC_PALETTE[self.palette_index(x, y) as usize]
+    }
+
+    /// Get the RGB values of all the pixels, three bytes each, line by line from the top left,
+    /// like the textures of the frontends expect.
+    pub fn to_rgb(&self) -> Vec<u8> {
+        self.pixels.iter().flat_map(|&color| NTSC_PALETTE[color as usize]).collect()
+    }
+
+    /// Set the index of the color of the pixel at the given position.
+    pub(super) fn set_palette_index(&mut self, x: usize, y: usize, color: u8) {
+        self.pixels[Frame::offset(x, y)] = color;
+    }
+
+    /// Get the offset of the pixel at the given position.
+    fn offset(x: usize, y: usize) -> usize {
+        assert!(x < FRAME_WIDTH && y < FRAME_HEIGHT, "The pixel ({x}, {y}) is outside of the frame");
+
+        y * FRAME_WIDTH + x
+    }
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Frame::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_colors() {
+        let mut frame = Frame::new();
+        frame.set_palette_index(255, 239, 0x30);
+
+        assert_eq!(frame.palette_index(0, 0), BLANK_COLOR);
+        assert_eq!(frame.rgb(255, 239), [236, 238, 236]);
+
+        let rgb = frame.to_rgb();
+        assert_eq!(rgb.len(), FRAME_WIDTH * FRAME_HEIGHT * 3);
+        assert_eq!(rgb[..3], [0, 0, 0]);
+        assert_eq!(rgb[rgb.len() - 3..], [236, 238, 236]);
+    }
+}
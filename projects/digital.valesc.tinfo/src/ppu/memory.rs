@@ -0,0 +1,369 @@
+//! Holds the memory map of the PPU, with the pattern tables on the cartridge and the nametables
+//! and the palettes on the PPU, along with PPUDATA, the way the CPU accesses it.
+
+use crate::cartridge::{Cartridge, Mirroring};
+use crate::ppu::{Ppu, PpuCtrl, PpuMask, LOOPY_BITS};
+use crate::BYTES_ON_A_KIBIBYTE;
+
+/// The number of bytes of the VRAM of the PPU, holding two nametables.
+pub const VRAM_SIZE: usize = 2 * BYTES_ON_A_KIBIBYTE;
+
+/// The number of bytes of a nametable, with its attribute table.
+const NAMETABLE_SIZE: usize = BYTES_ON_A_KIBIBYTE;
+
+/// The bits of the addresses of the nametables telling which of the four is accessed.
+const NAMETABLE_SELECT_BITS: u16 = 0b0000_1100_0000_0000;
+
+/// The bits of the addresses of the nametables telling the byte accessed in the nametable.
+const NAMETABLE_OFFSET_BITS: u16 = 0b0000_0011_1111_1111;
+
+/// The number of bytes of the palette RAM.
+pub const PALETTE_SIZE: usize = 32;
+
+/// The bits of the addresses of the PPU memory map, which is 14 bits wide.
+const PPU_ADDRESS_BITS: u16 = 0x3FFF;
+
+/// The last address of the pattern tables, mapped to the cartridge.
+const PATTERN_TABLES_END_ADDRESS: u16 = 0x1FFF;
+
+/// The first address of the nametables, mirrored up to `$3EFF`.
+pub(super) const NAMETABLES_START_ADDRESS: u16 = 0x2000;
+
+/// The last address of the mirrors of the nametables.
+const NAMETABLES_END_ADDRESS: u16 = 0x3EFF;
+
+/// The bits of the addresses of the mirrors of the nametables, mirroring `$3000` to `$3EFF` down
+/// to `$2000` to `$2EFF`.
+const NAMETABLES_MIRROR_BITS: u16 = 0x2FFF;
+
+/// The first address of the palettes, mirrored up to `$3FFF`.
+pub(super) const PALETTES_START_ADDRESS: u16 = 0x3F00;
+
+/// The distance between the palettes and the mirror of the nametables they hide.
+const PALETTES_NAMETABLES_DISTANCE: u16 = 0x1000;
+
+/// The bits stored by every byte of the palette RAM, the other ones come from the data bus when
+/// it's read.
+const PALETTE_BITS: u8 = 0b0011_1111;
+
+/// The bits of the palette bytes kept by the greyscale mode, the grey column of every row.
+pub(super) const GREYSCALE_BITS: u8 = 0b0011_0000;
+
+/// The bits of the indexes of the palette RAM telling the first color of a sprite palette, which
+/// is the same cell as the first color of the background palette.
+const SPRITE_BACKDROP_BITS: usize = 0b1_0011;
+
+/// The bit of the indexes of the palette RAM telling the sprite palettes.
+const SPRITE_PALETTES_BIT: usize = 0b1_0000;
+
+impl Ppu {
+    /// Read PPUDATA, returning the byte buffered by the previous read and buffering the one at the
+    /// VRAM address, which is then incremented.
+    ///
+    /// The palettes are read right away instead, while the buffer takes the byte of the mirror of
+    /// the nametables they hide.
+    pub(super) fn read_data<C: Cartridge>(&mut self, cartridge: &C) -> u8 {
+        let value = self.peek_data();
+        let address = self.vram_address & PPU_ADDRESS_BITS;
+
+        let buffered_address = match address >= PALETTES_START_ADDRESS {
+            true => address - PALETTES_NAMETABLES_DISTANCE,
+            false => address,
+        };
+
+        self.read_buffer = self.read_memory(buffered_address, cartridge);
+        self.increment_vram_address();
+
+        value
+    }
+
+    /// Read PPUDATA like [Ppu::read_data] does, without side effects.
+    ///
+    /// The greyscale mode of PPUMASK applies to the palettes read.
+    pub(super) fn peek_data(&self) -> u8 {
+        let address = self.vram_address & PPU_ADDRESS_BITS;
+
+        if address < PALETTES_START_ADDRESS {
+            return self.read_buffer;
+        }
+
+        let mut color = self.palette[Ppu::palette_index(address)];
+
+        if self.mask.contains(PpuMask::Greyscale) {
+            color &= GREYSCALE_BITS;
+        }
+
+        (color & PALETTE_BITS) | (self.io_bus & !PALETTE_BITS)
+    }
+
+    /// Write PPUDATA, storing the value at the VRAM address, which is then incremented.
+    pub(super) fn write_data<C: Cartridge>(&mut self, value: u8, cartridge: &mut C) {
+        self.write_memory(self.vram_address, value, cartridge);
+        self.increment_vram_address();
+    }
+
+    /// Read the byte at the given address of the PPU memory map.
+    ///
+    /// The addresses the cartridge maps nothing to read the lower byte of the address, left on
+    /// the bus shared by the addresses and the data.
+    pub(super) fn read_memory<C: Cartridge>(&self, address: u16, cartridge: &C) -> u8 {
+        let address = address & PPU_ADDRESS_BITS;
+
+        match address {
+            0x0000..=PATTERN_TABLES_END_ADDRESS => unsafe { cartridge.ppu_read(address) }.unwrap_or(address as u8),
+
+            NAMETABLES_START_ADDRESS..=NAMETABLES_END_ADDRESS => match cartridge.mirroring() {
+                Mirroring::FourScreen => {
+                    let address = address & NAMETABLES_MIRROR_BITS;
+
+                    unsafe { cartridge.ppu_read(address) }.unwrap_or(address as u8)
+                }
+
+                mirroring => self.vram[Ppu::vram_index(address, mirroring)],
+            },
+
+            _ => self.palette[Ppu::palette_index(address)],
+        }
+    }
+
+    /// Write a value to the given address of the PPU memory map.
+    ///
+    /// The writes to the CHR ROM of the cartridge are ignored.
+    pub(super) fn write_memory<C: Cartridge>(&mut self, address: u16, value: u8, cartridge: &mut C) {
+        let address = address & PPU_ADDRESS_BITS;
+
+        match address {
+            0x0000..=PATTERN_TABLES_END_ADDRESS => {
+                let _ = unsafe { cartridge.ppu_write(address, value) };
+            }
+
+            NAMETABLES_START_ADDRESS..=NAMETABLES_END_ADDRESS => match cartridge.mirroring() {
+                Mirroring::FourScreen => {
+                    let _ = unsafe { cartridge.ppu_write(address & NAMETABLES_MIRROR_BITS, value) };
+                }
+
+                mirroring => self.vram[Ppu::vram_index(address, mirroring)] = value,
+            },
+
+            _ => self.palette[Ppu::palette_index(address)] = value & PALETTE_BITS,
+        }
+    }
+
+    /// Increment the VRAM address after an access to PPUDATA, by 32 or 1 as PPUCTRL tells.
+    fn increment_vram_address(&mut self) {
+        let increment = match self.ctrl.contains(PpuCtrl::VramIncrement32) {
+            true => 32,
+            false => 1,
+        };
+
+        self.vram_address = self.vram_address.wrapping_add(increment) & LOOPY_BITS;
+    }
+
+    /// Get the index of the VRAM holding the nametable byte at the given address, given the
+    /// layout of the nametables, which can't be [Mirroring::FourScreen].
+    fn vram_index(address: u16, mirroring: Mirroring) -> usize {
+        let nametable = (address & NAMETABLE_SELECT_BITS) >> 10;
+
+        let table = match mirroring {
+            Mirroring::Horizontal => nametable >> 1,
+            Mirroring::Vertical => nametable & 1,
+            Mirroring::SingleScreenLow => 0,
+            Mirroring::SingleScreenHigh => 1,
+            Mirroring::FourScreen => unreachable!("The four nametables are on the cartridge"),
+        };
+
+        table as usize * NAMETABLE_SIZE + (address & NAMETABLE_OFFSET_BITS) as usize
+    }
+
+    /// Get the index of the palette RAM holding the palette byte at the given address.
+    ///
+    /// The first color of every sprite palette is the same cell as the one of the background
+    /// palette above it, so `$3F10`, `$3F14`, `$3F18` and `$3F1C` alias `$3F00`, `$3F04`, `$3F08`
+    /// and `$3F0C`.
+    pub(super) fn palette_index(address: u16) -> usize {
+        let index = address as usize % PALETTE_SIZE;
+
+        match index & SPRITE_BACKDROP_BITS == SPRITE_PALETTES_BIT {
+            true => index & !SPRITE_PALETTES_BIT,
+            false => index,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cartridge::testing::FlatCartridge;
+    use crate::cartridge::{Cartridge, Mirroring};
+    use crate::ppu::Ppu;
+
+    /// Point the VRAM address to the given one through PPUADDR.
+    fn set_vram_address(ppu: &mut Ppu, cartridge: &mut FlatCartridge, address: u16) {
+        ppu.write(0x2006, (address >> 8) as u8, cartridge);
+        ppu.write(0x2006, address as u8, cartridge);
+    }
+
+    #[test]
+    fn test_read_buffer_delay() {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+
+        set_vram_address(&mut ppu, &mut cartridge, 0x2400);
+
+        for value in [0x11, 0x22, 0x33] {
+            ppu.write(0x2007, value, &mut cartridge);
+        }
+
+        assert_eq!(ppu.vram_address(), 0x2403);
+
+        // The first read returns the stale buffer, every other one the byte before
+        set_vram_address(&mut ppu, &mut cartridge, 0x2400);
+        let values: Vec<u8> = (0..4).map(|_| ppu.read(0x2007, &cartridge)).collect();
+        assert_eq!(values, [0x00, 0x11, 0x22, 0x33]);
+
+        // The pattern tables are on the cartridge
+        set_vram_address(&mut ppu, &mut cartridge, 0x1FFF);
+        ppu.write(0x2007, 0x44, &mut cartridge);
+        set_vram_address(&mut ppu, &mut cartridge, 0x1FFF);
+        ppu.read(0x2007, &cartridge);
+        assert_eq!(ppu.peek(0x2007), 0x44);
+    }
+
+    #[test]
+    fn test_increment_by_32() {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+
+        ppu.write(0x2000, 0b0000_0100, &mut cartridge);
+        set_vram_address(&mut ppu, &mut cartridge, 0x2000);
+
+        for value in [0x01, 0x02, 0x03] {
+            ppu.write(0x2007, value, &mut cartridge);
+        }
+
+        // The values go down a column of the nametable
+        assert_eq!(ppu.vram_address(), 0x2060);
+        assert_eq!(ppu.vram[0x000..0x002], [0x01, 0x00]);
+        assert_eq!(ppu.vram[0x020], 0x02);
+        assert_eq!(ppu.vram[0x040], 0x03);
+
+        // The reads increment the address the same way
+        ppu.read(0x2007, &cartridge);
+        assert_eq!(ppu.vram_address(), 0x2080);
+    }
+
+    #[test]
+    fn test_palette_reads_bypass_buffer() {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+
+        // The nametable byte hidden by the palette entry
+        set_vram_address(&mut ppu, &mut cartridge, 0x2F01);
+        ppu.write(0x2007, 0x5A, &mut cartridge);
+
+        set_vram_address(&mut ppu, &mut cartridge, 0x3F01);
+        ppu.write(0x2007, 0x2C, &mut cartridge);
+
+        // The palette entry is read right away, with its upper bits from the data bus
+        set_vram_address(&mut ppu, &mut cartridge, 0x3F01);
+        ppu.write(0x2001, 0xC0, &mut cartridge);
+        assert_eq!(ppu.read(0x2007, &cartridge), 0xEC);
+
+        // While the buffer took the nametable byte
+        set_vram_address(&mut ppu, &mut cartridge, 0x2000);
+        assert_eq!(ppu.read(0x2007, &cartridge), 0x5A);
+
+        // The palettes are mirrored up to $3FFF
+        set_vram_address(&mut ppu, &mut cartridge, 0x3FE1);
+        assert_eq!(ppu.read(0x2007, &cartridge) & 0x3F, 0x2C);
+    }
+
+    #[test]
+    fn test_nametable_mirroring() {
+        let cases = [
+            // Whether the byte at $2000 is seen at $2400, $2800 and $2C00
+            (Mirroring::Horizontal, [true, false, false]),
+            (Mirroring::Vertical, [false, true, false]),
+            (Mirroring::SingleScreenLow, [true, true, true]),
+            (Mirroring::SingleScreenHigh, [true, true, true]),
+            (Mirroring::FourScreen, [false, false, false]),
+        ];
+
+        for (mirroring, seen) in cases {
+            let mut ppu = Ppu::new();
+            let mut cartridge = FlatCartridge::new(vec![]).with_mirroring(mirroring);
+
+            set_vram_address(&mut ppu, &mut cartridge, 0x2000);
+            ppu.write(0x2007, 0x42, &mut cartridge);
+
+            for (address, seen) in [0x2400, 0x2800, 0x2C00].into_iter().zip(seen) {
+                assert_eq!(ppu.read_memory(address, &cartridge) == 0x42, seen, "{mirroring:?} at ${address:04X}");
+            }
+
+            // The range from $3000 mirrors the nametables
+            assert_eq!(ppu.read_memory(0x3000, &cartridge), 0x42, "{mirroring:?}");
+        }
+
+        // The single screens use distinct tables
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]).with_mirroring(Mirroring::SingleScreenHigh);
+        set_vram_address(&mut ppu, &mut cartridge, 0x2C05);
+        ppu.write(0x2007, 0x24, &mut cartridge);
+        assert_eq!(ppu.vram[0x405], 0x24);
+
+        // And the four screens land on the cartridge
+        let mut cartridge = FlatCartridge::new(vec![]).with_mirroring(Mirroring::FourScreen);
+        set_vram_address(&mut ppu, &mut cartridge, 0x2C05);
+        ppu.write(0x2007, 0x99, &mut cartridge);
+        assert_eq!(unsafe { cartridge.ppu_read(0x2C05) }.unwrap(), 0x99);
+        assert_eq!(ppu.vram[0x405], 0x24);
+    }
+
+    #[test]
+    fn test_palette_backdrop_aliases() {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+
+        // The sprite backdrops write the background ones
+        for (index, address) in [0x3F10, 0x3F14, 0x3F18, 0x3F1C].into_iter().enumerate() {
+            set_vram_address(&mut ppu, &mut cartridge, address);
+            ppu.write(0x2007, 0x20 + index as u8, &mut cartridge);
+        }
+
+        let palette = ppu.palette();
+        assert_eq!([0x00, 0x04, 0x08, 0x0C].map(|index| palette[index]), [0x20, 0x21, 0x22, 0x23]);
+
+        // And the background backdrops write the sprite ones
+        set_vram_address(&mut ppu, &mut cartridge, 0x3F08);
+        ppu.write(0x2007, 0x0F, &mut cartridge);
+
+        set_vram_address(&mut ppu, &mut cartridge, 0x3F18);
+        assert_eq!(ppu.read(0x2007, &cartridge), 0x0F);
+        assert_eq!(ppu.palette()[0x18], 0x0F);
+
+        // The other colors of the sprite palettes have their own cells
+        set_vram_address(&mut ppu, &mut cartridge, 0x3F11);
+        ppu.write(0x2007, 0x16, &mut cartridge);
+        assert_eq!(ppu.palette()[0x01], 0x00);
+        assert_eq!(ppu.palette()[0x11], 0x16);
+    }
+
+    #[test]
+    fn test_palette_greyscale_reads() {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+
+        set_vram_address(&mut ppu, &mut cartridge, 0x3F05);
+        ppu.write(0x2007, 0x2C, &mut cartridge);
+
+        ppu.write(0x2001, 0b0000_0001, &mut cartridge);
+        set_vram_address(&mut ppu, &mut cartridge, 0x3F05);
+        assert_eq!(ppu.read(0x2007, &cartridge), 0x20);
+
+        // The palette RAM keeps the color
+        assert_eq!(ppu.palette()[0x05], 0x2C);
+
+        ppu.write(0x2001, 0b0000_0000, &mut cartridge);
+        set_vram_address(&mut ppu, &mut cartridge, 0x3F05);
+        assert_eq!(ppu.read(0x2007, &cartridge), 0x2C);
+    }
+}
@@ -0,0 +1,415 @@
+//! Holds the rendering of the PPU, fetching the tiles of the background dot by dot with the loopy
+//! registers and drawing the pixels into the frame.
+
+use crate::cartridge::Cartridge;
+use crate::ppu::frame::{FRAME_HEIGHT, FRAME_WIDTH};
+use crate::ppu::memory::{GREYSCALE_BITS, NAMETABLES_START_ADDRESS, PALETTES_START_ADDRESS};
+use crate::ppu::timing::PRE_RENDER_SCANLINE;
+use crate::ppu::{Ppu, PpuCtrl, PpuMask, PpuStatus, COARSE_X_BITS, NAMETABLE_BITS, Y_SCROLL_BITS};
+
+/// The address of the first attribute table, right after the tiles of the first nametable.
+const ATTRIBUTE_TABLE_ADDRESS: u16 = 0x23C0;
+
+/// The address of the second pattern table.
+pub(super) const SECOND_PATTERN_TABLE_ADDRESS: u16 = 0x1000;
+
+/// The number of bytes of a tile of a pattern table, its low plane followed by its high one.
+pub(super) const PATTERN_TILE_SIZE: u16 = 16;
+
+/// The bits of the loopy registers holding the fine Y scroll.
+const FINE_Y_BITS: u16 = 0b111_0000_0000_0000;
+
+/// The bits of the loopy registers holding the coarse Y scroll.
+const COARSE_Y_BITS: u16 = 0b000_0011_1110_0000;
+
+/// The bit of the loopy registers selecting the nametables on the right.
+const NAMETABLE_X_BIT: u16 = 0b000_0100_0000_0000;
+
+/// The bit of the loopy registers selecting the nametables on the bottom.
+const NAMETABLE_Y_BIT: u16 = 0b000_1000_0000_0000;
+
+/// The bits of the loopy registers copied from `t` to `v` at the end of every scanline.
+const HORIZONTAL_BITS: u16 = COARSE_X_BITS | NAMETABLE_X_BIT;
+
+/// The bits of the loopy registers copied from `t` to `v` during the pre-render scanline.
+const VERTICAL_BITS: u16 = Y_SCROLL_BITS | NAMETABLE_Y_BIT;
+
+/// The number of rows of tiles of a nametable, the rows below being its attribute table.
+const NAMETABLE_ROWS: u16 = 30;
+
+/// The number of columns of pixels clipped on the left of the screen by PPUMASK.
+pub(super) const LEFT_CLIP_WIDTH: usize = 8;
+
+/// The state of the pipeline fetching the tiles of the background, two tiles ahead of the pixels
+/// drawn.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(super) struct Background {
+    /// The nametable byte of the next tile, the index of its pattern.
+    next_tile: u8,
+
+    /// The palette of the next tile, taken from its attribute byte.
+    next_palette: u8,
+
+    /// The low plane of the row of the pattern of the next tile.
+    next_pattern_low: u8,
+
+    /// The high plane of the row of the pattern of the next tile.
+    next_pattern_high: u8,
+
+    /// The low and high planes of the patterns of the tile drawn and the next one, the pixel drawn
+    /// being on the bit 15 moved right by the fine X scroll.
+    pattern_shifts: [u16; 2],
+
+    /// The low and high bits of the palette of the tile drawn and the next one, spread to match
+    /// [Background::pattern_shifts].
+    palette_shifts: [u16; 2],
+}
+
+impl Background {
+    /// Move the shift registers on to the next pixel.
+    fn shift(&mut self) {
+        for shift in self.pattern_shifts.iter_mut().chain(self.palette_shifts.iter_mut()) {
+            *shift <<= 1;
+        }
+    }
+
+    /// Load the next tile into the lower bytes of the shift registers.
+    fn load_next_tile(&mut self) {
+        let planes = [self.next_pattern_low, self.next_pattern_high];
+
+        for (bit, (pattern_shift, palette_shift)) in
+            self.pattern_shifts.iter_mut().zip(self.palette_shifts.iter_mut()).enumerate()
+        {
+            let palette_bits = match self.next_palette >> bit & 1 {
+                1 => 0xFF,
+                _ => 0x00,
+            };
+
+            *pattern_shift = (*pattern_shift & 0xFF00) | planes[bit] as u16;
+            *palette_shift = (*palette_shift & 0xFF00) | palette_bits;
+        }
+    }
+
+    /// Get the color of the pixel drawn, from 0 to 3, and its palette, given the fine X scroll.
+    fn pixel(&self, fine_x: u8) -> (u8, u8) {
+        let bit = 15 - fine_x;
+        let two_bits = |shifts: [u16; 2]| ((shifts[1] >> bit & 1) << 1 | (shifts[0] >> bit & 1)) as u8;
+
+        (two_bits(self.pattern_shifts), two_bits(self.palette_shifts))
+    }
+}
+
+impl Ppu {
+    /// Tell if the background or the sprites are rendered, which moves the loopy registers.
+    pub fn rendering_enabled(&self) -> bool {
+        self.mask.intersects(PpuMask::ShowBackground | PpuMask::ShowSprites)
+    }
+
+    /// Run the rendering of the dot the PPU is at, fetching the tiles of the background on the
+    /// visible and pre-render scanlines, evaluating the sprites of the next scanline on the dot 257
+    /// of the visible ones and drawing their pixels.
+    ///
+    /// The tiles are fetched in eight dots each, the nametable byte first, then the attribute byte
+    /// and the two planes of the pattern, from the dot 1 to 256 and again from 321 to 336 for the
+    /// first two tiles of the next scanline. The coarse X scroll of `v` moves on after every tile,
+    /// the Y scroll on the dot 256, and the horizontal scroll is copied back from `t` on the dot
+    /// 257, the vertical one from the dot 280 to 304 of the pre-render scanline.
+    pub(super) fn render_dot<C: Cartridge>(&mut self, cartridge: &C) {
+        let visible = (self.scanline as usize) < FRAME_HEIGHT;
+
+        if self.rendering_enabled() && (visible || self.scanline == PRE_RENDER_SCANLINE) {
+            self.fetch_background(cartridge);
+        }
+
+        if self.dot == 257 {
+            match self.rendering_enabled() && visible {
+                true => self.evaluate_sprites(cartridge),
+                false => self.clear_sprites(),
+            }
+        }
+
+        if visible && (1..=256).contains(&self.dot) {
+            self.draw_pixel();
+        }
+    }
+
+    /// Run the fetches of the background and the moves of `v` of the dot the PPU is at.
+    fn fetch_background<C: Cartridge>(&mut self, cartridge: &C) {
+        let dot = self.dot;
+
+        if (2..=256).contains(&dot) || (321..=337).contains(&dot) {
+            self.background.shift();
+
+            match (dot - 1) % 8 {
+                0 => {
+                    self.background.load_next_tile();
+
+                    let address = NAMETABLES_START_ADDRESS | (self.vram_address & 0x0FFF);
+                    self.background.next_tile = self.read_memory(address, cartridge);
+                }
+
+                2 => self.fetch_attribute(cartridge),
+                4 => self.background.next_pattern_low = self.read_memory(self.pattern_address(), cartridge),
+                6 => self.background.next_pattern_high = self.read_memory(self.pattern_address() + 8, cartridge),
+                7 => self.increment_coarse_x(),
+                _ => {}
+            }
+        }
+
+        match dot {
+            256 => self.increment_y(),
+
+            257 => {
+                self.background.load_next_tile();
+                self.copy_bits_from_temporary_address(HORIZONTAL_BITS);
+            }
+
+            280..=304 if self.scanline == PRE_RENDER_SCANLINE => self.copy_bits_from_temporary_address(VERTICAL_BITS),
+
+            _ => {}
+        }
+    }
+
+    /// Fetch the attribute byte of the next tile, keeping the palette of its quadrant of 2x2 tiles.
+    fn fetch_attribute<C: Cartridge>(&mut self, cartridge: &C) {
+        let v = self.vram_address;
+        let address = ATTRIBUTE_TABLE_ADDRESS | (v & NAMETABLE_BITS) | ((v >> 4) & 0x38) | ((v >> 2) & 0x07);
+        let attribute = self.read_memory(address, cartridge);
+
+        // The quadrant is told by the bit 1 of the coarse X and Y scrolls
+        let shift = ((v >> 4) & 0x04) | (v & 0x02);
+
+        self.background.next_palette = (attribute >> shift) & 0x03;
+    }
+
+    /// Get the address of the low plane of the row of the pattern of the next tile.
+    fn pattern_address(&self) -> u16 {
+        let table = match self.ctrl.contains(PpuCtrl::BackgroundPatternTable) {
+            true => SECOND_PATTERN_TABLE_ADDRESS,
+            false => 0x0000,
+        };
+
+        let fine_y = (self.vram_address & FINE_Y_BITS) >> 12;
+
+        table + self.background.next_tile as u16 * PATTERN_TILE_SIZE + fine_y
+    }
+
+    /// Move `v` to the next tile on the right, wrapping to the next horizontal nametable.
+    fn increment_coarse_x(&mut self) {
+        match self.vram_address & COARSE_X_BITS == COARSE_X_BITS {
+            true => self.vram_address = (self.vram_address & !COARSE_X_BITS) ^ NAMETABLE_X_BIT,
+            false => self.vram_address += 1,
+        }
+    }
+
+    /// Move `v` to the next row of pixels, wrapping to the next vertical nametable after the last
+    /// row of tiles.
+    fn increment_y(&mut self) {
+        if self.vram_address & FINE_Y_BITS != FINE_Y_BITS {
+            self.vram_address += 1 << 12;
+            return;
+        }
+
+        self.vram_address &= !FINE_Y_BITS;
+
+        let coarse_y = (self.vram_address & COARSE_Y_BITS) >> 5;
+
+        // The rows past the tiles, in the attribute table, wrap without changing the nametable
+        let coarse_y = match coarse_y {
+            _ if coarse_y == NAMETABLE_ROWS - 1 => {
+                self.vram_address ^= NAMETABLE_Y_BIT;
+                0
+            }
+
+            31 => 0,
+            _ => coarse_y + 1,
+        };
+
+        self.vram_address = (self.vram_address & !COARSE_Y_BITS) | (coarse_y << 5);
+    }
+
+    /// Copy the given bits of `t` to `v`.
+    fn copy_bits_from_temporary_address(&mut self, bits: u16) {
+        self.vram_address = (self.vram_address & !bits) | (self.temporary_address & bits);
+    }
+
+    /// Draw the pixel of the dot the PPU is at into the frame.
+    ///
+    /// An opaque sprite pixel is drawn over the background, unless the sprite is behind it and the
+    /// background pixel is opaque too. The transparent pixels, and all of them while both layers
+    /// are hidden, show the backdrop color.
+    ///
+    /// The sprite 0 hit flag of PPUSTATUS is set when an opaque pixel of the sprite 0 meets an
+    /// opaque pixel of the background, whatever their priority, except on the last column. Both
+    /// pixels being drawn, the hit also needs both layers shown, and none happens on the leftmost
+    /// 8 pixels while either of them is clipped there.
+    fn draw_pixel(&mut self) {
+        let x = (self.dot - 1) as usize;
+        let y = self.scanline as usize;
+
+        let clipped = x < LEFT_CLIP_WIDTH && !self.mask.contains(PpuMask::ShowBackgroundLeft);
+
+        let background = match self.mask.contains(PpuMask::ShowBackground) && !clipped {
+            true => self.background.pixel(self.fine_x),
+            false => (0, 0),
+        };
+
+        let sprite = self.sprite_pixel(x);
+
+        if let Some(sprite) = &sprite {
+            if sprite.sprite_zero && background.0 != 0 && x != FRAME_WIDTH - 1 {
+                self.status.insert(PpuStatus::Sprite0Hit);
+            }
+        }
+
+        let (pixel, palette) = match sprite {
+            Some(sprite) if background.0 == 0 || !sprite.behind_background => (sprite.pixel, sprite.palette),
+            _ => background,
+        };
+
+        let address = match pixel {
+            0 => PALETTES_START_ADDRESS,
+            _ => PALETTES_START_ADDRESS | (palette as u16) << 2 | pixel as u16,
+        };
+
+        let mut color = self.palette[Ppu::palette_index(address)];
+
+        if self.mask.contains(PpuMask::Greyscale) {
+            color &= GREYSCALE_BITS;
+        }
+
+        self.frame.set_palette_index(x, y, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cartridge::Cartridge;
+    use crate::ppu::frame::NTSC_PALETTE;
+    use crate::ppu::tests::*;
+    use crate::ppu::Ppu;
+
+    /// Create a [Ppu] and the cartridge of its pattern tables, with the tile 1 drawing the colors
+    /// 3, 3, 1, 1, 2, 2, 0, 0 on every row, placed on the first three tiles of the first row of the
+    /// first nametable, the first two with the palette 1.
+    fn background_setup() -> (Ppu, FlatCartridge) {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+
+        for row in 0..8 {
+            unsafe {
+                cartridge.ppu_write(0x0010 + row, 0b1111_0000).unwrap();
+                cartridge.ppu_write(0x0018 + row, 0b1100_1100).unwrap();
+            }
+        }
+
+        for address in [0x2000, 0x2001, 0x2002] {
+            ppu.write_memory(address, 0x01, &mut cartridge);
+        }
+
+        ppu.write_memory(0x23C0, 0b0000_0001, &mut cartridge);
+
+        let palettes = [
+            (0x3F00, BACKDROP),
+            (0x3F01, 0x01),
+            (0x3F02, 0x02),
+            (0x3F03, 0x03),
+            (0x3F05, 0x16),
+            (0x3F06, 0x27),
+            (0x3F07, 0x30),
+        ];
+
+        for (address, color) in palettes {
+            ppu.write_memory(address, color, &mut cartridge);
+        }
+
+        (ppu, cartridge)
+    }
+
+    /// Hash the colors of the frame with the 32-bit FNV-1a.
+    fn frame_hash(ppu: &Ppu) -> u32 {
+        ppu.frame()
+            .palette_indices()
+            .iter()
+            .fold(0x811C_9DC5, |hash, &color| (hash ^ color as u32).wrapping_mul(0x0100_0193))
+    }
+
+    #[test]
+    fn test_background_pixels() {
+        let (mut ppu, mut cartridge) = background_setup();
+        ppu.write(0x2001, 0b0000_1010, &mut cartridge);
+        render_two_frames(&mut ppu, &cartridge);
+
+        let frame = ppu.frame();
+        let first_row: Vec<u8> = (0..8).map(|x| frame.palette_index(x, 0)).collect();
+        assert_eq!(first_row, [0x30, 0x30, 0x16, 0x16, 0x27, 0x27, BACKDROP, BACKDROP]);
+
+        // The third tile is on the top right quadrant of the attributes, with the palette 0
+        assert_eq!(frame.palette_index(16, 7), 0x03);
+        assert_eq!(frame.palette_index(20, 0), 0x02);
+
+        // The other tiles are transparent
+        assert_eq!(frame.palette_index(0, 8), BACKDROP);
+        assert_eq!(frame.palette_index(255, 239), BACKDROP);
+
+        assert_eq!(frame.rgb(0, 0), NTSC_PALETTE[0x30]);
+        assert_eq!(frame_hash(&ppu), 0xF2D7_A035);
+    }
+
+    #[test]
+    fn test_background_left_clip_and_disable() {
+        let (mut ppu, mut cartridge) = background_setup();
+        ppu.write(0x2001, 0b0000_1000, &mut cartridge);
+        render_two_frames(&mut ppu, &cartridge);
+
+        assert_eq!(ppu.frame().palette_index(0, 0), BACKDROP);
+        assert_eq!(ppu.frame().palette_index(7, 0), BACKDROP);
+        assert_eq!(ppu.frame().palette_index(16, 0), 0x03);
+
+        // With the background hidden only the backdrop is drawn
+        ppu.write(0x2001, 0b0000_0000, &mut cartridge);
+        render_two_frames(&mut ppu, &cartridge);
+        assert!(ppu.frame().palette_indices().iter().all(|&color| color == BACKDROP));
+    }
+
+    #[test]
+    fn test_background_scroll() {
+        let (mut ppu, mut cartridge) = background_setup();
+        ppu.write(0x2001, 0b0000_1010, &mut cartridge);
+
+        // X = 2, Y = 4
+        ppu.write(0x2005, 0x02, &mut cartridge);
+        ppu.write(0x2005, 0x04, &mut cartridge);
+        render_two_frames(&mut ppu, &cartridge);
+
+        assert_eq!(ppu.frame().palette_index(0, 0), 0x16);
+        assert_eq!(ppu.frame().palette_index(0, 3), 0x16);
+        assert_eq!(ppu.frame().palette_index(0, 4), BACKDROP);
+        assert_eq!(ppu.frame().palette_index(14, 0), 0x03);
+
+        // The nametable on the right comes after the last tile, mirrored horizontally
+        ppu.write(0x2005, 0xFC, &mut cartridge);
+        ppu.write(0x2005, 0x00, &mut cartridge);
+        render_two_frames(&mut ppu, &cartridge);
+
+        assert_eq!(ppu.frame().palette_index(3, 0), BACKDROP);
+        assert_eq!(ppu.frame().palette_index(4, 0), 0x30);
+    }
+
+    #[test]
+    fn test_background_no_fetch_on_dot_257() {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+        ppu.write(0x2001, 0b0000_1010, &mut cartridge);
+
+        // The last tile fetched by the scanline, and the one after it
+        ppu.write_memory(0x2001, 0x05, &mut cartridge);
+        ppu.write_memory(0x2002, 0x06, &mut cartridge);
+
+        render_two_frames(&mut ppu, &cartridge);
+        run_until(&mut ppu, &cartridge, 0, 258);
+
+        assert_eq!(ppu.background.next_tile, 0x05);
+    }
+}
@@ -0,0 +1,506 @@
+//! Holds the sprites of the PPU, evaluated into the secondary OAM at the end of every scanline and
+//! drawn over or behind the background on the next one.
+
+use crate::cartridge::Cartridge;
+use crate::ppu::render::{LEFT_CLIP_WIDTH, PATTERN_TILE_SIZE, SECOND_PATTERN_TABLE_ADDRESS};
+use crate::ppu::{Ppu, PpuCtrl, PpuMask, PpuStatus};
+
+/// The number of sprites held by the OAM.
+const OAM_SPRITES: usize = 64;
+
+/// The number of bytes of every sprite in the OAM: its Y position, its tile, its attributes and
+/// its X position.
+const SPRITE_SIZE: usize = 4;
+
+/// The number of sprites drawn on a scanline at most.
+const SPRITES_PER_SCANLINE: usize = 8;
+
+/// The number of bytes of the secondary OAM, which holds the sprites of the next scanline.
+pub const SECONDARY_OAM_SIZE: usize = SPRITES_PER_SCANLINE * SPRITE_SIZE;
+
+/// The number of pixels of the rows of the sprites.
+const SPRITE_WIDTH: usize = 8;
+
+/// The bits of the attributes of a sprite selecting its palette.
+const PALETTE_BITS: u8 = 0b0000_0011;
+
+/// The bit of the attributes of a sprite hiding it behind the opaque pixels of the background.
+const BEHIND_BACKGROUND_BIT: u8 = 0b0010_0000;
+
+/// The bit of the attributes of a sprite flipping it horizontally.
+const FLIP_HORIZONTALLY_BIT: u8 = 0b0100_0000;
+
+/// The bit of the attributes of a sprite flipping it vertically.
+const FLIP_VERTICALLY_BIT: u8 = 0b1000_0000;
+
+/// The number of the first palette of the sprites, after the four of the background.
+const FIRST_SPRITE_PALETTE: u8 = 4;
+
+/// A sprite fetched for the scanline being drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+struct SpriteUnit {
+    /// The X position of the left column of the sprite.
+    x: u8,
+
+    /// The attributes of the sprite.
+    attributes: u8,
+
+    /// The low and high planes of the row of the pattern drawn, already flipped.
+    planes: [u8; 2],
+
+    /// If the sprite is the first one of the OAM.
+    sprite_zero: bool,
+}
+
+/// The state of the sprites of the PPU.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub(super) struct Sprites {
+    /// The sprites of the OAM found on the next scanline by the last evaluation.
+    secondary_oam: [u8; SECONDARY_OAM_SIZE],
+
+    /// The sprites fetched for the scanline being drawn, in the order of the OAM.
+    units: [SpriteUnit; SPRITES_PER_SCANLINE],
+
+    /// The number of sprites fetched for the scanline being drawn.
+    count: usize,
+}
+
+/// The pixel of a sprite drawn at a position.
+pub(super) struct SpritePixel {
+    /// The color of the pixel in its palette, from 1 to 3.
+    pub(super) pixel: u8,
+
+    /// The palette of the sprite, from 4 to 7.
+    pub(super) palette: u8,
+
+    /// If the sprite is hidden by the opaque pixels of the background.
+    pub(super) behind_background: bool,
+
+    /// If the sprite is the first one of the OAM, whose opaque pixels over opaque ones of the
+    /// background set the sprite 0 hit flag of PPUSTATUS.
+    pub(super) sprite_zero: bool,
+}
+
+impl Ppu {
+    /// Get the secondary OAM, holding the sprites found on the next scanline by the last
+    /// evaluation, four bytes each like in the OAM, unused bytes set to `$FF`.
+    pub fn secondary_oam(&self) -> &[u8; SECONDARY_OAM_SIZE] {
+        &self.sprites.secondary_oam
+    }
+
+    /// Evaluate the sprites of the next scanline and fetch their patterns, as the PPU does from
+    /// the dot 65 to 320 of every visible scanline, all at once.
+    ///
+    /// The first eight sprites of the OAM on the next scanline are kept, then the search goes on
+    /// with the hardware bug setting the sprite overflow flag of PPUSTATUS: the byte compared to
+    /// the scanline moves on along with the sprite, so the flag misses sprites and catches false
+    /// ones.
+    pub(super) fn evaluate_sprites<C: Cartridge>(&mut self, cartridge: &C) {
+        let height = self.sprite_height();
+        let scanline = self.scanline;
+        let on_scanline = |y: u8| scanline.wrapping_sub(y as u16) < height;
+
+        self.sprites.secondary_oam = [0xFF; SECONDARY_OAM_SIZE];
+        self.sprites.count = 0;
+
+        let mut sprite = 0;
+
+        while sprite < OAM_SPRITES && self.sprites.count < SPRITES_PER_SCANLINE {
+            let bytes = &self.oam[sprite * SPRITE_SIZE..(sprite + 1) * SPRITE_SIZE];
+
+            if on_scanline(bytes[0]) {
+                let slot = self.sprites.count * SPRITE_SIZE;
+                self.sprites.secondary_oam[slot..slot + SPRITE_SIZE].copy_from_slice(bytes);
+
+                self.sprites.units[self.sprites.count] = SpriteUnit {
+                    x: bytes[3],
+                    attributes: bytes[2],
+                    planes: self.fetch_sprite_planes(bytes[1], bytes[2], scanline - bytes[0] as u16, cartridge),
+                    sprite_zero: sprite == 0,
+                };
+
+                self.sprites.count += 1;
+            }
+
+            sprite += 1;
+        }
+
+        let mut byte = 0;
+
+        while sprite < OAM_SPRITES {
+            if on_scanline(self.oam[sprite * SPRITE_SIZE + byte]) {
+                self.status.insert(PpuStatus::SpriteOverflow);
+                break;
+            }
+
+            sprite += 1;
+            byte = (byte + 1) % SPRITE_SIZE;
+        }
+    }
+
+    /// Drop the sprites fetched, as no evaluation happens on the pre-render scanline or while the
+    /// rendering is disabled.
+    pub(super) fn clear_sprites(&mut self) {
+        self.sprites.count = 0;
+    }
+
+    /// Get the pixel of the first opaque sprite at the given column of the scanline being drawn,
+    /// if any.
+    ///
+    /// The sprites are hidden while PPUMASK tells so, including on the leftmost 8 pixels only.
+    pub(super) fn sprite_pixel(&self, x: usize) -> Option<SpritePixel> {
+        let clipped = x < LEFT_CLIP_WIDTH && !self.mask.contains(PpuMask::ShowSpritesLeft);
+
+        if !self.mask.contains(PpuMask::ShowSprites) || clipped {
+            return None;
+        }
+
+        self.sprites.units[..self.sprites.count].iter().find_map(|unit| {
+            let column = x.checked_sub(unit.x as usize).filter(|&column| column < SPRITE_WIDTH)?;
+            let bit = 7 - column;
+            let pixel = (unit.planes[1] >> bit & 1) << 1 | (unit.planes[0] >> bit & 1);
+
+            (pixel != 0).then_some(SpritePixel {
+                pixel,
+                palette: FIRST_SPRITE_PALETTE + (unit.attributes & PALETTE_BITS),
+                behind_background: unit.attributes & BEHIND_BACKGROUND_BIT != 0,
+                sprite_zero: unit.sprite_zero,
+            })
+        })
+    }
+
+    /// Get the height of the sprites, 8 or 16 pixels.
+    fn sprite_height(&self) -> u16 {
+        match self.ctrl.contains(PpuCtrl::TallSprites) {
+            true => 16,
+            false => 8,
+        }
+    }
+
+    /// Fetch the low and high planes of the given row of the pattern of a sprite, flipped as its
+    /// attributes tell.
+    ///
+    /// The 8x8 sprites take their pattern from the table selected by PPUCTRL, while the 8x16 ones
+    /// take the table from the bit 0 of their tile, the top half being the even tile below it and
+    /// the bottom half the next one.
+    fn fetch_sprite_planes<C: Cartridge>(&self, tile: u8, attributes: u8, row: u16, cartridge: &C) -> [u8; 2] {
+        let height = self.sprite_height();
+
+        let row = match attributes & FLIP_VERTICALLY_BIT != 0 {
+            true => height - 1 - row,
+            false => row,
+        };
+
+        let address = match height {
+            16 => {
+                let table = (tile as u16 & 1) * SECOND_PATTERN_TABLE_ADDRESS;
+                let tile = (tile & 0xFE) as u16 + row / 8;
+
+                table + tile * PATTERN_TILE_SIZE + row % 8
+            }
+
+            _ => {
+                let table = match self.ctrl.contains(PpuCtrl::SpritePatternTable) {
+                    true => SECOND_PATTERN_TABLE_ADDRESS,
+                    false => 0x0000,
+                };
+
+                table + tile as u16 * PATTERN_TILE_SIZE + row
+            }
+        };
+
+        let planes = [self.read_memory(address, cartridge), self.read_memory(address + 8, cartridge)];
+
+        match attributes & FLIP_HORIZONTALLY_BIT != 0 {
+            true => planes.map(u8::reverse_bits),
+            false => planes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cartridge::Cartridge;
+    use crate::ppu::tests::*;
+    use crate::ppu::{Ppu, PpuStatus};
+
+    /// The colors of the first sprite palette.
+    const SPRITE_COLORS: [u8; 3] = [0x21, 0x22, 0x23];
+
+    /// The color 1 of the first background palette.
+    const BACKGROUND_COLOR: u8 = 0x01;
+
+    /// Create a [Ppu] rendering the sprites and the background, and the cartridge of its pattern
+    /// tables, with the tile 2 filled with the color 1, the tile 3 holding a single pixel of the
+    /// color 1 on the top left, and the tiles 4 and 5 of the second table filled with the colors 1
+    /// and 2.
+    fn sprites_setup() -> (Ppu, FlatCartridge) {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+
+        unsafe {
+            for row in 0..8 {
+                cartridge.ppu_write(0x0020 + row, 0xFF).unwrap();
+                cartridge.ppu_write(0x1040 + row, 0xFF).unwrap();
+                cartridge.ppu_write(0x1058 + row, 0xFF).unwrap();
+            }
+
+            cartridge.ppu_write(0x0030, 0b1000_0000).unwrap();
+        }
+
+        ppu.write_memory(0x3F00, BACKDROP, &mut cartridge);
+        ppu.write_memory(0x3F01, BACKGROUND_COLOR, &mut cartridge);
+
+        for (index, color) in SPRITE_COLORS.into_iter().enumerate() {
+            ppu.write_memory(0x3F11 + index as u16, color, &mut cartridge);
+        }
+
+        ppu.write(0x2001, 0b0001_1110, &mut cartridge);
+
+        (ppu, cartridge)
+    }
+
+    /// Place the sprite of the given number of the OAM.
+    fn place_sprite(ppu: &mut Ppu, sprite: usize, x: u8, y: u8, tile: u8, attributes: u8) {
+        ppu.oam[sprite * 4..sprite * 4 + 4].copy_from_slice(&[y, tile, attributes, x]);
+    }
+
+    /// Hide every sprite below the picture.
+    fn hide_sprites(ppu: &mut Ppu) {
+        ppu.oam = [0xFF; 256];
+    }
+
+    /// Run the PPU until the pre-render scanline of the second frame, the first one setting up
+    /// the scroll, before the flags are cleared.
+    fn run_until_second_pre_render(ppu: &mut Ppu, cartridge: &FlatCartridge) {
+        run_until(ppu, cartridge, 261, 0);
+        ppu.step(cartridge);
+        run_until(ppu, cartridge, 261, 0);
+    }
+
+    #[test]
+    fn test_eight_sprites_per_scanline() {
+        let (mut ppu, cartridge) = sprites_setup();
+        hide_sprites(&mut ppu);
+
+        for sprite in 0..9 {
+            place_sprite(&mut ppu, sprite, 16 * sprite as u8, 10, 0x02, 0x00);
+        }
+
+        run_until_second_pre_render(&mut ppu, &cartridge);
+
+        // The sprites are drawn one scanline below their Y position
+        for sprite in 0..8 {
+            assert_eq!(ppu.frame().palette_index(16 * sprite, 11), SPRITE_COLORS[0], "sprite {sprite}");
+            assert_eq!(ppu.frame().palette_index(16 * sprite + 7, 18), SPRITE_COLORS[0], "sprite {sprite}");
+        }
+
+        assert_eq!(ppu.frame().palette_index(0, 10), BACKDROP);
+        assert_eq!(ppu.frame().palette_index(128, 11), BACKDROP);
+        assert!(ppu.status().contains(PpuStatus::SpriteOverflow));
+
+        // The evaluation on the scanline 10 found the first eight sprites
+        while (ppu.scanline(), ppu.dot()) != (11, 0) {
+            ppu.step(&cartridge);
+        }
+
+        assert_eq!(ppu.secondary_oam()[..], ppu.oam()[..32]);
+    }
+
+    #[test]
+    fn test_sprite_overflow_bug() {
+        let (mut ppu, cartridge) = sprites_setup();
+        hide_sprites(&mut ppu);
+
+        for sprite in 0..8 {
+            place_sprite(&mut ppu, sprite, 0, 50, 0x02, 0x00);
+        }
+
+        // The ninth sprite is compared with its Y position, the tenth one with its tile
+        place_sprite(&mut ppu, 8, 0, 200, 0x02, 0x00);
+        place_sprite(&mut ppu, 9, 0, 0xFF, 50, 0x00);
+        run_until_second_pre_render(&mut ppu, &cartridge);
+        assert!(ppu.status().contains(PpuStatus::SpriteOverflow));
+
+        // While a ninth sprite on the scanline is missed when compared with its tile
+        place_sprite(&mut ppu, 9, 0, 50, 0xFF, 0x00);
+        run_until_second_pre_render(&mut ppu, &cartridge);
+        assert!(!ppu.status().contains(PpuStatus::SpriteOverflow));
+    }
+
+    #[test]
+    fn test_sprite_flips() {
+        let (mut ppu, cartridge) = sprites_setup();
+        hide_sprites(&mut ppu);
+
+        place_sprite(&mut ppu, 0, 8, 40, 0x03, 0x00);
+        place_sprite(&mut ppu, 1, 24, 40, 0x03, 0x40);
+        place_sprite(&mut ppu, 2, 40, 40, 0x03, 0x80);
+        place_sprite(&mut ppu, 3, 56, 40, 0x03, 0xC1);
+        render_two_frames(&mut ppu, &cartridge);
+
+        let frame = ppu.frame();
+        let opaque = |x: usize, y: usize| frame.palette_index(x, y) != BACKDROP;
+
+        assert!(opaque(8, 41) && !opaque(15, 41) && !opaque(8, 48));
+        assert!(!opaque(24, 41) && opaque(31, 41));
+        assert!(!opaque(40, 41) && opaque(40, 48));
+
+        // Both flips at once, with the second sprite palette
+        assert!(opaque(63, 48));
+        assert_eq!(frame.palette_index(63, 48), 0x00);
+    }
+
+    #[test]
+    fn test_tall_sprites() {
+        let (mut ppu, mut cartridge) = sprites_setup();
+        hide_sprites(&mut ppu);
+        ppu.write(0x2000, 0b0010_0000, &mut cartridge);
+
+        // The odd tile takes the second pattern table
+        place_sprite(&mut ppu, 0, 100, 100, 0x05, 0x00);
+        place_sprite(&mut ppu, 1, 120, 100, 0x05, 0x80);
+        render_two_frames(&mut ppu, &cartridge);
+
+        let frame = ppu.frame();
+        assert_eq!(frame.palette_index(100, 101), SPRITE_COLORS[0]);
+        assert_eq!(frame.palette_index(100, 108), SPRITE_COLORS[0]);
+        assert_eq!(frame.palette_index(100, 109), SPRITE_COLORS[1]);
+        assert_eq!(frame.palette_index(100, 116), SPRITE_COLORS[1]);
+        assert_eq!(frame.palette_index(100, 117), BACKDROP);
+
+        // Flipping vertically swaps the halves
+        assert_eq!(frame.palette_index(120, 101), SPRITE_COLORS[1]);
+        assert_eq!(frame.palette_index(120, 116), SPRITE_COLORS[0]);
+    }
+
+    #[test]
+    fn test_sprite_priority() {
+        let (mut ppu, mut cartridge) = sprites_setup();
+        hide_sprites(&mut ppu);
+
+        // The first tile of the background is opaque, the second one transparent
+        ppu.write_memory(0x2000, 0x02, &mut cartridge);
+
+        place_sprite(&mut ppu, 0, 4, 0, 0x02, 0x20);
+        place_sprite(&mut ppu, 1, 0, 20, 0x02, 0x20);
+        place_sprite(&mut ppu, 2, 0, 8, 0x02, 0x00);
+        render_two_frames(&mut ppu, &cartridge);
+
+        let frame = ppu.frame();
+
+        // A sprite behind the background only shows through its transparent pixels
+        assert_eq!(frame.palette_index(4, 1), BACKGROUND_COLOR);
+        assert_eq!(frame.palette_index(8, 1), SPRITE_COLORS[0]);
+        assert_eq!(frame.palette_index(0, 21), SPRITE_COLORS[0]);
+
+        // While a sprite in front covers it
+        ppu.write_memory(0x2020, 0x02, &mut cartridge);
+        render_two_frames(&mut ppu, &cartridge);
+        assert_eq!(ppu.frame().palette_index(0, 9), SPRITE_COLORS[0]);
+    }
+
+    #[test]
+    fn test_sprite_zero_hit_dot() {
+        let (mut ppu, mut cartridge) = sprites_setup();
+        hide_sprites(&mut ppu);
+
+        // The tile 5 of the fourth row of tiles covers the pixels 40 to 47 of the lines 24 to 31,
+        // the sprite 0 starts on the line 31, so they first meet on the pixel (40, 31)
+        ppu.write_memory(0x2065, 0x02, &mut cartridge);
+        place_sprite(&mut ppu, 0, 40, 30, 0x02, 0x20);
+
+        run_until(&mut ppu, &cartridge, 261, 0);
+        ppu.step(&cartridge);
+
+        // The pixel of the column x is drawn on the dot x + 1, with no delay: a CPU polling
+        // PPUSTATUS sees the flag on the cycle running that dot, up to two dots later
+        run_until(&mut ppu, &cartridge, 31, 41);
+        assert!(!ppu.status().contains(PpuStatus::Sprite0Hit));
+
+        ppu.step(&cartridge);
+        assert!(ppu.status().contains(PpuStatus::Sprite0Hit));
+
+        // The flag stays set through the vertical blank, then the pre-render scanline clears it
+        run_until(&mut ppu, &cartridge, 261, 1);
+        assert!(ppu.status().contains(PpuStatus::Sprite0Hit));
+
+        ppu.step(&cartridge);
+        assert!(!ppu.status().contains(PpuStatus::Sprite0Hit));
+    }
+
+    #[test]
+    fn test_sprite_zero_hit_needs_sprite_zero() {
+        let (mut ppu, mut cartridge) = sprites_setup();
+        hide_sprites(&mut ppu);
+
+        ppu.write_memory(0x2065, 0x02, &mut cartridge);
+        place_sprite(&mut ppu, 1, 40, 30, 0x02, 0x00);
+
+        // The sprite 0 only meets transparent pixels of the background
+        place_sprite(&mut ppu, 0, 100, 30, 0x02, 0x00);
+        run_until_second_pre_render(&mut ppu, &cartridge);
+        assert!(!ppu.status().contains(PpuStatus::Sprite0Hit));
+
+        // Hiding either layer prevents the hit
+        place_sprite(&mut ppu, 0, 40, 30, 0x02, 0x00);
+
+        for mask in [0b0000_1110, 0b0001_0110] {
+            ppu.write(0x2001, mask, &mut cartridge);
+            run_until_second_pre_render(&mut ppu, &cartridge);
+            assert!(!ppu.status().contains(PpuStatus::Sprite0Hit), "PPUMASK {mask:08b}");
+        }
+
+        ppu.write(0x2001, 0b0001_1110, &mut cartridge);
+        run_until_second_pre_render(&mut ppu, &cartridge);
+        assert!(ppu.status().contains(PpuStatus::Sprite0Hit));
+    }
+
+    #[test]
+    fn test_sprite_zero_hit_last_column() {
+        let (mut ppu, mut cartridge) = sprites_setup();
+        hide_sprites(&mut ppu);
+
+        // The last tile of the first row covers the pixels 248 to 255
+        ppu.write_memory(0x201F, 0x02, &mut cartridge);
+        place_sprite(&mut ppu, 0, 255, 0, 0x02, 0x00);
+        run_until_second_pre_render(&mut ppu, &cartridge);
+
+        assert_eq!(ppu.frame().palette_index(255, 1), SPRITE_COLORS[0]);
+        assert!(!ppu.status().contains(PpuStatus::Sprite0Hit));
+
+        // One pixel on the left is enough
+        place_sprite(&mut ppu, 0, 254, 0, 0x02, 0x00);
+        run_until_second_pre_render(&mut ppu, &cartridge);
+        assert!(ppu.status().contains(PpuStatus::Sprite0Hit));
+    }
+
+    #[test]
+    fn test_sprite_zero_hit_left_clip() {
+        let (mut ppu, mut cartridge) = sprites_setup();
+        hide_sprites(&mut ppu);
+
+        // The single pixel of the tile 3 meets the first tile of the background on the pixel (0, 1)
+        ppu.write_memory(0x2000, 0x02, &mut cartridge);
+        place_sprite(&mut ppu, 0, 0, 0, 0x03, 0x00);
+
+        for mask in [0b0001_1100, 0b0001_1010] {
+            ppu.write(0x2001, mask, &mut cartridge);
+            run_until_second_pre_render(&mut ppu, &cartridge);
+            assert!(!ppu.status().contains(PpuStatus::Sprite0Hit), "PPUMASK {mask:08b}");
+        }
+
+        ppu.write(0x2001, 0b0001_1110, &mut cartridge);
+        run_until_second_pre_render(&mut ppu, &cartridge);
+        assert!(ppu.status().contains(PpuStatus::Sprite0Hit));
+
+        // The pixels past the leftmost 8 still hit while clipped
+        ppu.write(0x2001, 0b0001_1000, &mut cartridge);
+        ppu.write_memory(0x2001, 0x02, &mut cartridge);
+        place_sprite(&mut ppu, 0, 8, 0, 0x03, 0x00);
+        run_until_second_pre_render(&mut ppu, &cartridge);
+        assert!(ppu.status().contains(PpuStatus::Sprite0Hit));
+    }
+}
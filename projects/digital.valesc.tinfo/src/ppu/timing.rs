@@ -0,0 +1,266 @@
+//! Holds the timing of the PPU, walking the dots of the scanlines of every frame and raising the
+//! vertical blank.
+
+use crate::cartridge::Cartridge;
+use crate::ppu::{Ppu, PpuCtrl, PpuStatus};
+
+/// The number of dots of every scanline, the last one being 340.
+pub const DOTS_PER_SCANLINE: u16 = 341;
+
+/// The number of scanlines of every frame, counting the visible ones, the post-render one, the ones
+/// of the vertical blank and the pre-render one.
+pub const SCANLINES_PER_FRAME: u16 = 262;
+
+/// The scanline whose second dot starts the vertical blank.
+const VBLANK_SCANLINE: u16 = 241;
+
+/// The last scanline of every frame, whose second dot ends the vertical blank.
+pub(super) const PRE_RENDER_SCANLINE: u16 = SCANLINES_PER_FRAME - 1;
+
+impl Ppu {
+    /// Run the next dot of the PPU, given the cartridge mapping the pattern tables.
+    ///
+    /// The VBlank flag is set on the dot 1 of the scanline 241, raising a NMI if PPUCTRL asks for
+    /// it, and cleared on the dot 1 of the pre-render scanline along with the sprite flags.
+    ///
+    /// While rendering, the odd frames skip the last dot of their pre-render scanline, making them
+    /// 89341 dots long instead of 89342.
+    pub(crate) fn step<C: Cartridge>(&mut self, cartridge: &C) {
+        self.render_dot(cartridge);
+
+        match (self.scanline, self.dot) {
+            (VBLANK_SCANLINE, 1) => {
+                self.status.insert(PpuStatus::VBlank);
+
+                if self.ctrl.contains(PpuCtrl::Nmi) {
+                    self.nmi_request = true;
+                }
+            }
+
+            (PRE_RENDER_SCANLINE, 1) => {
+                self.status.remove(PpuStatus::VBlank | PpuStatus::Sprite0Hit | PpuStatus::SpriteOverflow)
+            }
+
+            _ => {}
+        }
+
+        self.dot += 1;
+
+        if self.scanline == PRE_RENDER_SCANLINE
+            && self.dot == DOTS_PER_SCANLINE - 1
+            && self.frame_count % 2 == 1
+            && self.rendering_enabled()
+        {
+            self.dot += 1;
+        }
+
+        if self.dot == DOTS_PER_SCANLINE {
+            self.dot = 0;
+            self.scanline += 1;
+        }
+
+        if self.scanline == SCANLINES_PER_FRAME {
+            self.scanline = 0;
+            self.frame_count += 1;
+        }
+    }
+
+    /// Take the NMI raised since the last call, if any.
+    pub(crate) fn take_nmi_request(&mut self) -> bool {
+        std::mem::take(&mut self.nmi_request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cartridge::testing::FlatCartridge;
+    use crate::cpu::Cpu;
+    use crate::ppu::{Ppu, PpuStatus};
+
+    /// The CPU cycle on which the VBlank flag is set, the dot 1 of the scanline 241 being the
+    /// first one it runs.
+    const VBLANK_START_CYCLE: u64 = (241 * 341 + 1) / 3;
+
+    /// Create a [Cpu] looping on a `JMP $8000`, with its NMI handler at `$9000`.
+    fn looping_cpu() -> Cpu<FlatCartridge> {
+        let cartridge = FlatCartridge::new(vec![0x4C, 0x00, 0x80]).with_nmi_vector(0x9000);
+
+        Cpu::from_cartridge(cartridge).unwrap()
+    }
+
+    /// Run cycles until the CPU has run the given number of them since the power-on.
+    fn run_until_cycle(cpu: &mut Cpu<FlatCartridge>, cycles: u64) {
+        while cpu.cycles() < cycles {
+            cpu.cycle().unwrap();
+        }
+    }
+
+    /// Run whole instructions until the one at the given address, returning the number of cycles
+    /// the CPU had run when it started.
+    fn run_until_instruction(cpu: &mut Cpu<FlatCartridge>, address: u16) -> u64 {
+        loop {
+            let snapshot = cpu.step().unwrap();
+
+            if snapshot.program_counter == address {
+                return snapshot.cpu_cycles;
+            }
+        }
+    }
+
+    #[test]
+    fn test_power_on_position() {
+        let cpu = looping_cpu();
+
+        // The PPU keeps running through the reset sequence
+        assert_eq!((cpu.bus().ppu().scanline(), cpu.bus().ppu().dot()), (0, 21));
+    }
+
+    #[test]
+    fn test_vblank_flag_timing() {
+        let mut cpu = looping_cpu();
+
+        run_until_cycle(&mut cpu, VBLANK_START_CYCLE);
+        assert!(!cpu.bus().ppu().status().contains(PpuStatus::VBlank));
+
+        run_until_cycle(&mut cpu, VBLANK_START_CYCLE + 1);
+        assert!(cpu.bus().ppu().status().contains(PpuStatus::VBlank));
+        assert_eq!((cpu.bus().ppu().scanline(), cpu.bus().ppu().dot()), (241, 4));
+
+        // The dot 1 of the pre-render scanline is run by the cycle 29667
+        run_until_cycle(&mut cpu, 29667);
+        assert!(cpu.bus().ppu().status().contains(PpuStatus::VBlank));
+
+        run_until_cycle(&mut cpu, 29668);
+        assert!(!cpu.bus().ppu().status().contains(PpuStatus::VBlank));
+
+        // A frame is 89342 dots long
+        run_until_cycle(&mut cpu, 29780);
+        assert_eq!(cpu.bus().ppu().frame_count(), 0);
+
+        run_until_cycle(&mut cpu, 29781);
+        assert_eq!(cpu.bus().ppu().frame_count(), 1);
+        assert_eq!((cpu.bus().ppu().scanline(), cpu.bus().ppu().dot()), (0, 1));
+    }
+
+    #[test]
+    fn test_vblank_clear_on_read() {
+        let cartridge = FlatCartridge::new(vec![
+            // BIT $2002
+            0x2C, 0x02, 0x20,
+
+            // BPL $8000
+            0x10, 0xFB,
+
+            // JMP $8005
+            0x4C, 0x05, 0x80,
+        ]);
+
+        let mut cpu = Cpu::from_cartridge(cartridge).unwrap();
+        cpu.bus_mut().write(0x2006, 0x20).unwrap();
+
+        // The read of the cycle 27394 comes before the flag is set on the same cycle, so the loop
+        // sees it on the next read, on the cycle 27401, which clears it
+        assert_eq!(run_until_instruction(&mut cpu, 0x8005), 27404);
+        assert!(!cpu.bus().ppu().status().contains(PpuStatus::VBlank));
+
+        // The write latch was reset too
+        assert!(!cpu.bus().ppu().write_latch());
+        assert_eq!(cpu.bus_mut().read(0x2002).unwrap() & 0x80, 0x00);
+    }
+
+    #[test]
+    fn test_nmi_at_vblank() {
+        let mut cpu = looping_cpu();
+        cpu.bus_mut().write(0x2000, 0x80).unwrap();
+
+        // The NMI is taken after the JMP running on the cycle setting the flag, from 27394 to
+        // 27396, then its sequence takes seven cycles
+        assert_eq!(run_until_instruction(&mut cpu, 0x9000), 27404);
+        assert!(cpu.bus().ppu().status().contains(PpuStatus::VBlank));
+
+        // A single NMI is raised by every vertical blank
+        assert!(!cpu.pending_interrupts().nmi_pending);
+    }
+
+    #[test]
+    fn test_nmi_enabled_during_vblank() {
+        let mut cpu = looping_cpu();
+
+        run_until_cycle(&mut cpu, 28000);
+        assert!(!cpu.pending_interrupts().nmi_pending);
+
+        cpu.bus_mut().write(0x2000, 0x80).unwrap();
+        let cycles = cpu.cycles();
+        assert!(run_until_instruction(&mut cpu, 0x9000) - cycles <= 3 + 7);
+
+        // Writing PPUCTRL again with the NMI already enabled raises no other one
+        cpu.bus_mut().write(0x2000, 0x80).unwrap();
+        cpu.cycle().unwrap();
+        assert!(!cpu.pending_interrupts().nmi_pending);
+
+        // Nor does enabling it outside of the vertical blank
+        cpu.bus_mut().write(0x2000, 0x00).unwrap();
+        cpu.bus_mut().read(0x2002).unwrap();
+        cpu.bus_mut().write(0x2000, 0x80).unwrap();
+        cpu.cycle().unwrap();
+        assert!(!cpu.pending_interrupts().nmi_pending);
+    }
+
+    /// Run the PPU until the next frame starts, returning the number of dots run.
+    fn run_frame(ppu: &mut Ppu, cartridge: &FlatCartridge) -> u32 {
+        let frame_count = ppu.frame_count();
+        let mut dots = 0;
+
+        while ppu.frame_count() == frame_count {
+            ppu.step(cartridge);
+            dots += 1;
+        }
+
+        dots
+    }
+
+    #[test]
+    fn test_odd_frame_skip() {
+        let mut ppu = Ppu::new();
+        let mut cartridge = FlatCartridge::new(vec![]);
+
+        assert_eq!(run_frame(&mut ppu, &cartridge), 89342);
+        assert_eq!(run_frame(&mut ppu, &cartridge), 89342);
+
+        ppu.write(0x2001, 0b0000_1000, &mut cartridge);
+        assert_eq!(run_frame(&mut ppu, &cartridge), 89342);
+        assert_eq!(run_frame(&mut ppu, &cartridge), 89341);
+
+        // The odd frame 5 skips the dot 340 of its pre-render scanline
+        run_frame(&mut ppu, &cartridge);
+        assert_eq!(ppu.frame_count(), 5);
+
+        while (ppu.scanline(), ppu.dot()) != (261, 339) {
+            ppu.step(&cartridge);
+        }
+
+        ppu.step(&cartridge);
+        assert_eq!((ppu.scanline(), ppu.dot(), ppu.frame_count()), (0, 0, 6));
+
+        // Showing only the sprites is rendering too
+        ppu.write(0x2001, 0b0001_0000, &mut cartridge);
+        assert_eq!(run_frame(&mut ppu, &cartridge), 89342);
+        assert_eq!(run_frame(&mut ppu, &cartridge), 89341);
+    }
+
+    #[test]
+    fn test_pre_render_clears_flags() {
+        let mut ppu = Ppu::new();
+        let cartridge = FlatCartridge::new(vec![]);
+        ppu.status = PpuStatus::all();
+
+        while (ppu.scanline(), ppu.dot()) != (261, 1) {
+            ppu.step(&cartridge);
+        }
+
+        assert_eq!(ppu.status(), PpuStatus::all());
+
+        ppu.step(&cartridge);
+        assert_eq!(ppu.status(), PpuStatus::empty());
+    }
+}
@@ -0,0 +1,183 @@
+//! Holds the rewinder, which keeps the recent history of the machine to go back in time.
+
+use std::collections::VecDeque;
+
+use crate::cartridge::Cartridge;
+use crate::cpu::{Cpu, CpuError, SaveState};
+
+/// Captures a [SaveState] of the machine at a regular interval of cycles into a bounded ring
+/// buffer, so the emulation can be rewound to one of them.
+///
+/// Once the buffer is full every capture drops the oldest state.
+pub struct Rewinder {
+    /// The number of cycles between two captures.
+    interval: u64,
+
+    /// The maximum number of states kept.
+    capacity: usize,
+
+    /// The captured states, from the oldest to the newest one.
+    slots: VecDeque<SaveState>,
+
+    /// The number of cycles the CPU must have run to take the next capture.
+    next_capture: u64,
+}
+
+impl Rewinder {
+    /// Create a new [Rewinder] capturing a state every `interval` cycles, keeping up to `capacity`
+    /// states.
+    ///
+    /// # Panics
+    /// If the interval or the capacity are zero.
+    pub fn new(interval: u64, capacity: usize) -> Rewinder {
+        assert!(interval > 0, "The rewinder needs a non-zero interval");
+        assert!(capacity > 0, "The rewinder needs a non-zero capacity");
+
+        Rewinder {
+            interval,
+            capacity,
+            slots: VecDeque::with_capacity(capacity),
+            next_capture: 0,
+        }
+    }
+
+    /// Capture the state of the CPU if the interval has passed since the last capture, returning
+    /// if it was captured.
+    ///
+    /// It must be called after every cycle or instruction run, the first call always captures.
+    pub fn update<C: Cartridge>(&mut self, cpu: &Cpu<C>) -> bool {
+        if cpu.cycles() < self.next_capture {
+            return false;
+        }
+
+        self.capture(cpu);
+
+        true
+    }
+
+    /// Capture the state of the CPU right away, starting a new interval.
+    pub fn capture<C: Cartridge>(&mut self, cpu: &Cpu<C>) {
+        let state = cpu.save_state();
+
+        if self.slots.len() == self.capacity {
+            self.slots.pop_front();
+        }
+
+        self.slots.push_back(state);
+
+        self.next_capture = cpu.cycles() + self.interval;
+    }
+
+    /// Restore the CPU to the state captured the given number of captures ago, counting only the
+    /// ones taken before the current cycle, returning how many captures it actually went back.
+    ///
+    /// Rewinding past the oldest state stops at it, and rewinding with no state older than the
+    /// current cycle does nothing. The states newer than the restored one are dropped, as they
+    /// belong to a timeline that will be run again.
+    pub fn rewind<C: Cartridge>(&mut self, cpu: &mut Cpu<C>, steps: usize) -> Result<usize, CpuError> {
+        let older_states = (0..self.slots.len())
+            .filter(|&age| self.state(age).cycles() < cpu.cycles())
+            .count();
+
+        let steps = steps.min(older_states);
+        if steps == 0 {
+            return Ok(0);
+        }
+
+        // The states at the current cycle or past it are newer than any of the older ones
+        let age = self.slots.len() - older_states + steps - 1;
+        cpu.load_state(self.state(age))?;
+
+        self.slots.truncate(self.slots.len() - age);
+
+        self.next_capture = cpu.cycles() + self.interval;
+
+        Ok(steps)
+    }
+
+    /// Get the number of states kept.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Check if there is no state kept.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Get the state captured the given number of captures before the newest one.
+    fn state(&self, age: usize) -> &SaveState {
+        &self.slots[self.slots.len() - 1 - age]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpu::tests::{counter_program, machine_state, FlatCartridge};
+
+    /// Run the given number of cycles, updating the rewinder after every one.
+    fn run_cycles(cpu: &mut Cpu, rewinder: &mut Rewinder, cycles: u64) {
+        for _ in 0..cycles {
+            cpu.cycle().unwrap();
+            rewinder.update(cpu);
+        }
+    }
+
+    #[test]
+    fn test_rewind() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+        let mut rewinder = Rewinder::new(1_000, 8);
+        rewinder.update(&cpu);
+
+        run_cycles(&mut cpu, &mut rewinder, 5_500);
+        let original_timeline = machine_state(&cpu);
+
+        // The captures are taken at the cycles 7, 1007, 2007, 3007, 4007 and 5007
+        assert_eq!(rewinder.len(), 6);
+
+        assert_eq!(rewinder.rewind(&mut cpu, 2).unwrap(), 2);
+        assert_eq!(cpu.cycles(), 4_007);
+        assert_eq!(rewinder.len(), 5);
+
+        run_cycles(&mut cpu, &mut rewinder, 5_507 - 4_007);
+        assert_eq!(machine_state(&cpu), original_timeline);
+        assert_eq!(rewinder.len(), 6);
+    }
+
+    #[test]
+    fn test_rewind_repeatedly() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+        let mut rewinder = Rewinder::new(1_000, 8);
+        rewinder.update(&cpu);
+
+        run_cycles(&mut cpu, &mut rewinder, 2_500);
+
+        // Standing on a captured state goes to the one before it
+        assert_eq!(rewinder.rewind(&mut cpu, 1).unwrap(), 1);
+        assert_eq!(cpu.cycles(), 2_007);
+
+        assert_eq!(rewinder.rewind(&mut cpu, 1).unwrap(), 1);
+        assert_eq!(cpu.cycles(), 1_007);
+    }
+
+    #[test]
+    fn test_rewind_past_oldest_state() {
+        let mut cpu = Cpu::new(Box::new(FlatCartridge::new(counter_program()))).unwrap();
+        let mut rewinder = Rewinder::new(1_000, 3);
+        rewinder.update(&cpu);
+
+        run_cycles(&mut cpu, &mut rewinder, 5_500);
+
+        // Only the captures at the cycles 3007, 4007 and 5007 are kept
+        assert_eq!(rewinder.len(), 3);
+
+        assert_eq!(rewinder.rewind(&mut cpu, 10).unwrap(), 3);
+        assert_eq!(cpu.cycles(), 3_007);
+        assert_eq!(rewinder.len(), 1);
+
+        // There is nothing older left
+        assert_eq!(rewinder.rewind(&mut cpu, 1).unwrap(), 0);
+        assert_eq!(cpu.cycles(), 3_007);
+    }
+}
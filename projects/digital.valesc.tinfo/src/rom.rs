@@ -10,4 +10,11 @@ pub(crate) trait Rom {
     /// Get a byte from the PRG ROM data chip, all banks should be merge and globally
     /// accessible by an index by concatenating them.
     fn read_prg_data(&self, index: usize) -> u8;
+
+    /// Get the size in bytes of the CHR ROM data, zero if the cartridge has CHR RAM instead.
+    fn chr_rom_size(&self) -> usize;
+
+    /// Get a byte from the CHR ROM data chip, all banks should be merge and globally accessible
+    /// by an index by concatenating them.
+    fn read_chr_data(&self, index: usize) -> u8;
 }
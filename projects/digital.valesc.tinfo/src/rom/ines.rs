@@ -1,3 +1,5 @@
+//! Holds the parser of the iNES ROM file format.
+
 use std::io;
 use std::io::{Read, Seek};
 
@@ -5,27 +7,52 @@ use log::debug;
 use thiserror::Error;
 
 use crate::cartridge::nrom::Nrom;
-use crate::cartridge::Cartridge;
+use crate::cartridge::{Cartridge, Mirroring};
 use crate::rom::Rom;
 
+/// The number of bytes in a kibibyte (1 KiB).
 pub const BYTES_ON_KIBIBYTE: usize = 1024;
 
+/// The bit of the flags 6 of the header telling the nametables are mirrored vertically instead of
+/// horizontally.
+const VERTICAL_MIRRORING_FLAG: u8 = 0b0000_0001;
+
+/// The bit of the flags 6 of the header telling the cartridge provides four distinct nametables.
+const FOUR_SCREEN_FLAG: u8 = 0b0000_1000;
+
+/// A ROM stored using the iNES file format.
 pub struct InesFile {
+    /// The PRG ROM data of all the banks concatenated.
     pub prg_rom: Vec<u8>,
+
+    /// The size in bytes of the PRG ROM data.
     pub prg_rom_size: usize,
+
+    /// The CHR ROM data of all the banks concatenated, empty if the cartridge has CHR RAM instead.
+    pub chr_rom: Vec<u8>,
 }
 
 #[derive(Debug, Error)]
+/// Errors that may happen when parsing an iNES ROM.
 pub enum InesFileError {
     #[error("The iNES ROM is missing the magic bytes NES<SUB> at its start")]
+    /// The iNES ROM is missing the magic bytes NES<SUB> at its start.
     MagicBytesMissing,
 
     #[error("Unable to read the iNES ROM: {0}")]
+    /// Unable to read the iNES ROM.
     ReadingRomFailed(#[from] io::Error),
 }
 
 impl InesFile {
+    /// Parse an iNES ROM from the given reader, returning the cartridge it describes.
     pub fn from_read<R: Read + Seek>(reader: &mut R) -> Result<Box<dyn Cartridge>, InesFileError> {
+        Ok(Box::new(InesFile::nrom_from_read(reader)?))
+    }
+
+    /// Parse an iNES ROM using the NROM mapper from the given reader, returning the cartridge as
+    /// is to run it on a [Cpu](crate::cpu::Cpu) without dynamic dispatch.
+    pub fn nrom_from_read<R: Read + Seek>(reader: &mut R) -> Result<Nrom, InesFileError> {
         debug!("Parsing iNES ROM");
 
         let mut magic_bytes = [0; 4];
@@ -44,22 +71,51 @@ impl InesFile {
         let prg_rom_size = prg_rom_size[0] as usize * 16 * BYTES_ON_KIBIBYTE;
         debug!("PRG ROM SIZE:{prg_rom_size}");
 
+        let mut chr_rom_size: [u8; 1] = [0; 1];
+        reader.read_exact(&mut chr_rom_size)?;
+
+        let chr_rom_size = chr_rom_size[0] as usize * 8 * BYTES_ON_KIBIBYTE;
+        debug!("CHR ROM SIZE:{chr_rom_size}");
+
+        let mut flags_6: [u8; 1] = [0; 1];
+        reader.read_exact(&mut flags_6)?;
+
+        let mirroring = match flags_6[0] {
+            flags if flags & FOUR_SCREEN_FLAG != 0 => Mirroring::FourScreen,
+            flags if flags & VERTICAL_MIRRORING_FLAG != 0 => Mirroring::Vertical,
+            _ => Mirroring::Horizontal,
+        };
+
+        debug!("MIRRORING:{mirroring:?}");
+
         let mut prg_rom = vec![0u8; prg_rom_size];
+        let mut chr_rom = vec![0u8; chr_rom_size];
 
+        // The CHR ROM follows the PRG ROM
         reader.seek(io::SeekFrom::Start(16))?;
         reader.read_exact(&mut prg_rom)?;
+        reader.read_exact(&mut chr_rom)?;
 
         let rom = Self {
             prg_rom,
             prg_rom_size,
+            chr_rom,
         };
 
-        Ok(Box::new(Nrom::new(false, rom)))
+        Ok(Nrom::new(false, mirroring, rom))
     }
 }
 
 impl Rom for InesFile {
     fn read_prg_data(&self, index: usize) -> u8 {
-        return self.prg_rom[index];
+        self.prg_rom[index]
+    }
+
+    fn chr_rom_size(&self) -> usize {
+        self.chr_rom.len()
+    }
+
+    fn read_chr_data(&self, index: usize) -> u8 {
+        self.chr_rom[index]
     }
 }
@@ -0,0 +1,153 @@
+//! Parses the nestest logs, holding the state of the CPU before every instruction run by the
+//! `nestest.nes` ROM in automation mode.
+
+/// The state of the CPU before running an instruction, as logged by nestest.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NestestLine {
+    /// The address of the instruction.
+    pub program_counter: u16,
+
+    /// The opcode of the instruction.
+    pub opcode: u8,
+
+    /// The value of the accumulator register.
+    pub accumulator: u8,
+
+    /// The value of the X index register.
+    pub register_x: u8,
+
+    /// The value of the Y index register.
+    pub register_y: u8,
+
+    /// The raw value of the status register.
+    pub status: u8,
+
+    /// The value of the stack pointer.
+    pub stack_pointer: u8,
+
+    /// The scanline of the next dot of the PPU.
+    pub ppu_scanline: u16,
+
+    /// The next dot of the PPU on its scanline.
+    pub ppu_dot: u16,
+
+    /// The number of cycles run by the CPU before the instruction.
+    pub cycles: u64,
+
+    /// The address, bytes and disassembly columns of the line, before the registers.
+    pub disassembly: String,
+
+    /// The line as it is in the log.
+    pub text: String,
+}
+
+/// Parse a line of a nestest log.
+pub fn parse_line(line: &str) -> Result<NestestLine, String> {
+    let mut tokens = line.split_whitespace();
+    let program_counter = tokens.next().ok_or("The line is empty")?;
+    let opcode = tokens.next().ok_or("The line has no opcode")?;
+
+    // The registers start after the disassembly, which may have tokens looking like them
+    let (disassembly, registers) = line.split_at(line.find(" A:").ok_or("The line has no registers")?);
+
+    let register = |name: &str| {
+        registers
+            .split_whitespace()
+            .find_map(|token| token.strip_prefix(name))
+            .ok_or(format!("The line has no {name} field"))
+    };
+
+    let hex_u8 = |name: &str| {
+        register(name).and_then(|value| u8::from_str_radix(value, 16).map_err(|error| format!("{name} {error}")))
+    };
+
+    // The scanline and the dot are right-aligned on three characters, with spaces in the column
+    let (ppu_scanline, ppu_dot) = registers
+        .split_once("PPU:")
+        .and_then(|(_, ppu)| ppu.split_once(" CYC:"))
+        .and_then(|(ppu, _)| ppu.split_once(','))
+        .ok_or("The line has no PPU: field")?;
+
+    let decimal_u16 = |value: &str| value.trim().parse::<u16>().map_err(|error| format!("PPU: {error}"));
+
+    Ok(NestestLine {
+        program_counter: u16::from_str_radix(program_counter, 16).map_err(|error| format!("PC {error}"))?,
+        opcode: u8::from_str_radix(opcode, 16).map_err(|error| format!("Opcode {error}"))?,
+        accumulator: hex_u8("A:")?,
+        register_x: hex_u8("X:")?,
+        register_y: hex_u8("Y:")?,
+        status: hex_u8("P:")?,
+        stack_pointer: hex_u8("SP:")?,
+        ppu_scanline: decimal_u16(ppu_scanline)?,
+        ppu_dot: decimal_u16(ppu_dot)?,
+        cycles: register("CYC:")?.parse().map_err(|error| format!("CYC: {error}"))?,
+        disassembly: String::from(disassembly.trim_end()),
+        text: String::from(line),
+    })
+}
+
+/// Parse a whole nestest log, skipping its empty lines.
+pub fn parse_log(log: &str) -> Result<Vec<NestestLine>, String> {
+    log.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| parse_line(line).map_err(|error| format!("Line {}: {error}", index + 1)))
+        .collect()
+}
+
+#[test]
+fn test_parse_sample_log() {
+    let lines = parse_log(include_str!("../data/nestest-sample.log")).unwrap();
+    assert_eq!(lines.len(), 12);
+
+    assert_eq!(
+        lines[0],
+        NestestLine {
+            program_counter: 0xC000,
+            opcode: 0x4C,
+            accumulator: 0x00,
+            register_x: 0x00,
+            register_y: 0x00,
+            status: 0x24,
+            stack_pointer: 0xFD,
+            ppu_scanline: 0,
+            ppu_dot: 21,
+            cycles: 7,
+            disassembly: String::from("C000  4C F5 C5  JMP $C5F5"),
+            text: String::from(
+                "C000  4C F5 C5  JMP $C5F5                       A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7"
+            ),
+        }
+    );
+
+    assert_eq!(lines[2].program_counter, 0xC5F7);
+    assert_eq!(lines[2].status, 0x26);
+    assert_eq!(lines[2].cycles, 12);
+    assert_eq!((lines[9].ppu_scanline, lines[9].ppu_dot), (0, 102));
+}
+
+#[test]
+fn test_parse_line_with_indexed_disassembly() {
+    let line = parse_line(
+        "E1E4  BC FF 05  LDY $05FF,X @ 0689 = BB         A:66 X:8A Y:00 P:26 SP:FB PPU:110,179 CYC:12563",
+    )
+    .unwrap();
+
+    assert_eq!(line.program_counter, 0xE1E4);
+    assert_eq!(line.opcode, 0xBC);
+    assert_eq!(line.accumulator, 0x66);
+    assert_eq!(line.register_x, 0x8A);
+    assert_eq!(line.register_y, 0x00);
+    assert_eq!(line.status, 0x26);
+    assert_eq!(line.stack_pointer, 0xFB);
+    assert_eq!((line.ppu_scanline, line.ppu_dot), (110, 179));
+    assert_eq!(line.cycles, 12563);
+    assert_eq!(line.disassembly, "E1E4  BC FF 05  LDY $05FF,X @ 0689 = BB");
+}
+
+#[test]
+fn test_parse_line_missing_registers() {
+    assert!(parse_line("C000  4C F5 C5  JMP $C5F5").is_err());
+    assert!(parse_line("C000  4C F5 C5  JMP $C5F5  A:00 X:00 Y:00 P:24 SP:FD").is_err());
+    assert!(parse_line("").is_err());
+}
@@ -0,0 +1,43 @@
+//! Checks a program can be assembled and run from outside the crate with the cartridges of the
+//! `test-utils` feature.
+
+use tinfo::cartridge::testing::{FlatAccess, FlatCartridge};
+use tinfo::cpu::{Cpu, CpuStatusFlags};
+
+#[test]
+fn test_run_program() {
+    let program = vec![
+        // LDA #$03, STA $10
+        0xA9, 0x03, 0x85, 0x10,
+
+        // LDA #$00
+        0xA9, 0x00,
+
+        // CLC, ADC #$05
+        0x18, 0x69, 0x05,
+
+        // DEC $10, BNE $C106
+        0xC6, 0x10, 0xD0, 0xF9,
+
+        // STA $C000
+        0x8D, 0x00, 0xC0,
+    ];
+
+    let cartridge = FlatCartridge::new(program).with_base_address(0xC100).with_reset_vector(0xC100);
+    let accesses = cartridge.accesses();
+
+    let mut cpu = Cpu::from_cartridge(cartridge).unwrap();
+    assert_eq!(cpu.program_counter(), 0xC100);
+
+    // The loop adds five three times before the store
+    let mut snapshot = cpu.step().unwrap();
+    while snapshot.instruction_data.assembly != "STA $C000 = EA" {
+        snapshot = cpu.step().unwrap();
+    }
+
+    assert_eq!(snapshot.accumulator, 0x0F);
+    assert!(snapshot.status.contains(CpuStatusFlags::Zero));
+
+    // The stored value reaches the cartridge
+    assert_eq!(accesses.borrow().last(), Some(&FlatAccess::Write(0xC000, 0x0F)));
+}
@@ -0,0 +1,97 @@
+//! Compares the CPU against the golden log of the `nestest.nes` ROM run in automation mode.
+
+mod common;
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::path::Path;
+
+use common::{parse_log, NestestLine};
+use tinfo::cpu::{Cpu, CpuSnapshot};
+use tinfo::rom::ines::InesFile;
+
+/// The number of lines of the log the CPU runs before hitting an unsupported opcode.
+///
+/// Bump it as opcodes get implemented, until the whole log is supported.
+const SUPPORTED_LINES: usize = 72;
+
+/// Tell if the PPU columns of the log are compared, which needs the PPU to run its dots in step with
+/// the cycles of the CPU.
+const COMPARE_PPU_COLUMNS: bool = true;
+
+/// The number of matching lines shown before the first divergence.
+const CONTEXT_LINES: usize = 5;
+
+/// Load the nestest ROM and its log, shipped along the crate.
+///
+/// # Panics
+/// If `nestest.nes` or `nestest.log` are missing, so the comparison can't pass without running.
+fn load_nestest() -> (Cpu, Vec<NestestLine>) {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR"));
+
+    let mut rom_file = File::open(directory.join("nestest.nes")).expect("nestest.nes is missing");
+    let log = std::fs::read_to_string(directory.join("nestest.log")).expect("nestest.log is missing");
+
+    let cartridge = InesFile::from_read(&mut rom_file).unwrap();
+    let lines = parse_log(&log).unwrap();
+
+    let cpu = Cpu::new_with_program_counter(cartridge, 0xC000);
+
+    (cpu, lines)
+}
+
+/// Check if the snapshot taken before running an instruction matches a line of the log.
+fn matches_line(snapshot: &CpuSnapshot, line: &NestestLine) -> bool {
+    snapshot.program_counter == line.program_counter
+        && snapshot.opcode == line.opcode
+        && snapshot.accumulator == line.accumulator
+        && snapshot.register_x == line.register_x
+        && snapshot.register_y == line.register_y
+        && snapshot.status.bits() == line.status
+        && snapshot.stack_pointer == line.stack_pointer
+        && snapshot.cpu_cycles == line.cycles
+        && (!COMPARE_PPU_COLUMNS || (snapshot.ppu_scanline, snapshot.ppu_dot) == (line.ppu_scanline, line.ppu_dot))
+        && snapshot.to_string().split(" A:").next().map(str::trim_end) == Some(line.disassembly.as_str())
+}
+
+/// Run the CPU comparing every instruction against the lines of the log, failing with the context
+/// of the first divergence.
+fn compare_with_log(cpu: &mut Cpu, lines: &[NestestLine]) {
+    let mut context = VecDeque::with_capacity(CONTEXT_LINES);
+
+    for (index, line) in lines.iter().enumerate() {
+        let report = |actual: &str| {
+            let context = context.iter().map(|line: &&NestestLine| format!("  {}\n", line.text)).collect::<String>();
+
+            format!("Diverged from nestest on line {}:\n{context}- {}\n+ {actual}", index + 1, line.text)
+        };
+
+        let snapshot = match cpu.step() {
+            Ok(snapshot) => snapshot,
+            Err(error) => panic!("{}", report(&format!("Error: {error}"))),
+        };
+
+        assert!(matches_line(&snapshot, line), "{}", report(&snapshot.to_string()));
+
+        if context.len() == CONTEXT_LINES {
+            context.pop_front();
+        }
+
+        context.push_back(line);
+    }
+}
+
+#[test]
+fn test_nestest_supported_lines() {
+    let (mut cpu, lines) = load_nestest();
+
+    compare_with_log(&mut cpu, &lines[..SUPPORTED_LINES]);
+}
+
+#[test]
+#[ignore = "not every opcode run by nestest is supported yet"]
+fn test_nestest_full_log() {
+    let (mut cpu, lines) = load_nestest();
+
+    compare_with_log(&mut cpu, &lines);
+}
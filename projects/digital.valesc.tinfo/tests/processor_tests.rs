@@ -0,0 +1,291 @@
+//! Runs the single instruction test vectors of the `ProcessorTests` suite, comparing the registers,
+//! the memory and the bus accesses after every instruction.
+//!
+//! The suite is not bundled, point `TINFO_PROCESSOR_TESTS_DIR` to the directory holding its JSON
+//! files, one per opcode like `a9.json`, to run it.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use serde::Deserialize;
+use tinfo::bus::BusObserver;
+use tinfo::cartridge::{Cartridge, CartridgeError};
+use tinfo::cpu::{CpuBuilder, CpuStatusFlags, Mnemonic};
+use tinfo::disasm::disassemble_slice;
+
+/// The environment variable with the directory of the suite.
+const DIRECTORY_VARIABLE: &str = "TINFO_PROCESSOR_TESTS_DIR";
+
+/// The flags of the status register that only exist on its pushed copies.
+const PUSHED_ONLY_FLAGS: u8 = 0x30;
+
+/// The state of the machine before or after running an instruction.
+#[derive(Debug, Deserialize)]
+struct MachineState {
+    /// The program counter register.
+    pc: u16,
+
+    /// The stack pointer register.
+    s: u8,
+
+    /// The accumulator register.
+    a: u8,
+
+    /// The X index register.
+    x: u8,
+
+    /// The Y index register.
+    y: u8,
+
+    /// The raw value of the status register.
+    p: u8,
+
+    /// The addresses of the memory set by the test and their values.
+    ram: Vec<(u16, u8)>,
+}
+
+/// A test vector, running a single instruction.
+#[derive(Debug, Deserialize)]
+struct TestCase {
+    /// The name of the test, made of the bytes of the instruction.
+    name: String,
+
+    /// The state before running the instruction.
+    initial: MachineState,
+
+    /// The state after running the instruction.
+    #[serde(rename = "final")]
+    final_state: MachineState,
+
+    /// The access done to the bus on every cycle, along with its kind, `read` or `write`.
+    cycles: Vec<(u16, u8, String)>,
+}
+
+/// A cartridge mapping the whole address space to a flat memory of 64 KiB, shared with the test.
+struct FlatMemory {
+    /// The memory, indexed by address.
+    memory: Rc<RefCell<Vec<u8>>>,
+}
+
+impl Cartridge for FlatMemory {
+    unsafe fn read(&self, address: u16) -> Result<u8, CartridgeError> {
+        Ok(self.memory.borrow()[address as usize])
+    }
+
+    unsafe fn write(&mut self, address: u16, value: u8) -> Result<(), CartridgeError> {
+        self.memory.borrow_mut()[address as usize] = value;
+
+        Ok(())
+    }
+
+    fn maps_whole_address_space(&self) -> bool {
+        true
+    }
+}
+
+/// Records the accesses to the bus in the format of the test vectors.
+struct AccessRecorder {
+    /// The recorded accesses.
+    accesses: Rc<RefCell<Vec<(u16, u8, String)>>>,
+}
+
+impl BusObserver for AccessRecorder {
+    fn on_read(&mut self, address: u16, value: u8) {
+        self.accesses.borrow_mut().push((address, value, String::from("read")));
+    }
+
+    fn on_write(&mut self, address: u16, value: u8) {
+        self.accesses.borrow_mut().push((address, value, String::from("write")));
+    }
+}
+
+/// The results of running the test vectors of an opcode.
+#[derive(Debug, Default)]
+struct OpcodeReport {
+    /// The number of vectors run.
+    total: usize,
+
+    /// The number of vectors ending with the expected registers and memory.
+    state_passed: usize,
+
+    /// The number of vectors doing the expected bus accesses.
+    bus_passed: usize,
+
+    /// The description of the first failure, if any.
+    first_failure: Option<String>,
+}
+
+impl OpcodeReport {
+    /// Check if every vector passed.
+    fn passed(&self) -> bool {
+        self.state_passed == self.total && self.bus_passed == self.total
+    }
+}
+
+/// Run a test vector on a fresh CPU, returning the mismatches of the state and the bus accesses.
+fn run_test_case(test_case: &TestCase, memory: &Rc<RefCell<Vec<u8>>>) -> (Option<String>, Option<String>) {
+    let initial = &test_case.initial;
+    let builder = CpuBuilder::new()
+        .cartridge(FlatMemory { memory: memory.clone() })
+        .trace(false)
+        .program_counter(initial.pc)
+        .stack_pointer(initial.s)
+        .accumulator(initial.a)
+        .x(initial.x)
+        .y(initial.y)
+        .status(CpuStatusFlags::from_p_byte(initial.p & !PUSHED_ONLY_FLAGS));
+
+    let mut cpu = initial
+        .ram
+        .iter()
+        .fold(builder, |builder, &(address, value)| builder.ram(address, &[value]))
+        .build()
+        .unwrap();
+
+    let accesses = Rc::new(RefCell::new(vec![]));
+    cpu.bus_mut().set_observer(Some(Box::new(AccessRecorder { accesses: accesses.clone() })));
+
+    let result = cpu.step();
+
+    let expected = &test_case.final_state;
+    let state_mismatch = match result {
+        Err(error) => Some(format!("{}: {error}", test_case.name)),
+
+        Ok(_) => {
+            let actual = (
+                cpu.program_counter(),
+                cpu.stack_pointer(),
+                cpu.accumulator(),
+                cpu.x(),
+                cpu.y(),
+                cpu.status().bits() | PUSHED_ONLY_FLAGS,
+            );
+
+            let registers = (expected.pc, expected.s, expected.a, expected.x, expected.y, expected.p | PUSHED_ONLY_FLAGS);
+
+            let memory = memory.borrow();
+            let wrong_memory: Vec<_> = expected
+                .ram
+                .iter()
+                .filter(|&&(address, value)| memory[address as usize] != value)
+                .map(|&(address, value)| format!("${address:04X} = {:02X}, expected {value:02X}", memory[address as usize]))
+                .collect();
+
+            match actual == registers && wrong_memory.is_empty() {
+                true => None,
+                false => Some(format!(
+                    "{}: registers {actual:02X?}, expected {registers:02X?}, memory [{}]",
+                    test_case.name,
+                    wrong_memory.join(", ")
+                )),
+            }
+        }
+    };
+
+    let accesses = accesses.borrow();
+    let bus_mismatch = (*accesses != test_case.cycles)
+        .then(|| format!("{}: bus accesses {accesses:?}, expected {:?}", test_case.name, test_case.cycles));
+
+    // Leave the memory clean for the next vector
+    let mut memory = memory.borrow_mut();
+    for &(address, _) in test_case.initial.ram.iter().chain(&expected.ram) {
+        memory[address as usize] = 0;
+    }
+
+    for (address, _, _) in accesses.iter() {
+        memory[*address as usize] = 0;
+    }
+
+    (state_mismatch, bus_mismatch)
+}
+
+/// Run the given test vectors, all of them of the same opcode.
+fn run_test_cases(test_cases: &[TestCase]) -> OpcodeReport {
+    let memory = Rc::new(RefCell::new(vec![0; 0x10000]));
+    let mut report = OpcodeReport::default();
+
+    for test_case in test_cases {
+        let (state_mismatch, bus_mismatch) = run_test_case(test_case, &memory);
+
+        report.total += 1;
+        report.state_passed += state_mismatch.is_none() as usize;
+        report.bus_passed += bus_mismatch.is_none() as usize;
+
+        if report.first_failure.is_none() {
+            report.first_failure = state_mismatch.or(bus_mismatch);
+        }
+    }
+
+    report
+}
+
+/// Check if the CPU runs the given opcode, as the vectors of the unsupported and halting opcodes
+/// are skipped.
+fn is_supported(opcode: u8) -> bool {
+    let instruction = &disassemble_slice(&[opcode, 0x00, 0x00], 0x0000, 1)[0];
+
+    !matches!(instruction.mnemonic, None | Some(Mnemonic::Jam))
+}
+
+/// Get the JSON files of the suite found in the given directory, by opcode.
+fn find_test_files(directory: &Path) -> BTreeMap<u8, PathBuf> {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return BTreeMap::new();
+    };
+
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "json"))
+        .filter_map(|path| {
+            let opcode = u8::from_str_radix(path.file_stem()?.to_str()?, 16).ok()?;
+
+            Some((opcode, path))
+        })
+        .collect()
+}
+
+#[test]
+fn test_processor_tests_sample() {
+    let test_cases: Vec<TestCase> = serde_json::from_str(include_str!("data/processor-tests-sample.json")).unwrap();
+    let report = run_test_cases(&test_cases);
+
+    assert_eq!(report.total, 2);
+    assert!(report.passed(), "{:?}", report.first_failure);
+}
+
+#[test]
+fn test_processor_tests_suite() {
+    let Ok(directory) = std::env::var(DIRECTORY_VARIABLE) else {
+        eprintln!("Skipping the ProcessorTests suite as {DIRECTORY_VARIABLE} is not set");
+        return;
+    };
+
+    let test_files = find_test_files(Path::new(&directory));
+    assert!(!test_files.is_empty(), "No test vectors found in {directory}");
+
+    let mut failed_opcodes = vec![];
+
+    for (opcode, path) in test_files {
+        if !is_supported(opcode) {
+            println!("{opcode:02X}: skipped");
+            continue;
+        }
+
+        let test_cases: Vec<TestCase> = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let report = run_test_cases(&test_cases);
+
+        println!(
+            "{opcode:02X}: {}/{} state, {}/{} bus",
+            report.state_passed, report.total, report.bus_passed, report.total
+        );
+
+        if let Some(failure) = report.first_failure {
+            failed_opcodes.push(format!("{opcode:02X} ({failure})"));
+        }
+    }
+
+    assert!(failed_opcodes.is_empty(), "Failed opcodes:\n{}", failed_opcodes.join("\n"));
+}
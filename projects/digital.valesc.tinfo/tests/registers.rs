@@ -0,0 +1,84 @@
+//! Checks the registers of the CPU can be inspected and primed from outside the crate.
+
+use tinfo::cartridge::{Cartridge, CartridgeError};
+use tinfo::cpu::{Cpu, CpuBuilder, CpuStatusFlags, Mnemonic};
+
+/// A cartridge answering every read with a `NOP`.
+struct NopCartridge;
+
+impl Cartridge for NopCartridge {
+    unsafe fn read(&self, _address: u16) -> Result<u8, CartridgeError> {
+        Ok(0xEA)
+    }
+
+    unsafe fn write(&mut self, _address: u16, _value: u8) -> Result<(), CartridgeError> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_registers_round_trip() {
+    let mut cpu = Cpu::new_with_program_counter(Box::new(NopCartridge), 0x8000);
+
+    cpu.set_accumulator(0x12);
+    cpu.set_x(0x34);
+    cpu.set_y(0x56);
+    cpu.set_status(CpuStatusFlags::Carry | CpuStatusFlags::Stub);
+    cpu.set_stack_pointer(0x78);
+    cpu.set_program_counter(0x9ABC);
+
+    assert_eq!(cpu.accumulator(), 0x12);
+    assert_eq!(cpu.x(), 0x34);
+    assert_eq!(cpu.y(), 0x56);
+    assert_eq!(cpu.status(), CpuStatusFlags::Carry | CpuStatusFlags::Stub);
+    assert_eq!(cpu.stack_pointer(), 0x78);
+    assert_eq!(cpu.program_counter(), 0x9ABC);
+}
+
+#[test]
+fn test_primed_registers_are_used() {
+    let mut cpu = Cpu::new_with_program_counter(Box::new(NopCartridge), 0x8000);
+    cpu.set_program_counter(0xC000);
+
+    let snapshot = cpu.step().unwrap();
+    assert_eq!(snapshot.program_counter, 0xC000);
+    assert_eq!(snapshot.instruction_data.assembly, "NOP");
+    assert_eq!(snapshot.instruction_data.mnemonic, Mnemonic::Nop);
+
+    assert_eq!(cpu.program_counter(), 0xC001);
+}
+
+#[test]
+fn test_builder_primes_the_registers() {
+    let mut cpu = CpuBuilder::new()
+        .cartridge(NopCartridge)
+        .program_counter(0x0000)
+        .x(0x20)
+        .y(0x30)
+        .status(CpuStatusFlags::Carry | CpuStatusFlags::Stub)
+        .stack_pointer(0xF0)
+        // TXA, ADC $10, run from the internal RAM
+        .ram(0x0000, &[0x8A, 0x65, 0x10])
+        .ram(0x0010, &[0x05])
+        .build()
+        .unwrap();
+
+    let snapshot = cpu.step().unwrap();
+    assert_eq!(snapshot.program_counter, 0x0000);
+    assert_eq!(snapshot.instruction_data.assembly, "TXA");
+    assert_eq!(snapshot.accumulator, 0x00);
+    assert_eq!(snapshot.register_x, 0x20);
+    assert_eq!(snapshot.register_y, 0x30);
+    assert_eq!(snapshot.status, CpuStatusFlags::Carry | CpuStatusFlags::Stub);
+    assert_eq!(snapshot.stack_pointer, 0xF0);
+
+    let snapshot = cpu.step().unwrap();
+    assert_eq!(snapshot.program_counter, 0x0001);
+    assert_eq!(snapshot.instruction_data.assembly, "ADC $10 = 05");
+    assert_eq!(snapshot.accumulator, 0x20);
+
+    // The carry is added along the operand
+    let snapshot = cpu.step().unwrap();
+    assert_eq!(snapshot.program_counter, 0x0003);
+    assert_eq!(snapshot.accumulator, 0x26);
+}